@@ -0,0 +1,661 @@
+//! Read-side caching decorator.
+//!
+//! [`CachingRead`] wraps any [`UnauthenticatedTransportRead`] and memoizes results for
+//! a fixed time-to-live, so callers polling [`crate::get_payment_list`] do not hit the
+//! network on every call. Only successful reads are cached; errors always reach the
+//! caller and are retried on the next call.
+//!
+//! [`ReadWriteCache`] pairs a [`CachingRead`] with the writer for the same identity, so
+//! a process that publishes and then reads its own endpoints sees its writes at once.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Caching wrapper around an [`UnauthenticatedTransportRead`] implementation.
+///
+/// Clones share both the inner transport and the cache.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::{cache::CachingRead, get_payment_list, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn demo<R: UnauthenticatedTransportRead + Send + Sync>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let cached = CachingRead::new(reader, Duration::from_secs(30));
+/// let first = get_payment_list(&cached, pk).await?;
+/// // Served from memory until the TTL elapses or the payee is invalidated.
+/// let second = get_payment_list(&cached, pk).await?;
+/// assert_eq!(first, second);
+/// cached.invalidate(pk);
+/// # Ok(())
+/// # }
+/// ```
+pub struct CachingRead<R> {
+    inner: Arc<R>,
+    ttl: Duration,
+    state: Arc<Mutex<CacheState>>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    payments: HashMap<PublicKey, (Instant, SupportedPayments)>,
+    endpoints: HashMap<(PublicKey, MethodId), (Instant, Option<EndpointData>)>,
+    contacts: HashMap<PublicKey, (Instant, Vec<PublicKey>)>,
+}
+
+impl<R> Clone for CachingRead<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            ttl: self.ttl,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+impl<R> CachingRead<R> {
+    /// Wrap `inner`, caching each successful read for `ttl`.
+    pub fn new(inner: R, ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            ttl,
+            state: Arc::default(),
+        }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Configured time-to-live for cached entries.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Drop every cached entry for `payee` so the next read refetches it.
+    pub fn invalidate(&self, payee: &PublicKey) {
+        let mut state = self.state();
+        state.payments.remove(payee);
+        state.endpoints.retain(|(key, _), _| key != payee);
+        state.contacts.remove(payee);
+    }
+
+    /// Drop the whole cache.
+    pub fn clear(&self) {
+        *self.state() = CacheState::default();
+    }
+
+    fn state(&self) -> MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Apply a successful write by `owner` to the cache: `Some` stores the new payload,
+    /// `None` evicts the endpoint. A cached payment list is patched in place and keeps
+    /// its original expiry.
+    fn record_write(&self, owner: &PublicKey, method: &MethodId, data: Option<&EndpointData>) {
+        let mut state = self.state();
+        let key = (owner.clone(), method.clone());
+        match data {
+            Some(data) => {
+                state
+                    .endpoints
+                    .insert(key, (Instant::now(), Some(data.clone())));
+            }
+            None => {
+                state.endpoints.remove(&key);
+            }
+        }
+        if let Some((_, payments)) = state.payments.get_mut(owner) {
+            match data {
+                Some(data) => payments.entries.insert(method.clone(), data.clone()),
+                None => payments.entries.remove(method),
+            };
+        }
+    }
+
+    fn fresh<K, V>(&self, map: &HashMap<K, (Instant, V)>, key: &K) -> Option<V>
+    where
+        K: Eq + Hash,
+        V: Clone,
+    {
+        map.get(key)
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+impl<R> CachingRead<R>
+where
+    R: UnauthenticatedTransportRead,
+{
+    /// Fetch `payee`'s payment list and contacts now and cache both, so the reads a
+    /// contact screen makes next are served from memory.
+    ///
+    /// Entries already cached are refreshed. Each listed endpoint is also cached for
+    /// [`fetch_payment_endpoint`](UnauthenticatedTransportRead::fetch_payment_endpoint).
+    /// The two fetches run concurrently; if either fails, nothing is cached and the
+    /// error is returned.
+    pub async fn prewarm(&self, payee: &PublicKey) -> Result<()> {
+        let (payments, contacts) = futures::try_join!(
+            self.inner.fetch_supported_payments(payee),
+            self.inner.fetch_known_contacts(payee),
+        )?;
+        let now = Instant::now();
+        let mut state = self.state();
+        for (method, data) in payments.iter() {
+            state
+                .endpoints
+                .insert((payee.clone(), method.clone()), (now, Some(data.clone())));
+        }
+        state.payments.insert(payee.clone(), (now, payments));
+        state.contacts.insert(payee.clone(), (now, contacts));
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R> UnauthenticatedTransportRead for CachingRead<R>
+where
+    R: UnauthenticatedTransportRead + Send + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        if let Some(hit) = self.fresh(&self.state().payments, payee) {
+            return Ok(hit);
+        }
+        let payments = self.inner.fetch_supported_payments(payee).await?;
+        self.state()
+            .payments
+            .insert(payee.clone(), (Instant::now(), payments.clone()));
+        Ok(payments)
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        // Answer from a cached list when there is one, but do not cache the bare count.
+        if let Some(hit) = self.fresh(&self.state().payments, payee) {
+            return Ok(hit.len());
+        }
+        self.inner.count_supported_payments(payee).await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        // Only complete lists are cached, so a hit has no failures to report.
+        if let Some(hit) = self.fresh(&self.state().payments, payee) {
+            return Ok((hit, Vec::new()));
+        }
+        self.inner.fetch_supported_payments_lenient(payee).await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        let key = (payee.clone(), method.clone());
+        if let Some(hit) = self.fresh(&self.state().endpoints, &key) {
+            return Ok(hit);
+        }
+        let endpoint = self.inner.fetch_payment_endpoint(payee, method).await?;
+        self.state()
+            .endpoints
+            .insert(key, (Instant::now(), endpoint.clone()));
+        Ok(endpoint)
+    }
+
+    // Uncached: the cache keeps bodies only, and metadata from an older read could
+    // describe a payload that has since been replaced.
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.inner.fetch_payment_endpoint_full(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        // Answer from a cached body when there is one, but do not cache the bare flag.
+        let key = (payee.clone(), method.clone());
+        if let Some(hit) = self.fresh(&self.state().endpoints, &key) {
+            return Ok(hit.is_some());
+        }
+        self.inner
+            .fetch_payment_endpoint_exists(payee, method)
+            .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        // Raw files are not cached.
+        self.inner.fetch_raw(payee, relative_path).await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        if let Some(hit) = self.fresh(&self.state().contacts, owner) {
+            return Ok(hit);
+        }
+        let contacts = self.inner.fetch_known_contacts(owner).await?;
+        self.state()
+            .contacts
+            .insert(owner.clone(), (Instant::now(), contacts.clone()));
+        Ok(contacts)
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        // Reports are diagnostic, so always serve them from the source.
+        self.inner.fetch_known_contacts_with_report(owner).await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        // Pages are cheap to re-request and would make invalidation per-cursor.
+        self.inner
+            .fetch_known_contacts_page(owner, cursor, limit)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+/// A [`CachingRead`] kept in step with the writer for the same identity.
+///
+/// Reads go through the cache. A successful endpoint write through the wrapper stores
+/// the new payload in the cache, and a successful removal evicts it, so the writer's
+/// own reads never see stale data. Contact writes drop the cached contact list. Failed
+/// writes leave the cache alone. Writes made elsewhere, e.g. by another device, still
+/// only show up once the TTL elapses.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::cache::{CachingRead, ReadWriteCache};
+/// # use paykit_lib::{get_payment_endpoint, set_payment_endpoint, EndpointData, MethodId};
+/// # use paykit_lib::{AuthenticatedTransport, UnauthenticatedTransportRead};
+/// # async fn demo(
+/// #     reader: impl UnauthenticatedTransportRead + Send + Sync,
+/// #     session: impl AuthenticatedTransport + Send + Sync,
+/// # ) -> paykit_lib::Result<()> {
+/// let cache = ReadWriteCache::new(CachingRead::new(reader, Duration::from_secs(60)), session)?;
+/// let me = cache.owner().clone();
+/// set_payment_endpoint(&cache, MethodId::new("lightning")?, EndpointData("ln".into())).await?;
+/// // Served from the cache: the write above already put it there.
+/// let endpoint = get_payment_endpoint(&cache, &me, MethodId::new("lightning")?).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReadWriteCache<R, W> {
+    cache: CachingRead<R>,
+    writer: W,
+    owner: PublicKey,
+}
+
+impl<R, W> ReadWriteCache<R, W>
+where
+    W: AuthenticatedTransport,
+{
+    /// Pair `cache` with `writer`, whose identity comes from
+    /// [`AuthenticatedTransport::public_key`].
+    ///
+    /// Fails with that method's error, e.g. `PaykitError::Unimplemented`, when the
+    /// writer does not know its key.
+    pub fn new(cache: CachingRead<R>, writer: W) -> Result<Self> {
+        let owner = writer.public_key()?;
+        Ok(Self {
+            cache,
+            writer,
+            owner,
+        })
+    }
+}
+
+impl<R, W> ReadWriteCache<R, W> {
+    /// Access the read cache.
+    pub fn cache(&self) -> &CachingRead<R> {
+        &self.cache
+    }
+
+    /// Access the wrapped writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Identity the writer publishes as.
+    pub fn owner(&self) -> &PublicKey {
+        &self.owner
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R, W> UnauthenticatedTransportRead for ReadWriteCache<R, W>
+where
+    R: UnauthenticatedTransportRead + Send + Sync,
+    W: Send + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.cache.fetch_supported_payments(payee).await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.cache.count_supported_payments(payee).await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.cache.fetch_supported_payments_lenient(payee).await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.cache.fetch_payment_endpoint(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.cache.fetch_payment_endpoint_full(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.cache
+            .fetch_payment_endpoint_exists(payee, method)
+            .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.cache.fetch_raw(payee, relative_path).await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.cache.fetch_known_contacts(owner).await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.cache.fetch_known_contacts_with_report(owner).await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.cache
+            .fetch_known_contacts_page(owner, cursor, limit)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.cache.health_check().await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R, W> AuthenticatedTransport for ReadWriteCache<R, W>
+where
+    R: Send + Sync,
+    W: AuthenticatedTransport + Sync,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.writer.upsert_payment_endpoint(method, data).await?;
+        self.cache.record_write(&self.owner, method, Some(data));
+        Ok(())
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.writer
+            .upsert_payment_endpoint_with_type(method, data, content_type)
+            .await?;
+        self.cache.record_write(&self.owner, method, Some(data));
+        Ok(())
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.writer.remove_payment_endpoint(method).await?;
+        self.cache.record_write(&self.owner, method, None);
+        Ok(())
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        let etag = self
+            .writer
+            .upsert_payment_endpoint_if_match(method, data, expected_etag)
+            .await?;
+        self.cache.record_write(&self.owner, method, Some(data));
+        Ok(etag)
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.writer.list_own_endpoints().await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.writer.list_own_contacts().await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.writer.add_contact(contact).await?;
+        self.cache.state().contacts.remove(&self.owner);
+        Ok(())
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.writer.remove_contact(contact).await?;
+        self.cache.state().contacts.remove(&self.owner);
+        Ok(())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.writer.health_check().await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        Ok(self.owner.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{
+        get_known_contacts, get_payment_endpoint, get_payment_list, remove_payment_endpoint,
+        set_payment_endpoint,
+    };
+
+    fn seeded() -> (MockTransport, PublicKey, MethodId) {
+        let payee = random_public_key();
+        let mock = MockTransport::new(payee.clone());
+        let method = MethodId::new("lightning").unwrap();
+        mock.insert_endpoint(&payee, method.clone(), EndpointData("ln".into()));
+        (mock, payee, method)
+    }
+
+    #[tokio::test]
+    async fn reads_within_ttl_hit_cache() {
+        let (mock, payee, method) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+
+        for _ in 0..3 {
+            get_payment_list(&cached, &payee).await.unwrap();
+            get_payment_endpoint(&cached, &payee, &method)
+                .await
+                .unwrap();
+            get_known_contacts(&cached, &payee).await.unwrap();
+        }
+
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 1);
+        assert_eq!(mock.call_count("fetch_known_contacts"), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refetched() {
+        let (mock, payee, _) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::ZERO);
+
+        get_payment_list(&cached, &payee).await.unwrap();
+        get_payment_list(&cached, &payee).await.unwrap();
+
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn invalidate_forces_refetch() {
+        let (mock, payee, method) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+
+        get_payment_list(&cached, &payee).await.unwrap();
+        mock.insert_endpoint(&payee, method.clone(), EndpointData("updated".into()));
+        let stale = get_payment_list(&cached, &payee).await.unwrap();
+        assert_eq!(stale.entries[&method], EndpointData("ln".into()));
+
+        cached.invalidate(&payee);
+        let fresh = get_payment_list(&cached, &payee).await.unwrap();
+        assert_eq!(fresh.entries[&method], EndpointData("updated".into()));
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn prewarm_fills_the_cache() {
+        let (mock, payee, method) = seeded();
+        mock.insert_contact(&payee, &random_public_key());
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+
+        cached.prewarm(&payee).await.unwrap();
+        let list = get_payment_list(&cached, &payee).await.unwrap();
+        assert_eq!(list.get(&method), Some(&EndpointData("ln".into())));
+        let endpoint = get_payment_endpoint(&cached, &payee, &method)
+            .await
+            .unwrap();
+        assert_eq!(endpoint, Some(EndpointData("ln".into())));
+        assert_eq!(get_known_contacts(&cached, &payee).await.unwrap().len(), 1);
+
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 0);
+        assert_eq!(mock.call_count("fetch_known_contacts"), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_prewarm_caches_nothing() {
+        let (mock, payee, _) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+
+        mock.fail_contacts_of(&payee, "follows unavailable");
+        assert!(cached.prewarm(&payee).await.is_err());
+        mock.clear_failures();
+        get_payment_list(&cached, &payee).await.unwrap();
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn writes_through_the_wrapper_update_the_cache() {
+        let (mock, payee, method) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+        let cache = ReadWriteCache::new(cached, mock.clone()).unwrap();
+        get_payment_list(&cache, &payee).await.unwrap();
+
+        set_payment_endpoint(&cache, method.clone(), EndpointData("updated".into()))
+            .await
+            .unwrap();
+        let endpoint = get_payment_endpoint(&cache, &payee, &method).await.unwrap();
+        assert_eq!(endpoint, Some(EndpointData("updated".into())));
+        let list = get_payment_list(&cache, &payee).await.unwrap();
+        assert_eq!(list.get(&method), Some(&EndpointData("updated".into())));
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 0);
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+
+        remove_payment_endpoint(&cache, method.clone())
+            .await
+            .unwrap();
+        assert!(get_payment_list(&cache, &payee).await.unwrap().is_empty());
+        let endpoint = get_payment_endpoint(&cache, &payee, &method).await.unwrap();
+        assert_eq!(endpoint, None);
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_writes_leave_the_cache_alone() {
+        let (mock, payee, method) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+        let cache = ReadWriteCache::new(cached, mock.clone()).unwrap();
+        get_payment_endpoint(&cache, &payee, &method).await.unwrap();
+
+        mock.fail_next("offline");
+        assert!(
+            set_payment_endpoint(&cache, method.clone(), EndpointData("lost".into()))
+                .await
+                .is_err()
+        );
+        let endpoint = get_payment_endpoint(&cache, &payee, &method).await.unwrap();
+        assert_eq!(endpoint, Some(EndpointData("ln".into())));
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let (mock, payee, _) = seeded();
+        let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+
+        mock.fail_next("offline");
+        assert!(get_payment_list(&cached, &payee).await.is_err());
+        assert!(get_payment_list(&cached, &payee).await.is_ok());
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+}
@@ -0,0 +1,321 @@
+//! Concurrency-limiting decorator.
+//!
+//! [`ConcurrencyLimited`] caps how many operations run against the wrapped transport at
+//! once, so a burst of payee lookups queues locally instead of getting throttled by the
+//! homeserver. Calls wait for a permit from a `tokio::sync::Semaphore`, which can be
+//! shared between several decorators to bound them together.
+//!
+//! Requires the `tokio` feature.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Semaphore;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Decorator holding a semaphore permit for the duration of each transport operation.
+///
+/// Clones share the semaphore, so cloning the decorator (or building several with
+/// [`Self::with_semaphore`]) bounds their combined concurrency.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::concurrency::ConcurrencyLimited;
+/// # use paykit_lib::{get_payment_list, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo<R: UnauthenticatedTransportRead + Sync>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let reader = ConcurrencyLimited::new(reader, 4);
+/// let payments = get_payment_list(&reader, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConcurrencyLimited<T> {
+    inner: T,
+    semaphore: Arc<Semaphore>,
+}
+
+impl<T> ConcurrencyLimited<T> {
+    /// Wrap `inner`, allowing at most `max_permits` operations in flight (clamped to at
+    /// least one).
+    pub fn new(inner: T, max_permits: usize) -> Self {
+        Self::with_semaphore(inner, Arc::new(Semaphore::new(max_permits.max(1))))
+    }
+
+    /// Wrap `inner`, drawing permits from an existing `semaphore` shared with other
+    /// callers.
+    pub fn with_semaphore(inner: T, semaphore: Arc<Semaphore>) -> Self {
+        Self { inner, semaphore }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Semaphore gating the wrapped transport.
+    pub fn semaphore(&self) -> &Arc<Semaphore> {
+        &self.semaphore
+    }
+
+    async fn run<V>(&self, op: &'static str, fut: impl Future<Output = Result<V>>) -> Result<V> {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| PaykitError::Transport(format!("{op}: concurrency limiter closed")))?;
+        fut.await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> UnauthenticatedTransportRead for ConcurrencyLimited<T>
+where
+    T: UnauthenticatedTransportRead + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.run(
+            "fetch_supported_payments",
+            self.inner.fetch_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.run(
+            "count_supported_payments",
+            self.inner.count_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.run(
+            "fetch_supported_payments_lenient",
+            self.inner.fetch_supported_payments_lenient(payee),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.run(
+            "fetch_payment_endpoint",
+            self.inner.fetch_payment_endpoint(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.run(
+            "fetch_payment_endpoint_full",
+            self.inner.fetch_payment_endpoint_full(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.run(
+            "fetch_payment_endpoint_exists",
+            self.inner.fetch_payment_endpoint_exists(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.run("fetch_raw", self.inner.fetch_raw(payee, relative_path))
+            .await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.run(
+            "fetch_known_contacts",
+            self.inner.fetch_known_contacts(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.run(
+            "fetch_known_contacts_with_report",
+            self.inner.fetch_known_contacts_with_report(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.run(
+            "fetch_known_contacts_page",
+            self.inner.fetch_known_contacts_page(owner, cursor, limit),
+        )
+        .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> AuthenticatedTransport for ConcurrencyLimited<T>
+where
+    T: AuthenticatedTransport + Sync,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint",
+            self.inner.upsert_payment_endpoint(method, data),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint_with_type",
+            self.inner
+                .upsert_payment_endpoint_with_type(method, data, content_type),
+        )
+        .await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.run(
+            "remove_payment_endpoint",
+            self.inner.remove_payment_endpoint(method),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.run(
+            "upsert_payment_endpoint_if_match",
+            self.inner
+                .upsert_payment_endpoint_if_match(method, data, expected_etag),
+        )
+        .await
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.run("list_own_endpoints", self.inner.list_own_endpoints())
+            .await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.run("list_own_contacts", self.inner.list_own_contacts())
+            .await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("add_contact", self.inner.add_contact(contact))
+            .await
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("remove_contact", self.inner.remove_contact(contact))
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::get_payment_endpoint;
+    use crate::mock::{random_public_key, MockTransport};
+
+    #[tokio::test]
+    async fn in_flight_calls_never_exceed_permits() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.set_latency(Duration::from_millis(20));
+        let limited = ConcurrencyLimited::new(mock.clone(), 3);
+        let method = MethodId::new("lightning").unwrap();
+
+        let tasks: Vec<_> = (0..12)
+            .map(|_| {
+                let limited = limited.clone();
+                let owner = owner.clone();
+                let method = method.clone();
+                tokio::spawn(async move { get_payment_endpoint(&limited, &owner, &method).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap(), None);
+        }
+
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 12);
+        assert_eq!(mock.peak_in_flight(), 3);
+    }
+
+    #[tokio::test]
+    async fn shared_semaphore_bounds_every_decorator() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.set_latency(Duration::from_millis(20));
+        let semaphore = Arc::new(Semaphore::new(1));
+        let reader = ConcurrencyLimited::with_semaphore(mock.clone(), semaphore.clone());
+        let writer = ConcurrencyLimited::with_semaphore(mock.clone(), semaphore);
+
+        let method = MethodId::new("lightning").unwrap();
+        let data = EndpointData("ln".into());
+        let (read, write) = tokio::join!(
+            reader.fetch_payment_endpoint(&owner, &method),
+            writer.upsert_payment_endpoint(&method, &data),
+        );
+        read.unwrap();
+        write.unwrap();
+
+        assert_eq!(mock.peak_in_flight(), 1);
+    }
+}
@@ -0,0 +1,325 @@
+//! Stale-while-revalidate cache for known-contacts lookups.
+//!
+//! Contact lists change slowly, so [`ContactCache`] answers
+//! [`UnauthenticatedTransportRead::fetch_known_contacts`] from memory. Once an entry is
+//! older than its soft TTL it is still served immediately, and a background task
+//! refetches it for the next caller. Every other read passes straight through.
+//!
+//! Requires the `tokio` feature for the background refresh.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::transport::traits::UnauthenticatedTransportRead;
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Contact-list cache around an [`UnauthenticatedTransportRead`] implementation.
+///
+/// The first lookup for an owner fetches inline. Later lookups return the cached list;
+/// when it is older than `soft_ttl`, at most one refresh per owner is spawned on the
+/// Tokio runtime. A failed refresh keeps the stale list, and the next lookup tries again.
+///
+/// Clones share both the inner transport and the cache.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::contact_cache::ContactCache;
+/// # use paykit_lib::{get_known_contacts, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo<R: UnauthenticatedTransportRead + Send + Sync + 'static>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let contacts = ContactCache::new(reader, Duration::from_secs(60));
+/// let first = get_known_contacts(&contacts, pk).await?;
+/// // After adding a contact, skip the wait for the background refresh.
+/// let updated = contacts.force_refresh(pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ContactCache<R> {
+    inner: Arc<R>,
+    soft_ttl: Duration,
+    entries: Arc<Mutex<HashMap<PublicKey, CachedContacts>>>,
+}
+
+struct CachedContacts {
+    fetched_at: Instant,
+    contacts: Vec<PublicKey>,
+    refreshing: bool,
+}
+
+impl<R> Clone for ContactCache<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            soft_ttl: self.soft_ttl,
+            entries: Arc::clone(&self.entries),
+        }
+    }
+}
+
+impl<R> ContactCache<R> {
+    /// Wrap `inner`, revalidating cached contact lists older than `soft_ttl`.
+    pub fn new(inner: R, soft_ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            soft_ttl,
+            entries: Arc::default(),
+        }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Age after which a cached list is refreshed in the background.
+    pub fn soft_ttl(&self) -> Duration {
+        self.soft_ttl
+    }
+
+    /// Drop the cached list for `owner` so the next lookup fetches it inline.
+    pub fn invalidate(&self, owner: &PublicKey) {
+        lock(&self.entries).remove(owner);
+    }
+}
+
+impl<R> ContactCache<R>
+where
+    R: UnauthenticatedTransportRead,
+{
+    /// Fetch `owner`'s contacts now, replacing the cached list, and return them.
+    pub async fn force_refresh(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        let contacts = self.inner.fetch_known_contacts(owner).await?;
+        store(&self.entries, owner, contacts.clone());
+        Ok(contacts)
+    }
+}
+
+impl<R> ContactCache<R>
+where
+    R: UnauthenticatedTransportRead + Send + Sync + 'static,
+{
+    fn spawn_refresh(&self, owner: &PublicKey) {
+        let inner = Arc::clone(&self.inner);
+        let entries = Arc::clone(&self.entries);
+        let owner = owner.clone();
+        tokio::spawn(async move {
+            match inner.fetch_known_contacts(&owner).await {
+                Ok(contacts) => store(&entries, &owner, contacts),
+                Err(_) => {
+                    if let Some(entry) = lock(&entries).get_mut(&owner) {
+                        entry.refreshing = false;
+                    }
+                }
+            }
+        });
+    }
+}
+
+fn lock(
+    entries: &Mutex<HashMap<PublicKey, CachedContacts>>,
+) -> MutexGuard<'_, HashMap<PublicKey, CachedContacts>> {
+    entries.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn store(
+    entries: &Mutex<HashMap<PublicKey, CachedContacts>>,
+    owner: &PublicKey,
+    contacts: Vec<PublicKey>,
+) {
+    lock(entries).insert(
+        owner.clone(),
+        CachedContacts {
+            fetched_at: Instant::now(),
+            contacts,
+            refreshing: false,
+        },
+    );
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R> UnauthenticatedTransportRead for ContactCache<R>
+where
+    R: UnauthenticatedTransportRead + Send + Sync + 'static,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.inner.fetch_supported_payments(payee).await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.inner.count_supported_payments(payee).await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.inner.fetch_supported_payments_lenient(payee).await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_payment_endpoint(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.inner.fetch_payment_endpoint_full(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.inner
+            .fetch_payment_endpoint_exists(payee, method)
+            .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_raw(payee, relative_path).await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        let (cached, refresh) = {
+            let mut entries = lock(&self.entries);
+            match entries.get_mut(owner) {
+                Some(entry) => {
+                    let stale = entry.fetched_at.elapsed() >= self.soft_ttl;
+                    let refresh = stale && !entry.refreshing;
+                    entry.refreshing |= refresh;
+                    (Some(entry.contacts.clone()), refresh)
+                }
+                None => (None, false),
+            }
+        };
+        match cached {
+            Some(contacts) => {
+                if refresh {
+                    self.spawn_refresh(owner);
+                }
+                Ok(contacts)
+            }
+            None => self.force_refresh(owner).await,
+        }
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        // Reports are diagnostic, so always serve them from the source.
+        self.inner.fetch_known_contacts_with_report(owner).await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.inner
+            .fetch_known_contacts_page(owner, cursor, limit)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use super::*;
+    use crate::get_known_contacts;
+    use crate::mock::{random_public_key, MockTransport};
+
+    #[tokio::test]
+    async fn serves_stale_contacts_while_revalidating() {
+        let owner = random_public_key();
+        let first = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_contact(&owner, &first);
+        let cache = ContactCache::new(mock.clone(), Duration::from_millis(30));
+        let stale = slice::from_ref(&first);
+
+        assert_eq!(get_known_contacts(&cache, &owner).await.unwrap(), stale);
+        assert_eq!(get_known_contacts(&cache, &owner).await.unwrap(), stale);
+        assert_eq!(mock.call_count("fetch_known_contacts"), 1);
+
+        let second = random_public_key();
+        mock.insert_contact(&owner, &second);
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        // Answered from memory while a single refresh runs in the background.
+        assert_eq!(get_known_contacts(&cache, &owner).await.unwrap(), stale);
+        assert_eq!(get_known_contacts(&cache, &owner).await.unwrap(), stale);
+
+        let mut expected = vec![first.clone(), second];
+        expected.sort_by_key(ToString::to_string);
+        let mut refreshed = false;
+        for _ in 0..100 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            if get_known_contacts(&cache, &owner).await.unwrap() == expected {
+                refreshed = true;
+                break;
+            }
+        }
+        assert!(refreshed, "background refresh never landed");
+        assert_eq!(mock.call_count("fetch_known_contacts"), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_refresh_keeps_stale_contacts() {
+        let owner = random_public_key();
+        let contact = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_contact(&owner, &contact);
+        let cache = ContactCache::new(mock.clone(), Duration::from_millis(10));
+        get_known_contacts(&cache, &owner).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        mock.fail_next("homeserver down");
+        let cached = get_known_contacts(&cache, &owner).await.unwrap();
+        assert_eq!(cached, slice::from_ref(&contact));
+
+        // Once the failed refresh has run, the next lookup schedules another.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        get_known_contacts(&cache, &owner).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(mock.call_count("fetch_known_contacts"), 3);
+    }
+
+    #[tokio::test]
+    async fn force_refresh_bypasses_the_cache() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let cache = ContactCache::new(mock.clone(), Duration::from_secs(60));
+        assert!(get_known_contacts(&cache, &owner).await.unwrap().is_empty());
+
+        let contact = random_public_key();
+        mock.insert_contact(&owner, &contact);
+        let refreshed = cache.force_refresh(&owner).await.unwrap();
+        assert_eq!(refreshed, slice::from_ref(&contact));
+        assert_eq!(get_known_contacts(&cache, &owner).await.unwrap(), refreshed);
+        assert_eq!(mock.call_count("fetch_known_contacts"), 2);
+    }
+}
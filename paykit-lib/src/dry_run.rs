@@ -0,0 +1,330 @@
+//! Write previews for scripted migrations.
+//!
+//! [`DryRunTransport`] wraps an [`AuthenticatedTransport`] and records every write it
+//! would have made instead of making it. Run a migration against the wrapper, print
+//! [`DryRunTransport::operations`], and only then run it for real.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// A write captured by [`DryRunTransport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlannedOp {
+    /// `upsert_payment_endpoint(method, data)`.
+    Upsert {
+        /// Target method.
+        method: MethodId,
+        /// Payload that would have been written.
+        data: EndpointData,
+    },
+    /// `upsert_payment_endpoint_if_match(method, data, expected_etag)`.
+    ///
+    /// The ETag is recorded but not checked, so a conflicting write still shows up here.
+    ConditionalUpsert {
+        /// Target method.
+        method: MethodId,
+        /// Payload that would have been written.
+        data: EndpointData,
+        /// ETag the caller expected the endpoint to have.
+        expected_etag: Option<String>,
+    },
+    /// `remove_payment_endpoint(method)`.
+    Remove {
+        /// Method that would have been removed.
+        method: MethodId,
+    },
+    /// `add_contact(contact)`.
+    AddContact(PublicKey),
+    /// `remove_contact(contact)`.
+    RemoveContact(PublicKey),
+}
+
+impl fmt::Display for PlannedOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlannedOp::Upsert { method, .. } => write!(f, "upsert {method}"),
+            PlannedOp::ConditionalUpsert { method, .. } => {
+                write!(f, "conditional upsert {method}")
+            }
+            PlannedOp::Remove { method } => write!(f, "remove {method}"),
+            PlannedOp::AddContact(contact) => write!(f, "add contact {contact}"),
+            PlannedOp::RemoveContact(contact) => write!(f, "remove contact {contact}"),
+        }
+    }
+}
+
+/// Authenticated transport that logs writes instead of sending them.
+///
+/// Every write returns `Ok` without reaching the inner transport; conditional
+/// upserts return an empty ETag. Reads, including `list_own_endpoints`, go to the
+/// inner transport, so helpers such as [`crate::set_payment_list`] plan against the
+/// live state. Clones share the log.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::dry_run::DryRunTransport;
+/// # use paykit_lib::{set_payment_list, AuthenticatedTransport, SupportedPayments};
+/// # async fn preview(client: impl AuthenticatedTransport + Sync, desired: SupportedPayments) -> paykit_lib::Result<()> {
+/// let dry_run = DryRunTransport::new(client);
+/// set_payment_list(&dry_run, desired).await?;
+/// for op in dry_run.operations() {
+///     println!("would {op}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct DryRunTransport<T> {
+    inner: T,
+    log: Arc<Mutex<Vec<PlannedOp>>>,
+}
+
+impl<T> DryRunTransport<T> {
+    /// Record writes meant for `inner`.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            log: Arc::default(),
+        }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Writes recorded so far, in call order.
+    pub fn operations(&self) -> Vec<PlannedOp> {
+        self.log().clone()
+    }
+
+    /// Forget the recorded writes.
+    pub fn clear(&self) {
+        self.log().clear();
+    }
+
+    fn record(&self, op: PlannedOp) -> Result<()> {
+        self.log().push(op);
+        Ok(())
+    }
+
+    fn log(&self) -> MutexGuard<'_, Vec<PlannedOp>> {
+        self.log.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> AuthenticatedTransport for DryRunTransport<T>
+where
+    T: AuthenticatedTransport + Sync,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.record(PlannedOp::Upsert {
+            method: method.clone(),
+            data: data.clone(),
+        })
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        _content_type: &str,
+    ) -> Result<()> {
+        // The planned write is the same; only its metadata differs.
+        self.upsert_payment_endpoint(method, data).await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.record(PlannedOp::Remove {
+            method: method.clone(),
+        })
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.record(PlannedOp::ConditionalUpsert {
+            method: method.clone(),
+            data: data.clone(),
+            expected_etag,
+        })?;
+        Ok(String::new())
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.inner.list_own_endpoints().await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.inner.list_own_contacts().await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.record(PlannedOp::AddContact(contact.clone()))
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.record(PlannedOp::RemoveContact(contact.clone()))
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> UnauthenticatedTransportRead for DryRunTransport<T>
+where
+    T: UnauthenticatedTransportRead + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.inner.fetch_supported_payments(payee).await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.inner.count_supported_payments(payee).await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.inner.fetch_supported_payments_lenient(payee).await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_payment_endpoint(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.inner.fetch_payment_endpoint_full(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.inner
+            .fetch_payment_endpoint_exists(payee, method)
+            .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_raw(payee, relative_path).await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.inner.fetch_known_contacts(owner).await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.inner.fetch_known_contacts_with_report(owner).await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.inner
+            .fetch_known_contacts_page(owner, cursor, limit)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{add_contact, remove_payment_endpoint, set_payment_endpoint, set_payment_list};
+
+    #[tokio::test]
+    async fn writes_are_logged_in_order_and_not_sent() {
+        let owner = random_public_key();
+        let contact = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let dry_run = DryRunTransport::new(mock.clone());
+        let lightning = MethodId::new("lightning").unwrap();
+
+        set_payment_endpoint(&dry_run, &lightning, EndpointData("ln".into()))
+            .await
+            .unwrap();
+        add_contact(&dry_run, &contact).await.unwrap();
+        remove_payment_endpoint(&dry_run, &lightning).await.unwrap();
+
+        assert_eq!(
+            dry_run.operations(),
+            vec![
+                PlannedOp::Upsert {
+                    method: lightning.clone(),
+                    data: EndpointData("ln".into()),
+                },
+                PlannedOp::AddContact(contact),
+                PlannedOp::Remove { method: lightning },
+            ]
+        );
+        assert_eq!(mock.total_calls(), 0);
+        assert!(mock.list_own_endpoints().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_convergence_plans_against_live_state() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let stale = MethodId::new("onchain").unwrap();
+        mock.insert_endpoint(&owner, stale.clone(), EndpointData("bc1".into()));
+        let dry_run = DryRunTransport::new(mock.clone());
+
+        set_payment_list(&dry_run, SupportedPayments::default())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            dry_run.operations(),
+            vec![PlannedOp::Remove {
+                method: stale.clone()
+            }]
+        );
+        assert!(mock.list_own_endpoints().await.unwrap().contains(&stale));
+        dry_run.clear();
+        assert!(dry_run.operations().is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+//! Reader composition with ordered fallback.
+//!
+//! [`FallbackRead`] lets a deployment read from a local cache or mirror first and only
+//! reach the live homeserver when the earlier sources are unavailable.
+
+use std::future::Future;
+
+use async_trait::async_trait;
+
+use crate::transport::traits::UnauthenticatedTransportRead;
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Reader that tries each wrapped reader in order.
+///
+/// Only network-level failures (`PaykitError::Network`, `PaykitError::Timeout`, and a
+/// transient `PaykitError::TransportSource`) move on to the next reader. Any other
+/// outcome is answered by the reader that produced it, including empty lists, `None`
+/// endpoints, and local failures such as `PaykitError::Transport`. When every reader
+/// fails, the last network error is returned.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::fallback::FallbackRead;
+/// # use paykit_lib::{get_payment_list, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(
+/// #     mirror: impl UnauthenticatedTransportRead + Send + Sync + 'static,
+/// #     live: impl UnauthenticatedTransportRead + Send + Sync + 'static,
+/// #     pk: &PublicKey,
+/// # ) -> paykit_lib::Result<()> {
+/// let reader = FallbackRead::new(vec![Box::new(mirror), Box::new(live)]);
+/// let payments = get_payment_list(&reader, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FallbackRead {
+    readers: Vec<Box<dyn UnauthenticatedTransportRead + Send + Sync>>,
+}
+
+impl FallbackRead {
+    /// Try `readers` in the given order.
+    pub fn new(readers: Vec<Box<dyn UnauthenticatedTransportRead + Send + Sync>>) -> Self {
+        Self { readers }
+    }
+
+    /// Number of wrapped readers.
+    pub fn len(&self) -> usize {
+        self.readers.len()
+    }
+
+    /// Whether no readers are wrapped; every call then fails with a transport error.
+    pub fn is_empty(&self) -> bool {
+        self.readers.is_empty()
+    }
+
+    async fn run<'a, V, F, Fut>(&'a self, op: &'static str, call: F) -> Result<V>
+    where
+        F: Fn(&'a (dyn UnauthenticatedTransportRead + Send + Sync)) -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        let mut last = PaykitError::Transport(format!("{op}: no readers configured"));
+        for reader in &self.readers {
+            match call(reader.as_ref()).await {
+                Err(err) if falls_through(&err) => last = err,
+                other => return other,
+            }
+        }
+        Err(last)
+    }
+}
+
+/// Whether `err` is a network failure another reader might not hit.
+fn falls_through(err: &PaykitError) -> bool {
+    match err {
+        PaykitError::Network(_) | PaykitError::Timeout(_) => true,
+        PaykitError::TransportSource(_) => err.is_transient(),
+        _ => false,
+    }
+}
+
+impl From<Vec<Box<dyn UnauthenticatedTransportRead + Send + Sync>>> for FallbackRead {
+    fn from(readers: Vec<Box<dyn UnauthenticatedTransportRead + Send + Sync>>) -> Self {
+        Self::new(readers)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl UnauthenticatedTransportRead for FallbackRead {
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.run("fetch_supported_payments", |reader| {
+            reader.fetch_supported_payments(payee)
+        })
+        .await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.run("count_supported_payments", |reader| {
+            reader.count_supported_payments(payee)
+        })
+        .await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.run("fetch_supported_payments_lenient", |reader| {
+            reader.fetch_supported_payments_lenient(payee)
+        })
+        .await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.run("fetch_payment_endpoint", |reader| {
+            reader.fetch_payment_endpoint(payee, method)
+        })
+        .await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.run("fetch_payment_endpoint_full", |reader| {
+            reader.fetch_payment_endpoint_full(payee, method)
+        })
+        .await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.run("fetch_payment_endpoint_exists", |reader| {
+            reader.fetch_payment_endpoint_exists(payee, method)
+        })
+        .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.run("fetch_raw", |reader| reader.fetch_raw(payee, relative_path))
+            .await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.run("fetch_known_contacts", |reader| {
+            reader.fetch_known_contacts(owner)
+        })
+        .await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.run("fetch_known_contacts_with_report", |reader| {
+            reader.fetch_known_contacts_with_report(owner)
+        })
+        .await
+    }
+
+    // A cursor issued by one reader is handed unchanged to the next on failure, which
+    // only works when the readers share a cursor format (e.g. a mirror of the same
+    // homeserver layout).
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.run("fetch_known_contacts_page", |reader| {
+            reader.fetch_known_contacts_page(owner, cursor.clone(), limit)
+        })
+        .await
+    }
+
+    /// Healthy as long as one reader is.
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", |reader| reader.health_check())
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{get_known_contacts, get_payment_list};
+
+    #[tokio::test]
+    async fn falls_through_network_errors() {
+        let payee = random_public_key();
+        let mirror = MockTransport::new(payee.clone());
+        mirror.fail_next_network("mirror offline");
+        let live = MockTransport::new(payee.clone());
+        live.insert_endpoint(
+            &payee,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        );
+        let reader = FallbackRead::new(vec![Box::new(mirror.clone()), Box::new(live.clone())]);
+
+        let payments = get_payment_list(&reader, &payee).await.unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(mirror.call_count("fetch_supported_payments"), 1);
+        assert_eq!(live.call_count("fetch_supported_payments"), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn falls_through_timeouts() {
+        use crate::timeout::TimeoutTransport;
+        use std::time::Duration;
+
+        let payee = random_public_key();
+        let slow = MockTransport::new(payee.clone());
+        slow.set_latency(Duration::from_millis(50));
+        let live = MockTransport::new(payee.clone());
+        live.insert_endpoint(
+            &payee,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        );
+        let reader = FallbackRead::new(vec![
+            Box::new(TimeoutTransport::new(slow, Duration::from_millis(5))),
+            Box::new(live.clone()),
+        ]);
+
+        let payments = get_payment_list(&reader, &payee).await.unwrap();
+        assert_eq!(payments.len(), 1);
+        assert_eq!(live.call_count("fetch_supported_payments"), 1);
+    }
+
+    #[tokio::test]
+    async fn local_transport_errors_do_not_fall_through() {
+        let payee = random_public_key();
+        let mirror = MockTransport::new(payee.clone());
+        mirror.fail_next("unresolvable homeserver");
+        let live = MockTransport::new(payee.clone());
+        let reader = FallbackRead::new(vec![Box::new(mirror), Box::new(live.clone())]);
+
+        let err = get_payment_list(&reader, &payee).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)));
+        assert_eq!(live.total_calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn empty_results_are_authoritative() {
+        let owner = random_public_key();
+        let mirror = MockTransport::new(owner.clone());
+        let live = MockTransport::new(owner.clone());
+        live.insert_contact(&owner, &random_public_key());
+        let reader = FallbackRead::new(vec![Box::new(mirror), Box::new(live.clone())]);
+
+        assert!(get_known_contacts(&reader, &owner)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(get_payment_list(&reader, &owner).await.unwrap().is_empty());
+        assert_eq!(live.total_calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn returns_last_error_when_all_fail() {
+        let owner = random_public_key();
+        let first = MockTransport::new(owner.clone());
+        first.fail_next_network("first");
+        let second = MockTransport::new(owner.clone());
+        second.fail_next_network("second");
+        let reader = FallbackRead::new(vec![Box::new(first), Box::new(second)]);
+
+        let err = reader.fetch_supported_payments(&owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Network(msg) if msg == "second"));
+        assert!(FallbackRead::new(Vec::new())
+            .fetch_known_contacts(&owner)
+            .await
+            .is_err());
+    }
+}
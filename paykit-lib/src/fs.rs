@@ -0,0 +1,481 @@
+//! Filesystem-backed transport for offline development.
+//!
+//! [`FilesystemTransport`] stores every identity under `root/<pubkey>/`, using the same
+//! paths as a Pubky homeserver (`/pub/paykit.app/v0/{method}` and
+//! `/pub/pubky.app/follows/{contact}` by default). Bindings and demos can exercise the
+//! full Paykit flow against a plain directory, and the files can be inspected or
+//! edited by hand.
+//!
+//! File access goes through blocking `std::fs` calls, which is fine for development
+//! but should not share a runtime with latency-sensitive work. Requires the `fs`
+//! feature.
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::transport::paths::{
+    contact_key_from_path, method_id_from_path, validate_relative_path, PaykitPaths,
+};
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Transport reading and writing Paykit data in a local directory tree.
+///
+/// Writes go to the `owner` key given at construction; reads accept any payee.
+/// Missing files read as `Ok(None)` and missing directories as empty, mirroring the
+/// Pubky adapters, and deleting a missing file fails with a 404 `PaykitError::Server`
+/// like the homeserver does.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::fs::FilesystemTransport;
+/// # use paykit_lib::{get_payment_list, set_payment_endpoint, EndpointData, MethodId, PublicKey};
+/// # async fn demo(owner: PublicKey) -> paykit_lib::Result<()> {
+/// let transport = FilesystemTransport::new("./paykit-data", owner.clone());
+/// set_payment_endpoint(&transport, MethodId::new("lightning")?, EndpointData("ln".into())).await?;
+/// let payments = get_payment_list(&transport, &owner).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct FilesystemTransport {
+    root: PathBuf,
+    owner: PublicKey,
+    paths: PaykitPaths,
+    strict_empty: bool,
+}
+
+impl FilesystemTransport {
+    /// Store data under `root`, writing on behalf of `owner` with the default `v0`
+    /// layout.
+    pub fn new(root: impl Into<PathBuf>, owner: PublicKey) -> Self {
+        Self::new_with_paths(root, owner, PaykitPaths::default())
+    }
+
+    /// Like [`Self::new`], but reads and writes endpoints under `paths`.
+    pub fn new_with_paths(root: impl Into<PathBuf>, owner: PublicKey, paths: PaykitPaths) -> Self {
+        Self {
+            root: root.into(),
+            owner,
+            paths,
+            strict_empty: false,
+        }
+    }
+
+    /// Report an endpoint file that exists but is empty as `PaykitError::InvalidData`
+    /// instead of reading it as absent, to surface endpoints written blank. Off by
+    /// default.
+    pub fn with_strict_empty(mut self, enabled: bool) -> Self {
+        self.strict_empty = enabled;
+        self
+    }
+
+    /// Directory holding one subdirectory per identity.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Key that writes are attributed to.
+    pub fn owner(&self) -> &PublicKey {
+        &self.owner
+    }
+
+    /// Endpoint layout this transport uses.
+    pub fn paths(&self) -> &PaykitPaths {
+        &self.paths
+    }
+
+    fn endpoints_dir(&self, key: &PublicKey) -> PathBuf {
+        self.resolve(key, &self.paths.prefix())
+    }
+
+    fn endpoint_file(&self, key: &PublicKey, method: &MethodId) -> Result<PathBuf> {
+        // Re-check here: trait callers may bypass the helpers' validation, and an
+        // unchecked id could point outside `root`.
+        method.validate()?;
+        Ok(self.endpoints_dir(key).join(&method.0))
+    }
+
+    fn follows_dir(&self, key: &PublicKey) -> PathBuf {
+        self.resolve(key, self.paths.follows())
+    }
+
+    fn resolve(&self, key: &PublicKey, path: &str) -> PathBuf {
+        let mut resolved = self.root.join(key.to_string());
+        resolved.extend(path.split('/').filter(|segment| !segment.is_empty()));
+        resolved
+    }
+
+    fn payments_of(&self, key: &PublicKey) -> Result<SupportedPayments> {
+        let dir = self.endpoints_dir(key);
+        let mut entries = HashMap::new();
+        for name in file_names(&dir)? {
+            // Same rule as the Pubky listings: names that are not valid ids are skipped.
+            let Some(method) = method_id_from_path(&name) else {
+                continue;
+            };
+            if let Some(payload) = read_text(&dir.join(&name), self.strict_empty)? {
+                entries.insert(method, EndpointData(payload));
+            }
+        }
+        Ok(SupportedPayments { entries })
+    }
+}
+
+/// Reads a file, treating missing and empty files alike unless `strict_empty` is set.
+fn read_text(path: &Path, strict_empty: bool) -> Result<Option<String>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(io_error(err)),
+    };
+    if bytes.is_empty() {
+        if strict_empty {
+            return Err(PaykitError::InvalidData(format!(
+                "{}: empty endpoint body",
+                path.display()
+            )));
+        }
+        return Ok(None);
+    }
+    String::from_utf8(bytes)
+        .map(Some)
+        .map_err(|err| PaykitError::InvalidData(format!("{}: {err}", path.display())))
+}
+
+/// Names of the regular files in `dir`; a missing directory has none.
+fn file_names(dir: &Path) -> Result<Vec<String>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(io_error(err)),
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(io_error)?;
+        if !entry.file_type().map_err(io_error)?.is_file() {
+            continue;
+        }
+        if let Ok(name) = entry.file_name().into_string() {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+fn write_file(path: &Path, contents: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    std::fs::write(path, contents).map_err(io_error)
+}
+
+fn delete_file(path: &Path, what: &str) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(PaykitError::Server {
+            status: 404,
+            message: format!("delete {what}: {} not found", path.display()),
+        }),
+        Err(err) => Err(io_error(err)),
+    }
+}
+
+fn io_error(err: io::Error) -> PaykitError {
+    PaykitError::TransportSource(Box::new(err))
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl UnauthenticatedTransportRead for FilesystemTransport {
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.payments_of(payee)
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        let path = self.endpoint_file(payee, method)?;
+        Ok(read_text(&path, self.strict_empty)?.map(EndpointData))
+    }
+
+    // Files carry no media type, so only `last_modified` is filled in.
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        let path = self.endpoint_file(payee, method)?;
+        let Some(payload) = read_text(&path, self.strict_empty)? else {
+            return Ok(None);
+        };
+        let last_modified = std::fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        Ok(Some(FetchedEndpoint {
+            data: EndpointData(payload),
+            content_type: None,
+            last_modified,
+        }))
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        validate_relative_path(relative_path)?;
+        let path = self.resolve(payee, &self.paths.file(relative_path));
+        Ok(read_text(&path, self.strict_empty)?.map(EndpointData))
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        match std::fs::metadata(self.endpoint_file(payee, method)?) {
+            Ok(meta) => Ok(meta.is_file() && meta.len() > 0),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(io_error(err)),
+        }
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        let (contacts, _skipped) = self.fetch_known_contacts_with_report(owner).await?;
+        Ok(contacts)
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        let mut contacts = Vec::new();
+        let mut seen = HashSet::new();
+        let mut skipped = Vec::new();
+        for name in file_names(&self.follows_dir(owner))? {
+            match contact_key_from_path(&name) {
+                // File names spelling the same key differently yield it once.
+                Some(contact) if seen.insert(contact.clone()) => contacts.push(contact),
+                Some(_) => {}
+                None => skipped.push(name),
+            }
+        }
+        contacts.sort_by_cached_key(ToString::to_string);
+        skipped.sort();
+        Ok((contacts, skipped))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthenticatedTransport for FilesystemTransport {
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        write_file(&self.endpoint_file(&self.owner, method)?, data.0.as_bytes())
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        delete_file(&self.endpoint_file(&self.owner, method)?, "endpoint")
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.payments_of(&self.owner)
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.fetch_known_contacts(&self.owner).await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        write_file(
+            &self.follows_dir(&self.owner).join(contact.to_string()),
+            &[],
+        )
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        delete_file(
+            &self.follows_dir(&self.owner).join(contact.to_string()),
+            "contact",
+        )
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        Ok(self.owner.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::random_public_key;
+    use crate::{
+        add_contact, get_known_contacts, get_payment_endpoint, get_payment_list,
+        remove_payment_endpoint, set_payment_endpoint,
+    };
+
+    /// Scratch directory removed when the test finishes.
+    struct Scratch(PathBuf);
+
+    impl Scratch {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("paykit-fs-{}", random_public_key()));
+            Self(dir)
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn endpoints_round_trip_through_files() {
+        let scratch = Scratch::new();
+        let owner = random_public_key();
+        let transport = FilesystemTransport::new(&scratch.0, owner.clone());
+        let lightning = MethodId::new("lightning").unwrap();
+        let onchain = MethodId::new("onchain").unwrap();
+
+        set_payment_endpoint(&transport, lightning.clone(), EndpointData("ln".into()))
+            .await
+            .unwrap();
+        set_payment_endpoint(&transport, onchain.clone(), EndpointData("bc1".into()))
+            .await
+            .unwrap();
+        let on_disk = scratch
+            .0
+            .join(owner.to_string())
+            .join("pub/paykit.app/v0/lightning");
+        assert_eq!(std::fs::read_to_string(on_disk).unwrap(), "ln");
+
+        let list = get_payment_list(&transport, &owner).await.unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.get(&onchain), Some(&EndpointData("bc1".into())));
+
+        remove_payment_endpoint(&transport, lightning.clone())
+            .await
+            .unwrap();
+        assert!(get_payment_endpoint(&transport, &owner, &lightning)
+            .await
+            .unwrap()
+            .is_none());
+        let err = remove_payment_endpoint(&transport, lightning)
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found(), "{err:?}");
+    }
+
+    #[cfg(feature = "integrity")]
+    #[tokio::test]
+    async fn checksum_sidecars_are_not_listed() {
+        use crate::integrity::set_payment_endpoint_with_checksum;
+
+        let scratch = Scratch::new();
+        let owner = random_public_key();
+        let transport = FilesystemTransport::new(&scratch.0, owner.clone());
+        set_payment_endpoint_with_checksum(
+            &transport,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        )
+        .await
+        .unwrap();
+
+        let list = get_payment_list(&transport, &owner).await.unwrap();
+        assert_eq!(list.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn missing_data_reads_as_empty() {
+        let scratch = Scratch::new();
+        let transport = FilesystemTransport::new(&scratch.0, random_public_key());
+        let stranger = random_public_key();
+
+        assert!(get_payment_list(&transport, &stranger)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(get_known_contacts(&transport, &stranger)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn empty_endpoint_files_are_absent_unless_strict() {
+        let scratch = Scratch::new();
+        let owner = random_public_key();
+        let lenient = FilesystemTransport::new(&scratch.0, owner.clone());
+        let method = MethodId::new("lightning").unwrap();
+        set_payment_endpoint(&lenient, method.clone(), EndpointData(String::new()))
+            .await
+            .unwrap();
+
+        assert!(get_payment_endpoint(&lenient, &owner, &method)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(get_payment_list(&lenient, &owner).await.unwrap().is_empty());
+
+        let strict = lenient.clone().with_strict_empty(true);
+        let err = get_payment_endpoint(&strict, &owner, &method)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::InvalidData(msg) if msg.ends_with("empty endpoint body")),
+            "{err:?}"
+        );
+        assert!(matches!(
+            get_payment_list(&strict, &owner).await,
+            Err(PaykitError::InvalidData(_))
+        ));
+        assert!(
+            get_payment_endpoint(&strict, &owner, &MethodId::new("onchain").unwrap())
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn contacts_are_marker_files() {
+        let scratch = Scratch::new();
+        let owner = random_public_key();
+        let contact = random_public_key();
+        let transport = FilesystemTransport::new(&scratch.0, owner.clone());
+
+        add_contact(&transport, &contact).await.unwrap();
+        assert_eq!(
+            get_known_contacts(&transport, &owner).await.unwrap(),
+            vec![contact]
+        );
+    }
+
+    #[tokio::test]
+    async fn unsafe_method_ids_cannot_escape_root() {
+        let scratch = Scratch::new();
+        let owner = random_public_key();
+        let transport = FilesystemTransport::new(&scratch.0, owner.clone());
+        let escape = MethodId("../../../../escaped".into());
+
+        let err = transport
+            .upsert_payment_endpoint(&escape, &EndpointData("x".into()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)), "{err:?}");
+        assert!(transport
+            .fetch_payment_endpoint(&owner, &escape)
+            .await
+            .is_err());
+        assert!(!scratch.0.exists());
+    }
+}
@@ -0,0 +1,220 @@
+//! Checksummed endpoint publishing.
+//!
+//! [`set_payment_endpoint_with_checksum`] stores the SHA-256 of a payload in a sidecar
+//! endpoint next to it (`lightning.sha256` for `lightning`), and
+//! [`get_payment_endpoint_verified`] checks a fetched payload against that digest, so a
+//! payer can detect a truncated or altered payload. Requires the `integrity` feature.
+//!
+//! The sidecar is stored as an ordinary endpoint file, but the Pubky, filesystem, and
+//! wasm transports leave ids ending in [`SIDECAR_SUFFIX`] out of their listings, so it
+//! never shows up in [`crate::get_payment_list`] as a method of its own.
+//! `PubkyAuthenticatedTransport` removes it together with its endpoint.
+
+use futures::future;
+use sha2::{Digest, Sha256};
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{map_transport_error, EndpointData, MethodId, PaykitError, PublicKey, Result};
+
+/// Suffix appended to a method id to name its checksum sidecar.
+pub const SIDECAR_SUFFIX: &str = ".sha256";
+
+/// An endpoint payload and whether its checksum was checked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CheckedEndpoint {
+    /// The payload as fetched.
+    pub data: EndpointData,
+    /// `true` when a sidecar digest was published and matched; `false` when the payee
+    /// published no sidecar.
+    pub verified: bool,
+}
+
+/// Method id of the sidecar holding the checksum for `method`.
+///
+/// Fails with `PaykitError::InvalidMethodId` when the suffixed id would exceed
+/// [`MethodId::MAX_LEN`].
+pub fn sidecar_method(method: &MethodId) -> Result<MethodId> {
+    MethodId::new(format!("{method}{SIDECAR_SUFFIX}"))
+}
+
+/// Lowercase hex SHA-256 of the payload as stored.
+pub fn sha256_hex(data: &EndpointData) -> String {
+    Sha256::digest(data.0.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Publishes `data` for `method` together with its SHA-256 sidecar.
+///
+/// The payload is written first, then the sidecar. If the second write fails, the
+/// payload is live without a fresh digest: readers then see a mismatch against an
+/// older sidecar, or an unverified payload if there was none. Retrying repairs it.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::integrity::set_payment_endpoint_with_checksum;
+/// # use paykit_lib::{AuthenticatedTransport, EndpointData, MethodId};
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// set_payment_endpoint_with_checksum(client, MethodId::new("lightning")?, EndpointData("ln".into()))
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_payment_endpoint_with_checksum<S, M>(
+    client: &S,
+    method: M,
+    data: EndpointData,
+) -> Result<()>
+where
+    S: AuthenticatedTransport + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    let sidecar = sidecar_method(&method)?;
+    let digest = EndpointData(sha256_hex(&data));
+
+    client
+        .upsert_payment_endpoint(&method, &data)
+        .await
+        .map_err(|err| map_transport_error("set_payment_endpoint_with_checksum", err))?;
+    client
+        .upsert_payment_endpoint(&sidecar, &digest)
+        .await
+        .map_err(|err| map_transport_error("set_payment_endpoint_with_checksum", err))
+}
+
+/// Fetches `method` for `payee` and checks it against the published SHA-256 sidecar.
+///
+/// # Semantics
+/// - Returns `Ok(None)` when the endpoint itself is absent, whether or not a sidecar
+///   exists.
+/// - Returns the payload with `verified: false` when no sidecar is published.
+/// - Fails with `PaykitError::InvalidData` when the sidecar does not match the payload.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::integrity::get_payment_endpoint_verified;
+/// # use paykit_lib::{MethodId, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// if let Some(endpoint) = get_payment_endpoint_verified(reader, pk, MethodId::new("lightning")?).await? {
+///     println!("verified: {}", endpoint.verified);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_payment_endpoint_verified<R, M>(
+    reader: &R,
+    payee: &PublicKey,
+    method: M,
+) -> Result<Option<CheckedEndpoint>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    let sidecar = sidecar_method(&method)?;
+
+    let (data, digest) = future::try_join(
+        reader.fetch_payment_endpoint(payee, &method),
+        reader.fetch_payment_endpoint(payee, &sidecar),
+    )
+    .await
+    .map_err(|err| map_transport_error("get_payment_endpoint_verified", err))?;
+
+    let Some(data) = data else {
+        return Ok(None);
+    };
+    let Some(digest) = digest else {
+        return Ok(Some(CheckedEndpoint {
+            data,
+            verified: false,
+        }));
+    };
+    let expected = digest.0.trim();
+    if !expected.eq_ignore_ascii_case(&sha256_hex(&data)) {
+        return Err(PaykitError::InvalidData(format!(
+            "get_payment_endpoint_verified: {method} does not match its sha256 sidecar"
+        )));
+    }
+    Ok(Some(CheckedEndpoint {
+        data,
+        verified: true,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+
+    fn lightning() -> MethodId {
+        MethodId::new("lightning").unwrap()
+    }
+
+    #[test]
+    fn digest_is_lowercase_hex() {
+        assert_eq!(
+            sha256_hex(&EndpointData("abc".into())),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(sidecar_method(&lightning()).unwrap().0, "lightning.sha256");
+    }
+
+    #[tokio::test]
+    async fn matching_payload_is_verified() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        set_payment_endpoint_with_checksum(&mock, lightning(), EndpointData("ln".into()))
+            .await
+            .unwrap();
+
+        let fetched = get_payment_endpoint_verified(&mock, &owner, lightning())
+            .await
+            .unwrap();
+        assert_eq!(
+            fetched,
+            Some(CheckedEndpoint {
+                data: EndpointData("ln".into()),
+                verified: true,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn tampered_payload_is_rejected() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        set_payment_endpoint_with_checksum(&mock, lightning(), EndpointData("ln".into()))
+            .await
+            .unwrap();
+        mock.insert_endpoint(&owner, lightning(), EndpointData("l".into()));
+
+        let err = get_payment_endpoint_verified(&mock, &owner, lightning())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn missing_sidecar_is_returned_unverified() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_endpoint(&owner, lightning(), EndpointData("ln".into()));
+
+        let fetched = get_payment_endpoint_verified(&mock, &owner, lightning())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!fetched.verified);
+        assert_eq!(fetched.data, EndpointData("ln".into()));
+
+        let stranger = random_public_key();
+        assert!(get_payment_endpoint_verified(&mock, &stranger, lightning())
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -12,12 +12,24 @@
 //!   [`AuthenticatedTransport`].
 //! - Keep storage/session management outside of the crate so integrators can inject their
 //!   own security model, capability scoping, caching, or telemetry.
-//! - Export the standard Pubky path prefixes (see [`transport::pubky`]) to keep file layout
+//! - Export the standard Pubky path prefixes (see `transport::pubky`) to keep file layout
 //!   consistent across bindings.
 //!
 //! For an architectural overview and example workflows, see `paykit-lib/README.md`.
 
-use std::{collections::HashMap, fmt};
+use std::{
+    borrow::{Borrow, Cow},
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    ops::Deref,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+#[cfg(any(feature = "pubky", feature = "tokio"))]
+use futures::Stream;
+#[cfg(feature = "pubky")]
+use futures::TryStreamExt;
+use futures::{future, stream, StreamExt};
 
 #[cfg(feature = "pubky")]
 pub use pubky::PublicKey;
@@ -28,6 +40,8 @@ pub use pubky::PublicKey;
 /// Applications providing their own transport layer should define a richer type
 /// and convert into this wrapper where necessary.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct PublicKey(pub String);
 
 #[cfg(not(feature = "pubky"))]
@@ -37,23 +51,116 @@ impl fmt::Display for PublicKey {
     }
 }
 
+#[cfg(not(feature = "pubky"))]
+impl PublicKey {
+    /// Length of a z-base-32 encoded ed25519 public key.
+    pub const ENCODED_LEN: usize = 52;
+
+    const Z_BASE_32: &'static [u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+    /// Parse and normalize a Pubky key.
+    ///
+    /// Accepts the 52-character z-base-32 form, optionally prefixed with `pk:` or
+    /// `pubky`, in any case. The key is stored lowercased without the prefix. Keys of the
+    /// wrong length, with characters outside the z-base-32 alphabet, or whose padding
+    /// bits are set are rejected with `PaykitError::InvalidData`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::PublicKey;
+    /// let key = PublicKey::parse("pk:O4DKSFBQK85OGZDB5OSZIW6BEFIGBUXMUXKUXQ8434Q89UJ56UYY")?;
+    /// assert_eq!(key.0, "o4dksfbqk85ogzdb5osziw6befigbuxmuxkuxq8434q89uj56uyy");
+    /// assert!(PublicKey::parse("not-a-key").is_err());
+    /// # Ok::<(), paykit_lib::PaykitError>(())
+    /// ```
+    pub fn parse(input: &str) -> Result<Self> {
+        let trimmed = input.trim();
+        let body = trimmed
+            .strip_prefix("pk:")
+            .or_else(|| trimmed.strip_prefix("pubky"))
+            .unwrap_or(trimmed)
+            .to_ascii_lowercase();
+
+        if body.len() != Self::ENCODED_LEN {
+            return Err(PaykitError::InvalidData(format!(
+                "public key {trimmed:?}: expected {} z-base-32 characters, found {}",
+                Self::ENCODED_LEN,
+                body.len()
+            )));
+        }
+        let mut last = 0;
+        for byte in body.bytes() {
+            last = Self::Z_BASE_32
+                .iter()
+                .position(|&c| c == byte)
+                .ok_or_else(|| {
+                    PaykitError::InvalidData(format!(
+                        "public key {trimmed:?}: invalid character {:?}",
+                        byte as char
+                    ))
+                })?;
+        }
+        // 52 characters carry 260 bits for a 256-bit key; the trailing 4 must be zero.
+        if last & 0b1111 != 0 {
+            return Err(PaykitError::InvalidData(format!(
+                "public key {trimmed:?}: non-zero padding bits"
+            )));
+        }
+        Ok(PublicKey(body))
+    }
+}
+
+/// Wraps the input verbatim without validation.
+///
+/// Deprecated: use `PublicKey::parse`, which rejects malformed keys. This impl stays
+/// infallible for backward compatibility.
 #[cfg(not(feature = "pubky"))]
 impl std::str::FromStr for PublicKey {
     type Err = std::convert::Infallible;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         Ok(PublicKey(s.to_string()))
     }
 }
 
-mod transport;
+pub mod cache;
+#[cfg(feature = "tokio")]
+pub mod concurrency;
+#[cfg(feature = "tokio")]
+pub mod contact_cache;
+pub mod dry_run;
+pub mod fallback;
+#[cfg(feature = "fs")]
+pub mod fs;
+#[cfg(feature = "integrity")]
+pub mod integrity;
+pub mod methods;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+pub mod noop;
+pub mod observe;
+pub mod pool;
+pub mod profile;
+#[cfg(feature = "tokio")]
+pub mod retry;
+pub mod shared;
+#[cfg(feature = "signed")]
+pub mod signed;
+#[cfg(feature = "tokio")]
+pub mod timeout;
+pub mod transport;
 
-pub use transport::{AuthenticatedTransport, UnauthenticatedTransportRead};
+pub use profile::{export_profile, import_profile, PaykitProfile};
+pub use transport::{AuthenticatedTransport, DynRead, UnauthenticatedTransportRead};
 
 /// Pubky adapters are only exposed when the default `pubky` feature is enabled.
 #[cfg(feature = "pubky")]
 pub use transport::{PubkyAuthenticatedTransport, PubkyUnauthenticatedTransport};
 
+/// The browser reader is only exposed when the `wasm` feature is enabled.
+#[cfg(feature = "wasm")]
+pub use transport::WasmUnauthenticatedTransport;
+
 /// Common result alias for Paykit operations.
 pub type Result<T> = std::result::Result<T, PaykitError>;
 
@@ -65,8 +172,115 @@ pub enum PaykitError {
     /// Wrapper for transport layer failures.
     ///
     /// Most user-facing failures bubble up through this variant, encapsulating
-    /// lower-level SDK/network errors. Other variants are reserved for future use.
+    /// lower-level SDK/network errors.
     Transport(String),
+    /// Transport failure that keeps the underlying error, exposed through
+    /// [`std::error::Error::source`] so reporters can show the root cause.
+    TransportSource(Box<dyn std::error::Error + Send + Sync>),
+    /// The request failed before any response arrived: DNS, connection, TLS, or a
+    /// connection dropped mid-transfer. Always transient.
+    ///
+    /// The message carries the whole error chain; a response with an error status is
+    /// reported as [`PaykitError::Server`] instead.
+    Network(String),
+    /// The transport succeeded but returned data that could not be decoded
+    /// (e.g., a non-UTF-8 endpoint body). Retrying will not help.
+    InvalidData(String),
+    /// A [`MethodId`] failed validation (empty, too long, or not a single path segment).
+    InvalidMethodId(String),
+    /// A batch operation failed part-way; see [`BatchError`] for what succeeded.
+    Batch(Box<BatchError>),
+    /// An operation did not complete within its deadline.
+    Timeout(String),
+    /// A conditional write found different content than expected and wrote nothing.
+    Conflict {
+        /// ETag the caller expected; `None` means the endpoint was expected to be absent.
+        expected: Option<String>,
+        /// ETag actually found; `None` means the endpoint does not exist, and an empty
+        /// tag means it exists but no ETag was available.
+        actual: Option<String>,
+    },
+    /// A create-only write found the method already published and wrote nothing.
+    AlreadyExists(MethodId),
+    /// A response body exceeded the configured size limit and was not buffered.
+    PayloadTooLarge {
+        /// Configured maximum, in bytes.
+        limit: usize,
+        /// Announced size, or the bytes received when the limit was crossed.
+        actual: usize,
+    },
+    /// The caller's credentials do not allow the operation, e.g. a session without
+    /// write access to the Paykit path. The first-party adapters also report 401 and
+    /// 403 responses this way, so wallets can prompt for a new session instead of
+    /// retrying.
+    Unauthorized(String),
+    /// The server is throttling the caller (HTTP 429).
+    ///
+    /// `retry::RetryingTransport` waits `retry_after` before the next attempt when it
+    /// is set, instead of its own backoff, and gives up when it exceeds `max_delay`.
+    RateLimited {
+        /// Delay advised by the server's `Retry-After` header, when it sent one in
+        /// seconds and the transport could read it.
+        retry_after: Option<std::time::Duration>,
+    },
+    /// The server answered with a non-success HTTP status.
+    ///
+    /// Failures before a response arrived (DNS, TLS, connection resets) are reported
+    /// as [`PaykitError::Network`] instead.
+    Server {
+        /// HTTP status code returned by the server.
+        status: u16,
+        /// Operation context plus the server's message.
+        message: String,
+    },
+}
+
+impl PaykitError {
+    /// HTTP status code for [`PaykitError::Server`] errors (429 for
+    /// [`PaykitError::RateLimited`]), `None` for every other variant.
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            PaykitError::Server { status, .. } => Some(*status),
+            PaykitError::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+
+    /// Whether the server reported the resource as missing (404) or gone (410).
+    pub fn is_not_found(&self) -> bool {
+        matches!(self.status_code(), Some(404 | 410))
+    }
+
+    /// Whether the failure may clear up on its own, so retrying is worthwhile.
+    ///
+    /// True for network-level failures (`Network`, and `TransportSource` unless the
+    /// wrapped SDK error is a local one such as an unparsable URL), `Timeout`,
+    /// `RateLimited`, and `Server` 5xx or 429 responses. False for everything else,
+    /// including `Transport` (local and resolution failures), `InvalidData`,
+    /// `InvalidMethodId`, `PayloadTooLarge`, `Unauthorized`, and `Unimplemented`.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            PaykitError::Network(_) | PaykitError::Timeout(_) | PaykitError::RateLimited { .. } => {
+                true
+            }
+            PaykitError::TransportSource(err) => source_is_transient(err.as_ref()),
+            PaykitError::Server { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+}
+
+/// Pubky SDK errors are classified by variant; any other source is assumed to be a
+/// network failure reported by a custom transport.
+#[cfg(feature = "pubky")]
+fn source_is_transient(err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    err.downcast_ref::<pubky::Error>()
+        .is_none_or(transport::pubky::is_transient)
+}
+
+#[cfg(not(feature = "pubky"))]
+fn source_is_transient(_err: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    true
 }
 
 impl fmt::Display for PaykitError {
@@ -76,78 +290,1223 @@ impl fmt::Display for PaykitError {
                 write!(f, "{label} is not implemented yet")
             }
             PaykitError::Transport(msg) => write!(f, "transport error: {msg}"),
+            PaykitError::TransportSource(err) => write!(f, "transport error: {err}"),
+            PaykitError::Network(msg) => write!(f, "network error: {msg}"),
+            PaykitError::InvalidData(msg) => write!(f, "invalid data: {msg}"),
+            PaykitError::InvalidMethodId(msg) => write!(f, "invalid method id: {msg}"),
+            PaykitError::Batch(batch) => write!(f, "{batch}"),
+            PaykitError::Timeout(msg) => write!(f, "timed out: {msg}"),
+            PaykitError::Conflict { expected, actual } => {
+                let show = |tag: &Option<String>| match tag {
+                    Some(tag) => format!("etag {tag:?}"),
+                    None => "no endpoint".to_string(),
+                };
+                write!(
+                    f,
+                    "conflict: expected {}, found {}",
+                    show(expected),
+                    show(actual)
+                )
+            }
+            PaykitError::AlreadyExists(method) => {
+                write!(f, "payment endpoint {method} already exists")
+            }
+            PaykitError::PayloadTooLarge { limit, actual } => {
+                write!(
+                    f,
+                    "payload of {actual} bytes exceeds the {limit} byte limit"
+                )
+            }
+            PaykitError::Unauthorized(msg) => write!(f, "unauthorized: {msg}"),
+            PaykitError::RateLimited {
+                retry_after: Some(delay),
+            } => write!(f, "rate limited, retry after {delay:?}"),
+            PaykitError::RateLimited { retry_after: None } => write!(f, "rate limited"),
+            PaykitError::Server { status, message } => write!(f, "http {status}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PaykitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PaykitError::TransportSource(err) => Some(err.as_ref()),
+            _ => None,
         }
     }
 }
 
-impl std::error::Error for PaykitError {}
+/// Outcome of a batch operation that did not fully succeed.
+#[derive(Debug)]
+pub struct BatchError {
+    /// Method ids whose operation completed successfully.
+    pub succeeded: Vec<MethodId>,
+    /// Every failing method id with its error, in input order. Never empty.
+    pub failed: Vec<(MethodId, PaykitError)>,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "batch failed for {} method(s) ({} succeeded)",
+            self.failed.len(),
+            self.succeeded.len()
+        )?;
+        if let Some((method, err)) = self.failed.first() {
+            write!(f, ", first at {}: {err}", method.0)?;
+        }
+        Ok(())
+    }
+}
 
 /// Identifier for a payment method specification.
 ///
 /// Typically based filename component stored under `/pub/paykit.app/v0/…`.
+///
+/// Prefer [`MethodId::new`], which guarantees the identifier is a single safe path
+/// segment. The tuple field stays public for backward compatibility; helpers such as
+/// [`set_payment_endpoint`] re-validate identifiers built that way before use.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct MethodId(pub String);
 
+impl MethodId {
+    /// Maximum length of a method identifier, in bytes.
+    pub const MAX_LEN: usize = 64;
+
+    /// Builds a validated method identifier.
+    ///
+    /// Rejects empty identifiers, `.`/`..`, identifiers longer than
+    /// [`MethodId::MAX_LEN`] bytes, and anything containing `/`, `\`, NUL or other
+    /// control characters, so the value can never escape the Paykit directory.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::MethodId;
+    /// let lightning = MethodId::new("lightning").unwrap();
+    /// assert_eq!(lightning.0, "lightning");
+    /// assert!(MethodId::new("../../secret").is_err());
+    /// ```
+    pub fn new(id: impl Into<String>) -> Result<Self> {
+        let method = Self(id.into());
+        method.validate()?;
+        Ok(method)
+    }
+
+    /// Checks that the identifier satisfies the rules enforced by [`MethodId::new`].
+    pub fn validate(&self) -> Result<()> {
+        let id = self.0.as_str();
+        let reason = if id.is_empty() {
+            "must not be empty"
+        } else if id.len() > Self::MAX_LEN {
+            "must not exceed 64 bytes"
+        } else if id == "." || id == ".." {
+            "must not be a relative path component"
+        } else if id.contains(['/', '\\']) {
+            "must not contain path separators"
+        } else if id.chars().any(char::is_control) {
+            "must not contain control characters"
+        } else {
+            return Ok(());
+        };
+        Err(PaykitError::InvalidMethodId(format!("{id:?} {reason}")))
+    }
+}
+
+/// Clones the identifier, so helpers taking `impl Into<MethodId>` accept references too.
+impl From<&MethodId> for MethodId {
+    fn from(method: &MethodId) -> Self {
+        method.clone()
+    }
+}
+
+impl fmt::Display for MethodId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for MethodId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Lets maps keyed by `MethodId` be queried with a plain `&str`.
+impl Borrow<str> for MethodId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for MethodId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
 /// Serialized payload served by a payment endpoint (UTF-8 text such as JSON, lnurl, etc.).
 ///
-/// If you need to transmit binary payloads, encode them (e.g., base64) before wrapping
-/// in `EndpointData`.
+/// Binary payloads are supported via [`EndpointData::from_bytes`], which stores them as
+/// base64 behind [`EndpointData::BINARY_PREFIX`] so they survive any text transport.
+/// Read them back with [`EndpointData::as_bytes`].
+///
+/// Publishers can declare which schema version a body follows with
+/// [`EndpointData::with_schema`]; readers branch on [`EndpointData::schema_version`] and
+/// parse [`EndpointData::body`].
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct EndpointData(pub String);
 
+impl EndpointData {
+    /// Marker prepended to base64-wrapped binary payloads (an RFC 2397 data URL).
+    pub const BINARY_PREFIX: &'static str = "data:application/octet-stream;base64,";
+
+    /// Start of the schema header line written by [`EndpointData::with_schema`], e.g.
+    /// `paykit-schema:2` followed by a newline and the body.
+    pub const SCHEMA_PREFIX: &'static str = "paykit-schema:";
+
+    /// The stored payload, schema header and binary prefix included.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Size of the stored payload in bytes, as counted against a reader's endpoint
+    /// size limit.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the payload is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Wrap `body` with a header declaring schema `version`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::EndpointData;
+    /// let data = EndpointData::with_schema(2, "{\"bolt11\":\"ln...\"}");
+    /// assert_eq!(data.0, "paykit-schema:2\n{\"bolt11\":\"ln...\"}");
+    /// assert_eq!(data.schema_version(), Some(2));
+    /// assert_eq!(data.body(), "{\"bolt11\":\"ln...\"}");
+    ///
+    /// let legacy = EndpointData("{\"bolt11\":\"ln...\"}".into());
+    /// assert_eq!(legacy.schema_version(), None);
+    /// assert_eq!(legacy.body(), legacy.0);
+    /// ```
+    pub fn with_schema(version: u32, body: &str) -> Self {
+        Self(format!("{}{version}\n{body}", Self::SCHEMA_PREFIX))
+    }
+
+    /// Schema version declared by a [`EndpointData::with_schema`] header, or `None` for
+    /// payloads without one (including a malformed header).
+    pub fn schema_version(&self) -> Option<u32> {
+        self.split_schema().map(|(version, _)| version)
+    }
+
+    /// Payload without its schema header; the whole payload when there is none.
+    pub fn body(&self) -> &str {
+        self.split_schema().map_or(&self.0, |(_, body)| body)
+    }
+
+    fn split_schema(&self) -> Option<(u32, &str)> {
+        let rest = self.0.strip_prefix(Self::SCHEMA_PREFIX)?;
+        let (version, body) = rest.split_once('\n')?;
+        // `u32::from_str` would also accept a leading `+`.
+        if version.is_empty() || !version.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        Some((version.parse().ok()?, body))
+    }
+
+    /// Wrap raw bytes as a binary payload.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::EndpointData;
+    /// let data = EndpointData::from_bytes(&[0x89, b'P', b'N', b'G']);
+    /// assert!(data.is_binary());
+    /// assert_eq!(&*data.as_bytes(), &[0x89, b'P', b'N', b'G']);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self(format!("{}{}", Self::BINARY_PREFIX, BASE64.encode(bytes)))
+    }
+
+    /// Whether the payload was produced by [`EndpointData::from_bytes`].
+    pub fn is_binary(&self) -> bool {
+        self.decode_binary().is_some()
+    }
+
+    /// Payload bytes: decoded for binary payloads, the UTF-8 text otherwise.
+    ///
+    /// Text that merely starts with [`EndpointData::BINARY_PREFIX`] but is not valid
+    /// base64 is returned verbatim.
+    pub fn as_bytes(&self) -> Cow<'_, [u8]> {
+        match self.decode_binary() {
+            Some(bytes) => Cow::Owned(bytes),
+            None => Cow::Borrowed(self.0.as_bytes()),
+        }
+    }
+
+    fn decode_binary(&self) -> Option<Vec<u8>> {
+        let body = self.0.strip_prefix(Self::BINARY_PREFIX)?;
+        BASE64.decode(body).ok()
+    }
+
+    /// Parse the payload [`body`](Self::body) as JSON, skipping any schema header.
+    ///
+    /// Fails with `PaykitError::InvalidData` when the body is not valid JSON.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::EndpointData;
+    /// let data = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+    /// assert_eq!(data.try_as_json()?["bolt11"], "lnbc1...");
+    /// # Ok::<(), paykit_lib::PaykitError>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn try_as_json(&self) -> Result<serde_json::Value> {
+        serde_json::from_str(self.body())
+            .map_err(|err| PaykitError::InvalidData(format!("endpoint payload is not JSON: {err}")))
+    }
+
+    /// Encode `value` as a compact JSON payload.
+    #[cfg(feature = "serde")]
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        Self(value.to_string())
+    }
+}
+
+/// Writes the payload as stored, so binary payloads print as their data URL.
+impl fmt::Display for EndpointData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+// No `Deref<Target = str>`: `str::as_bytes` would then sit next to the decoding
+// `EndpointData::as_bytes`, and the two disagree for binary payloads.
+impl AsRef<str> for EndpointData {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for EndpointData {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An endpoint payload together with the metadata its transport reported.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FetchedEndpoint {
+    /// The payload itself.
+    pub data: EndpointData,
+    /// Media type of the payload (e.g. `application/json`), if known.
+    pub content_type: Option<String>,
+    /// When the payload was last written, if known.
+    pub last_modified: Option<std::time::SystemTime>,
+}
+
+impl From<EndpointData> for FetchedEndpoint {
+    fn from(data: EndpointData) -> Self {
+        Self {
+            data,
+            content_type: None,
+            last_modified: None,
+        }
+    }
+}
+
 /// Collection of supported payment entries keyed by method identifiers.
+///
+/// With the `serde` feature this serializes as a flat object mapping method ids to
+/// endpoint payloads, e.g. `{"lightning":"{\"bolt11\":\"ln...\"}"}`.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct SupportedPayments {
     /// Map of `MethodId` to endpoint data.
     pub entries: HashMap<MethodId, EndpointData>,
 }
 
+impl SupportedPayments {
+    /// Start an empty [`SupportedPaymentsBuilder`].
+    pub fn builder() -> SupportedPaymentsBuilder {
+        SupportedPaymentsBuilder::default()
+    }
+
+    /// Payload published for `method`, if any.
+    pub fn get(&self, method: &MethodId) -> Option<&EndpointData> {
+        self.entries.get(method)
+    }
+
+    /// Whether an endpoint is published for `method`.
+    pub fn contains(&self, method: &MethodId) -> bool {
+        self.entries.contains_key(method)
+    }
+
+    /// Number of published methods.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no methods are published.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries in arbitrary order.
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, MethodId, EndpointData> {
+        self.entries.iter()
+    }
+
+    /// Entries sorted by method id, for output that must not change between calls.
+    pub fn sorted_entries(&self) -> Vec<(&MethodId, &EndpointData)> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+        entries
+    }
+
+    /// Adds the entries of `other`, resolving methods present in both with
+    /// `on_conflict`, and returns how many entries were added or changed.
+    ///
+    /// Overwriting an entry with an identical payload does not count as a change.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::{ConflictPolicy, EndpointData, MethodId, SupportedPayments};
+    /// let mut cached = SupportedPayments::default();
+    /// cached.entries.insert(MethodId::new("lightning")?, EndpointData("old".into()));
+    /// let mut fresh = SupportedPayments::default();
+    /// fresh.entries.insert(MethodId::new("lightning")?, EndpointData("new".into()));
+    /// assert_eq!(cached.merge(fresh, ConflictPolicy::Overwrite), 1);
+    /// # Ok::<(), paykit_lib::PaykitError>(())
+    /// ```
+    pub fn merge(&mut self, other: SupportedPayments, on_conflict: ConflictPolicy) -> usize {
+        let mut changed = 0;
+        for (method, data) in other.entries {
+            match self.entries.entry(method) {
+                Entry::Vacant(slot) => {
+                    slot.insert(data);
+                    changed += 1;
+                }
+                Entry::Occupied(mut slot) => {
+                    if on_conflict == ConflictPolicy::Overwrite && *slot.get() != data {
+                        slot.insert(data);
+                        changed += 1;
+                    }
+                }
+            }
+        }
+        changed
+    }
+
+    /// Copy of the list keeping only the entries for which `keep` returns `true`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::{EndpointData, MethodId, SupportedPayments};
+    /// # let payments = SupportedPayments::default();
+    /// // Drop binary payloads.
+    /// let text_only = payments.filter(|_, data| !data.is_binary());
+    /// ```
+    pub fn filter(&self, keep: impl Fn(&MethodId, &EndpointData) -> bool) -> SupportedPayments {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|(method, data)| keep(method, data))
+            .map(|(method, data)| (method.clone(), data.clone()))
+            .collect();
+        SupportedPayments { entries }
+    }
+
+    /// Copy of the list with every payload replaced by `map(method, payload)`.
+    ///
+    /// Stops at the first error `map` returns and propagates it unchanged; with entries
+    /// visited in arbitrary order, which error that is when several fail is unspecified.
+    pub fn try_map_values(
+        &self,
+        map: impl Fn(&MethodId, &EndpointData) -> Result<EndpointData>,
+    ) -> Result<SupportedPayments> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(method, data)| Ok((method.clone(), map(method, data)?)))
+            .collect::<Result<_>>()?;
+        Ok(SupportedPayments { entries })
+    }
+
+    /// Methods added, removed, or republished with a different payload since `previous`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::{EndpointData, MethodId, SupportedPayments};
+    /// let previous = SupportedPayments::default();
+    /// let mut current = SupportedPayments::default();
+    /// current.entries.insert(MethodId::new("lightning")?, EndpointData("lnbc1...".into()));
+    ///
+    /// let diff = current.diff(&previous);
+    /// assert_eq!(diff.added, [MethodId::new("lightning")?]);
+    /// assert!(current.diff(&current).is_empty());
+    /// # Ok::<(), paykit_lib::PaykitError>(())
+    /// ```
+    pub fn diff(&self, previous: &SupportedPayments) -> PaymentListDiff {
+        let mut diff = PaymentListDiff::default();
+        for (method, data) in &self.entries {
+            match previous.entries.get(method) {
+                None => diff.added.push(method.clone()),
+                Some(old) if old != data => diff.changed.push(method.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = previous
+            .entries
+            .keys()
+            .filter(|method| !self.entries.contains_key(*method))
+            .cloned()
+            .collect();
+        for ids in [&mut diff.added, &mut diff.removed, &mut diff.changed] {
+            ids.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+        diff
+    }
+
+    /// Encode the list in `format`, ordered by method id so equal lists always produce
+    /// the same string.
+    ///
+    /// # Examples
+    /// ```
+    /// # use paykit_lib::{EndpointData, MethodId, PaymentListFormat, SupportedPayments};
+    /// let mut payments = SupportedPayments::default();
+    /// payments.entries.insert(MethodId::new("lightning")?, EndpointData("lnbc1...".into()));
+    ///
+    /// let array = payments.serialize(PaymentListFormat::JsonArray);
+    /// assert_eq!(array, r#"[{"method":"lightning","data":"lnbc1..."}]"#);
+    /// let decoded = SupportedPayments::deserialize(&array, PaymentListFormat::JsonArray)?;
+    /// assert_eq!(decoded, payments);
+    /// # Ok::<(), paykit_lib::PaykitError>(())
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn serialize(&self, format: PaymentListFormat) -> String {
+        let sorted = self.sorted_entries();
+        // Maps and lists of strings always serialize.
+        let encoded = match format {
+            PaymentListFormat::JsonObject => {
+                let object: serde_json::Map<_, _> = sorted
+                    .into_iter()
+                    .map(|(method, data)| (method.0.clone(), data.0.clone().into()))
+                    .collect();
+                serde_json::to_string(&object)
+            }
+            PaymentListFormat::JsonArray => {
+                let entries: Vec<_> = sorted.into_iter().map(ListEntryWire::from).collect();
+                serde_json::to_string(&entries)
+            }
+            PaymentListFormat::NdJson => sorted
+                .into_iter()
+                .map(|entry| {
+                    serde_json::to_string(&ListEntryWire::from(entry)).map(|line| line + "\n")
+                })
+                .collect(),
+        };
+        encoded.expect("payment lists serialize to JSON")
+    }
+
+    /// Decode a list written by [`Self::serialize`] in `format`.
+    ///
+    /// Fails with `PaykitError::InvalidData` on malformed input, unknown fields, method
+    /// ids that [`MethodId::validate`] rejects, or a method listed twice. Blank lines are
+    /// skipped in [`PaymentListFormat::NdJson`].
+    #[cfg(feature = "serde")]
+    pub fn deserialize(input: &str, format: PaymentListFormat) -> Result<Self> {
+        let invalid =
+            |err: serde_json::Error| PaykitError::InvalidData(format!("payment list: {err}"));
+        let pairs: Vec<(String, String)> = match format {
+            PaymentListFormat::JsonObject => {
+                serde_json::from_str::<ObjectPairs>(input)
+                    .map_err(invalid)?
+                    .0
+            }
+            PaymentListFormat::JsonArray => serde_json::from_str::<Vec<ListEntryWire>>(input)
+                .map_err(invalid)?
+                .into_iter()
+                .map(|entry| (entry.method, entry.data))
+                .collect(),
+            PaymentListFormat::NdJson => input
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(index, line)| {
+                    let entry: ListEntryWire = serde_json::from_str(line).map_err(|err| {
+                        PaykitError::InvalidData(format!("payment list line {}: {err}", index + 1))
+                    })?;
+                    Ok((entry.method, entry.data))
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let mut entries = HashMap::with_capacity(pairs.len());
+        for (method, data) in pairs {
+            let method = MethodId(method);
+            method
+                .validate()
+                .map_err(|err| PaykitError::InvalidData(format!("payment list: {err}")))?;
+            match entries.entry(method) {
+                Entry::Vacant(slot) => {
+                    slot.insert(EndpointData(data));
+                }
+                Entry::Occupied(slot) => {
+                    return Err(PaykitError::InvalidData(format!(
+                        "payment list: method {:?} is listed twice",
+                        slot.key().0
+                    )));
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+}
+
+/// Wire layout picked explicitly by [`SupportedPayments::serialize`] and
+/// [`SupportedPayments::deserialize`], e.g. to suit an FFI binding.
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PaymentListFormat {
+    /// A JSON object mapping method ids to payloads, `{"lightning":"..."}`; the same
+    /// shape the `serde` feature uses.
+    JsonObject,
+    /// A JSON array of `{"method":"...","data":"..."}` objects.
+    JsonArray,
+    /// Newline-delimited JSON: one `{"method":"...","data":"..."}` object per line.
+    NdJson,
+}
+
+/// One entry in the array and newline-delimited list formats.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListEntryWire {
+    method: String,
+    data: String,
+}
+
+#[cfg(feature = "serde")]
+impl From<(&MethodId, &EndpointData)> for ListEntryWire {
+    fn from((method, data): (&MethodId, &EndpointData)) -> Self {
+        Self {
+            method: method.0.clone(),
+            data: data.0.clone(),
+        }
+    }
+}
+
+/// Members of a JSON object of strings in document order, keeping duplicate keys so
+/// they can be rejected instead of silently overwritten.
+#[cfg(feature = "serde")]
+struct ObjectPairs(Vec<(String, String)>);
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ObjectPairs {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct PairsVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for PairsVisitor {
+            type Value = ObjectPairs;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object mapping method ids to payload strings")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> std::result::Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut pairs = Vec::new();
+                while let Some(pair) = map.next_entry()? {
+                    pairs.push(pair);
+                }
+                Ok(ObjectPairs(pairs))
+            }
+        }
+
+        deserializer.deserialize_map(PairsVisitor)
+    }
+}
+
+/// How [`SupportedPayments::merge`] resolves a method present in both lists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConflictPolicy {
+    /// Keep the payload already in the list.
+    KeepExisting,
+    /// Replace it with the incoming payload.
+    Overwrite,
+}
+
+/// Changes between two payment lists, as computed by [`SupportedPayments::diff`].
+///
+/// Each list is sorted by method id.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PaymentListDiff {
+    /// Methods published now but not before.
+    pub added: Vec<MethodId>,
+    /// Methods published before but not now.
+    pub removed: Vec<MethodId>,
+    /// Methods published in both lists with different payloads.
+    pub changed: Vec<MethodId>,
+}
+
+impl PaymentListDiff {
+    /// Whether the two lists were identical.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl IntoIterator for SupportedPayments {
+    type Item = (MethodId, EndpointData);
+    type IntoIter = std::collections::hash_map::IntoIter<MethodId, EndpointData>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a SupportedPayments {
+    type Item = (&'a MethodId, &'a EndpointData);
+    type IntoIter = std::collections::hash_map::Iter<'a, MethodId, EndpointData>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Collects entries without validation; use [`SupportedPaymentsBuilder`] to check the
+/// method ids. Later duplicates replace earlier ones.
+impl FromIterator<(MethodId, EndpointData)> for SupportedPayments {
+    fn from_iter<I: IntoIterator<Item = (MethodId, EndpointData)>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Fluent constructor for a [`SupportedPayments`], e.g. to seed tests or a first
+/// publish.
+///
+/// Method ids are checked with the rules of [`MethodId::new`]; the first invalid one
+/// is reported by [`Self::build`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{EndpointData, MethodId, SupportedPayments};
+/// let payments = SupportedPayments::builder()
+///     .add(MethodId::new("lightning")?, EndpointData("lnbc1...".into()))
+///     .add(MethodId::new("onchain")?, EndpointData("bc1q...".into()))
+///     .build()?;
+/// assert_eq!(payments.len(), 2);
+/// # Ok::<(), paykit_lib::PaykitError>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SupportedPaymentsBuilder {
+    entries: HashMap<MethodId, EndpointData>,
+    invalid: Option<MethodId>,
+}
+
+impl SupportedPaymentsBuilder {
+    /// Add an entry, replacing any earlier payload for the same method.
+    ///
+    /// `method` may be a [`MethodId`] (by value or reference) or a
+    /// [`methods::PaymentMethod`]. An invalid id is left out and fails [`Self::build`].
+    pub fn add(&mut self, method: impl Into<MethodId>, data: EndpointData) -> &mut Self {
+        let method = method.into();
+        if method.validate().is_ok() {
+            self.entries.insert(method, data);
+        } else if self.invalid.is_none() {
+            self.invalid = Some(method);
+        }
+        self
+    }
+
+    /// The collected list, or `PaykitError::InvalidMethodId` for the first invalid id
+    /// passed to [`Self::add`].
+    pub fn build(&self) -> Result<SupportedPayments> {
+        if let Some(method) = &self.invalid {
+            method.validate()?;
+        }
+        Ok(SupportedPayments {
+            entries: self.entries.clone(),
+        })
+    }
+}
+
 /// Stores or updates a payment endpoint via the injected authenticated client.
 ///
 /// # Examples
 /// ```
 /// # use paykit_lib::{set_payment_endpoint, MethodId, EndpointData, PublicKey};
 /// # use paykit_lib::AuthenticatedTransport;
-/// # async fn demo(client: &impl AuthenticatedTransport) -> paykit_lib::Result<()> {
-/// let method = MethodId("lightning".into());
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let method = MethodId::new("lightning")?;
 /// let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
 /// set_payment_endpoint(client, method, data).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn set_payment_endpoint<S>(client: &S, method: MethodId, data: EndpointData) -> Result<()>
+pub async fn set_payment_endpoint<S, M>(client: &S, method: M, data: EndpointData) -> Result<()>
 where
-    S: AuthenticatedTransport,
+    S: AuthenticatedTransport + ?Sized,
+    M: Into<MethodId>,
 {
+    let method = method.into();
+    method.validate()?;
     client
         .upsert_payment_endpoint(&method, &data)
         .await
         .map_err(|err| map_transport_error("set_payment_endpoint", err))
 }
 
-/// Removes a payment endpoint via the injected authenticated client.
-pub async fn remove_payment_endpoint<S>(client: &S, method: MethodId) -> Result<()>
+/// Like [`set_payment_endpoint`], advertising the payload as `content_type` (e.g.
+/// `application/json` or `text/plain`).
+///
+/// # Semantics
+/// - Transports that cannot store a content type write the payload without it; see
+///   `transport::pubky::PubkyAuthenticatedTransport` for the Pubky homeserver.
+/// - Readers see the type through [`get_payment_endpoint_full`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{set_payment_endpoint_with_type, MethodId, EndpointData};
+/// # use paykit_lib::AuthenticatedTransport;
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+/// set_payment_endpoint_with_type(client, MethodId::new("lightning")?, data, "application/json")
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_payment_endpoint_with_type<S, M>(
+    client: &S,
+    method: M,
+    data: EndpointData,
+    content_type: &str,
+) -> Result<()>
 where
-    S: AuthenticatedTransport,
+    S: AuthenticatedTransport + Sync + ?Sized,
+    M: Into<MethodId>,
 {
+    let method = method.into();
+    method.validate()?;
     client
-        .remove_payment_endpoint(&method)
+        .upsert_payment_endpoint_with_type(&method, &data, content_type)
         .await
-        .map_err(|err| map_transport_error("remove_payment_endpoint", err))
+        .map_err(|err| map_transport_error("set_payment_endpoint_with_type", err))
 }
 
-/// Retrieves all supported payment methods for the given payee.
+/// Stores `data` only if the endpoint still has the content identified by
+/// `expected_etag`, returning the new ETag.
+///
+/// Use the ETag returned by one write as `expected_etag` for the next; pass `None` to
+/// create an endpoint that must not exist yet.
+///
+/// # Semantics
+/// - Returns `PaykitError::Conflict` when another writer changed (or created) the
+///   endpoint in the meantime; nothing is written.
+/// - Returns `PaykitError::Unimplemented` for transports without conditional writes.
+/// - See `transport::pubky::PubkyAuthenticatedTransport` for the guarantees of the Pubky
+///   adapter.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{set_payment_endpoint_cas, EndpointData, MethodId, PaykitError};
+/// # use paykit_lib::AuthenticatedTransport;
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let method = MethodId::new("lightning")?;
+/// let etag = set_payment_endpoint_cas(client, &method, EndpointData("v1".into()), None).await?;
+/// match set_payment_endpoint_cas(client, &method, EndpointData("v2".into()), Some(etag)).await {
+///     Ok(_) => println!("updated"),
+///     Err(PaykitError::Conflict { .. }) => println!("someone else updated it first"),
+///     Err(err) => return Err(err),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_payment_endpoint_cas<S, M>(
+    client: &S,
+    method: M,
+    data: EndpointData,
+    expected_etag: Option<String>,
+) -> Result<String>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    client
+        .upsert_payment_endpoint_if_match(&method, &data, expected_etag)
+        .await
+        .map_err(|err| map_transport_error("set_payment_endpoint_cas", err))
+}
+
+/// Stores a payment endpoint only if `method` is not published yet.
+///
+/// [`set_payment_endpoint`] stays the overwrite path. Because
+/// [`AuthenticatedTransport`] has no per-endpoint read, the existence check goes through
+/// `reader`, looking up the key from [`AuthenticatedTransport::public_key`]. Without a
+/// reader, the check uses [`AuthenticatedTransport::list_own_endpoints`].
+///
+/// # Semantics
+/// - Returns `PaykitError::AlreadyExists` when the endpoint already exists; nothing is
+///   written.
+/// - With `reader`, requires a transport that knows its public key; without one, a
+///   transport that can list its own endpoints. Otherwise returns
+///   `PaykitError::Unimplemented`.
+/// - The check and the write are separate requests, so a concurrent writer can still
+///   slip in between. Use [`set_payment_endpoint_cas`] with `None` where the transport
+///   supports conditional writes.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{create_payment_endpoint, EndpointData, MethodId, PaykitError};
+/// # use paykit_lib::{AuthenticatedTransport, UnauthenticatedTransportRead};
+/// # async fn demo(
+/// #     client: &(impl AuthenticatedTransport + Sync),
+/// #     reader: &(impl UnauthenticatedTransportRead + Sync),
+/// # ) -> paykit_lib::Result<()> {
+/// let method = MethodId::new("lightning")?;
+/// let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+/// match create_payment_endpoint(client, Some(reader), method, data).await {
+///     Ok(()) => println!("created"),
+///     Err(PaykitError::AlreadyExists(method)) => println!("{method} already published"),
+///     Err(err) => return Err(err),
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn create_payment_endpoint<S, M>(
+    client: &S,
+    reader: Option<&(dyn UnauthenticatedTransportRead + Sync)>,
+    method: M,
+    data: EndpointData,
+) -> Result<()>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+
+    let exists = match reader {
+        Some(reader) => match client.public_key() {
+            Ok(owner) => reader.fetch_payment_endpoint_exists(&owner, &method).await,
+            Err(err) => Err(err),
+        },
+        None => client
+            .list_own_endpoints()
+            .await
+            .map(|current| current.contains(&method)),
+    }
+    .map_err(|err| map_transport_error("create_payment_endpoint", err))?;
+    if exists {
+        return Err(PaykitError::AlreadyExists(method));
+    }
+
+    client
+        .upsert_payment_endpoint(&method, &data)
+        .await
+        .map_err(|err| map_transport_error("create_payment_endpoint", err))
+}
+
+/// Stores or updates several payment endpoints concurrently.
+///
+/// # Semantics
+/// - All method ids are validated before any write is issued.
+/// - Every upsert is attempted, even when another one fails, so the outcome is known
+///   for each method.
+/// - If any upsert fails, returns `PaykitError::Batch` carrying every failure (in input
+///   order) and the method ids that were written successfully.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{set_payment_endpoints, MethodId, EndpointData};
+/// # use paykit_lib::AuthenticatedTransport;
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// set_payment_endpoints(
+///     client,
+///     [
+///         (MethodId::new("lightning")?, EndpointData("{\"bolt11\":\"ln...\"}".into())),
+///         (MethodId::new("onchain")?, EndpointData("{\"address\":\"bc1...\"}".into())),
+///     ],
+/// )
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn set_payment_endpoints<S>(
+    client: &S,
+    entries: impl IntoIterator<Item = (MethodId, EndpointData)>,
+) -> Result<()>
+where
+    S: AuthenticatedTransport + ?Sized,
+{
+    let entries: Vec<_> = entries.into_iter().collect();
+    for (method, _) in &entries {
+        method.validate()?;
+    }
+
+    let results = future::join_all(
+        entries
+            .iter()
+            .map(|(method, data)| client.upsert_payment_endpoint(method, data)),
+    )
+    .await;
+
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    for ((method, _), result) in entries.into_iter().zip(results) {
+        match result {
+            Ok(()) => succeeded.push(method),
+            Err(err) => failed.push((method, map_transport_error("set_payment_endpoints", err))),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(PaykitError::Batch(Box::new(BatchError {
+            succeeded,
+            failed,
+        })))
+    }
+}
+
+/// Makes the caller's published endpoints match `desired` exactly.
+///
+/// Reads the current list through [`AuthenticatedTransport::list_own_endpoints`],
+/// removes methods absent from `desired`, and upserts entries that are new or changed.
+/// Unchanged entries are not rewritten.
+///
+/// # Semantics
+/// - Requires a transport that can list its own endpoints; otherwise returns
+///   `PaykitError::Unimplemented`.
+/// - Not transactional: if a write fails part-way the published list may be a mix of
+///   the old and desired state. Calling again converges from wherever it stopped.
+pub async fn set_payment_list<S>(client: &S, desired: SupportedPayments) -> Result<()>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    for method in desired.entries.keys() {
+        method.validate()?;
+    }
+
+    let current = client
+        .list_own_endpoints()
+        .await
+        .map_err(|err| map_transport_error("set_payment_list", err))?;
+
+    for method in current.entries.keys() {
+        if !desired.entries.contains_key(method) {
+            client
+                .remove_payment_endpoint(method)
+                .await
+                .map_err(|err| map_transport_error("set_payment_list", err))?;
+        }
+    }
+
+    let changed = desired
+        .entries
+        .into_iter()
+        .filter(|(method, data)| current.entries.get(method) != Some(data));
+    set_payment_endpoints(client, changed).await
+}
+
+/// Removes every endpoint the caller has published and returns how many were deleted.
+///
+/// # Semantics
+/// - Requires a transport that can list its own endpoints; otherwise returns
+///   `PaykitError::Unimplemented`.
+/// - An empty list returns `Ok(0)`. An endpoint that disappears between the listing
+///   and its delete (404/410) is skipped and not counted.
+/// - Stops at the first other failure; endpoints deleted before it stay deleted, so
+///   calling again finishes the job.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{clear_payment_list, AuthenticatedTransport};
+/// # async fn offboard(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let removed = clear_payment_list(client).await?;
+/// println!("removed {removed} endpoints");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn clear_payment_list<S>(client: &S) -> Result<usize>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    let current = client
+        .list_own_endpoints()
+        .await
+        .map_err(|err| map_transport_error("clear_payment_list", err))?;
+
+    let mut removed = 0;
+    for method in current.entries.keys() {
+        match client.remove_payment_endpoint(method).await {
+            Ok(()) => removed += 1,
+            Err(err) if err.is_not_found() => {}
+            Err(err) => return Err(map_transport_error("clear_payment_list", err)),
+        }
+    }
+    Ok(removed)
+}
+
+/// Removes a payment endpoint via the injected authenticated client.
+///
+/// # Semantics
+/// - Removing a method that is not published fails; the first-party transports report
+///   it as an error satisfying [`PaykitError::is_not_found`]. Use
+///   [`remove_payment_endpoint_idempotent`] to treat that case as success.
+pub async fn remove_payment_endpoint<S, M>(client: &S, method: M) -> Result<()>
+where
+    S: AuthenticatedTransport + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    client
+        .remove_payment_endpoint(&method)
+        .await
+        .map_err(|err| map_transport_error("remove_payment_endpoint", err))
+}
+
+/// Removes a payment endpoint, reporting whether anything was removed.
+///
+/// # Semantics
+/// - Returns `Ok(true)` when the endpoint existed and was deleted.
+/// - Returns `Ok(false)` when the transport reports it missing (see
+///   [`PaykitError::is_not_found`]), so repeated calls succeed.
+/// - Every other failure is returned unchanged.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{remove_payment_endpoint_idempotent, AuthenticatedTransport, MethodId};
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// if !remove_payment_endpoint_idempotent(client, MethodId::new("lightning")?).await? {
+///     println!("nothing to remove");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remove_payment_endpoint_idempotent<S, M>(client: &S, method: M) -> Result<bool>
+where
+    S: AuthenticatedTransport + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    match client.remove_payment_endpoint(&method).await {
+        Ok(()) => Ok(true),
+        Err(err) if err.is_not_found() => Ok(false),
+        Err(err) => Err(map_transport_error(
+            "remove_payment_endpoint_idempotent",
+            err,
+        )),
+    }
+}
+
+/// Removes several payment endpoints concurrently and returns the method ids removed.
+///
+/// # Semantics
+/// - All method ids are validated before any delete is issued. Duplicate ids are
+///   removed once.
+/// - With `idempotent` set, methods that are not published (see
+///   [`PaykitError::is_not_found`]) count as removed; otherwise they fail like any other
+///   delete.
+/// - Every delete is attempted, even when another one fails. On success the removed
+///   ids are returned in input order.
+/// - If any delete fails, returns `PaykitError::Batch` carrying every failure (in input
+///   order) and the method ids that were removed.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{remove_payment_endpoints, MethodId};
+/// # use paykit_lib::AuthenticatedTransport;
+/// # async fn demo(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let methods = [MethodId::new("lightning")?, MethodId::new("onchain")?];
+/// let removed = remove_payment_endpoints(client, methods, true).await?;
+/// println!("removed {} endpoints", removed.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn remove_payment_endpoints<S>(
+    client: &S,
+    methods: impl IntoIterator<Item = MethodId>,
+    idempotent: bool,
+) -> Result<Vec<MethodId>>
+where
+    S: AuthenticatedTransport + ?Sized,
+{
+    let mut unique = Vec::new();
+    for method in methods {
+        method.validate()?;
+        if !unique.contains(&method) {
+            unique.push(method);
+        }
+    }
+
+    let results = future::join_all(
+        unique
+            .iter()
+            .map(|method| client.remove_payment_endpoint(method)),
+    )
+    .await;
+
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    for (method, result) in unique.into_iter().zip(results) {
+        match result {
+            Ok(()) => succeeded.push(method),
+            Err(err) if idempotent && err.is_not_found() => succeeded.push(method),
+            Err(err) => failed.push((method, map_transport_error("remove_payment_endpoints", err))),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(succeeded)
+    } else {
+        Err(PaykitError::Batch(Box::new(BatchError {
+            succeeded,
+            failed,
+        })))
+    }
+}
+
+/// Retrieves all supported payment methods for the given payee.
 ///
 /// # Semantics
 /// - Returns an empty map when the payee has not published any endpoints or their
-///   storage directory is missing.
-/// - Propagates transport failures (e.g., network errors) as `PaykitError::Transport`.
+///   storage directory is missing. Map order is arbitrary; use
+///   [`SupportedPayments::sorted_entries`] for a stable order.
+/// - Propagates requests that got no response as `PaykitError::Network`, other
+///   transport failures as `PaykitError::Transport` or `PaykitError::TransportSource`,
+///   and non-success server responses as `PaykitError::Server`.
 ///
 /// # Examples
 /// ```
 /// # use paykit_lib::{get_payment_list, MethodId, EndpointData, SupportedPayments};
 /// # use paykit_lib::{AuthenticatedTransport, UnauthenticatedTransportRead};
-/// # async fn demo(reader: &impl UnauthenticatedTransportRead, pk: &paykit_lib::PublicKey) -> paykit_lib::Result<()> {
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &paykit_lib::PublicKey) -> paykit_lib::Result<()> {
 /// let payments = get_payment_list(reader, pk).await?;
 /// if payments.entries.is_empty() {
 ///     println!("payee published no endpoints yet");
@@ -161,7 +1520,7 @@ where
 /// ```
 pub async fn get_payment_list<R>(reader: &R, payee: &PublicKey) -> Result<SupportedPayments>
 where
-    R: UnauthenticatedTransportRead,
+    R: UnauthenticatedTransportRead + ?Sized,
 {
     reader
         .fetch_supported_payments(payee)
@@ -169,274 +1528,3639 @@ where
         .map_err(|err| map_transport_error("get_payment_list", err))
 }
 
+/// Like [`get_payment_list`], but keeps every endpoint that could be read and reports
+/// the methods whose body failed alongside it.
+///
+/// # Semantics
+/// - Only a failure to list the payee's methods is returned as `Err`; a failed body
+///   lands in the second element, sorted by method id, and is left out of the map.
+/// - Transports without per-entry reads (see
+///   [`UnauthenticatedTransportRead::fetch_supported_payments_lenient`]) behave like
+///   [`get_payment_list`], with no failures reported.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_payment_list_lenient, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let (payments, failures) = get_payment_list_lenient(reader, pk).await?;
+/// for (method, err) in &failures {
+///     eprintln!("skipping {}: {err}", method.0);
+/// }
+/// println!("{} usable methods", payments.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_payment_list_lenient<R>(
+    reader: &R,
+    payee: &PublicKey,
+) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)>
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+{
+    let (payments, failures) = reader
+        .fetch_supported_payments_lenient(payee)
+        .await
+        .map_err(|err| map_transport_error("get_payment_list_lenient", err))?;
+    let failures = failures
+        .into_iter()
+        .map(|(method, err)| (method, map_transport_error("get_payment_list_lenient", err)))
+        .collect();
+    Ok((payments, failures))
+}
+
+/// Like [`get_payment_list`], but fails with `PaykitError::Timeout` once `deadline`
+/// has elapsed.
+///
+/// The deadline covers the whole fetch, directory listing and every body together,
+/// unlike [`timeout::TimeoutTransport`], which bounds each transport call separately.
+/// Entries already fetched when it expires are discarded. Requires the `tokio` feature.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::{get_payment_list_within, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let payments = get_payment_list_within(reader, pk, Duration::from_secs(3)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn get_payment_list_within<R>(
+    reader: &R,
+    payee: &PublicKey,
+    deadline: std::time::Duration,
+) -> Result<SupportedPayments>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    tokio::time::timeout(deadline, get_payment_list(reader, payee))
+        .await
+        .unwrap_or_else(|_| {
+            Err(PaykitError::Timeout(format!(
+                "get_payment_list exceeded {deadline:?}; partial results were discarded"
+            )))
+        })
+}
+
+/// Counts the payment methods `payee` publishes without downloading their payloads
+/// where the transport allows it, e.g. for a "supports 5 payment methods" label.
+///
+/// The Pubky adapter counts directory entries, so an empty placeholder file is counted
+/// even though [`get_payment_list`] skips it. Transport failures are reported as for
+/// [`get_payment_list`].
+pub async fn get_payment_method_count<R>(reader: &R, payee: &PublicKey) -> Result<usize>
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+{
+    reader
+        .count_supported_payments(payee)
+        .await
+        .map_err(|err| map_transport_error("get_payment_method_count", err))
+}
+
+/// Retrieves the supported payments for `payee` across several protocol versions.
+///
+/// `versions` is ordered newest first, e.g. `&["v1", "v0"]`, so payers keep resolving
+/// payees that migrated to a newer layout. Results from every version are merged and a
+/// method published under multiple versions resolves to the newest (earliest listed)
+/// payload. See [`PubkyUnauthenticatedTransport::fetch_supported_payments_versioned`].
+#[cfg(feature = "pubky")]
+pub async fn get_payment_list_versioned(
+    reader: &PubkyUnauthenticatedTransport,
+    payee: &PublicKey,
+    versions: &[&str],
+) -> Result<SupportedPayments> {
+    reader
+        .fetch_supported_payments_versioned(payee, versions)
+        .await
+        .map_err(|err| map_transport_error("get_payment_list_versioned", err))
+}
+
+/// Retrieves only the supported payments of `payee` whose method id starts with
+/// `prefix`, e.g. `lightning.`.
+///
+/// Non-matching methods are dropped from the directory listing before any body is
+/// fetched. An empty prefix behaves like [`get_payment_list`]. See
+/// [`PubkyUnauthenticatedTransport::fetch_supported_payments_filtered`].
+#[cfg(feature = "pubky")]
+pub async fn get_payment_list_filtered(
+    reader: &PubkyUnauthenticatedTransport,
+    payee: &PublicKey,
+    prefix: &str,
+) -> Result<SupportedPayments> {
+    reader
+        .fetch_supported_payments_filtered(payee, prefix)
+        .await
+        .map_err(|err| map_transport_error("get_payment_list_filtered", err))
+}
+
+/// Streams the supported payments for `payee` as each endpoint body arrives.
+///
+/// Yields the entries of [`get_payment_list`] incrementally, so large lists can be
+/// filtered or processed without buffering them all. Transport failures appear as
+/// `Err` items. See [`PubkyUnauthenticatedTransport::stream_supported_payments`].
+///
+/// # Examples
+/// ```
+/// # use futures::TryStreamExt;
+/// # use paykit_lib::{stream_payment_list, PubkyUnauthenticatedTransport, PublicKey};
+/// # async fn demo(reader: &PubkyUnauthenticatedTransport, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let mut entries = std::pin::pin!(stream_payment_list(reader, pk));
+/// while let Some((method, data)) = entries.try_next().await? {
+///     println!("method={} payload={}", method.0, data.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "pubky")]
+pub fn stream_payment_list<'a>(
+    reader: &'a PubkyUnauthenticatedTransport,
+    payee: &'a PublicKey,
+) -> impl Stream<Item = Result<(MethodId, EndpointData)>> + Send + 'a {
+    reader
+        .stream_supported_payments(payee)
+        .map_err(|err| map_transport_error("stream_payment_list", err))
+}
+
 /// Retrieves a specific payment endpoint for `payee` and `method`.
 ///
 /// # Semantics
 /// - Returns `Ok(None)` when the endpoint file is missing or empty.
-/// - Returns `Err` only when the underlying transport fails (permissions, network, etc.).
+/// - Returns `PaykitError::InvalidMethodId` when `method` is not a valid identifier.
+/// - Returns `PaykitError::InvalidData` when the stored body is not valid UTF-8.
+/// - Otherwise returns `Err` only when the underlying transport fails (permissions,
+///   network, etc.).
+///
+/// `method` may be a [`MethodId`] (by value or reference) or a
+/// [`methods::PaymentMethod`].
 ///
 /// # Examples
 /// ```
 /// # use paykit_lib::{get_payment_endpoint, MethodId, PublicKey};
+/// # use paykit_lib::methods::PaymentMethod;
 /// # use paykit_lib::UnauthenticatedTransportRead;
-/// # async fn inspect(reader: &impl UnauthenticatedTransportRead, pk: &PublicKey) -> paykit_lib::Result<()> {
-/// let lightning = MethodId("lightning".into());
+/// # async fn inspect(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let lightning = MethodId::new("lightning")?;
 /// if let Some(endpoint) = get_payment_endpoint(reader, pk, &lightning).await? {
 ///     println!("lightning endpoint: {}", endpoint.0);
 /// } else {
 ///     println!("no lightning endpoint published");
 /// }
+/// let onchain = get_payment_endpoint(reader, pk, PaymentMethod::Onchain).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn get_payment_endpoint<R>(
+pub async fn get_payment_endpoint<R, M>(
     reader: &R,
     payee: &PublicKey,
-    method: &MethodId,
+    method: M,
 ) -> Result<Option<EndpointData>>
 where
-    R: UnauthenticatedTransportRead,
+    R: UnauthenticatedTransportRead + ?Sized,
+    M: Into<MethodId>,
 {
+    let method = method.into();
+    method.validate()?;
     reader
-        .fetch_payment_endpoint(payee, method)
+        .fetch_payment_endpoint(payee, &method)
         .await
         .map_err(|err| map_transport_error("get_payment_endpoint", err))
 }
 
-/// Returns known contacts of a given public key.
+/// Like [`get_payment_endpoint`], but also returns the payload's content type and
+/// last-modified time when the transport provides them.
 ///
-/// # Semantics
-/// - Returns an empty vector when no contacts are stored under the follows path
-///   or the directory does not exist yet.
-/// - Returns `Err` only when listing fails due to a transport error.
+/// The Pubky adapter takes both from the homeserver's response headers.
 ///
 /// # Examples
 /// ```
-/// # use paykit_lib::{get_known_contacts, PublicKey};
+/// # use paykit_lib::{get_payment_endpoint_full, MethodId, PublicKey};
 /// # use paykit_lib::UnauthenticatedTransportRead;
-/// # async fn contacts(reader: &impl UnauthenticatedTransportRead, pk: &PublicKey) -> paykit_lib::Result<()> {
-/// for contact in get_known_contacts(reader, pk).await? {
-///     println!("known contact: {}", contact);
+/// # async fn inspect(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// if let Some(endpoint) = get_payment_endpoint_full(reader, pk, MethodId::new("lightning")?).await? {
+///     println!("{:?} updated at {:?}", endpoint.content_type, endpoint.last_modified);
 /// }
 /// # Ok(())
 /// # }
 /// ```
-pub async fn get_known_contacts<R>(reader: &R, key: &PublicKey) -> Result<Vec<PublicKey>>
+pub async fn get_payment_endpoint_full<R, M>(
+    reader: &R,
+    payee: &PublicKey,
+    method: M,
+) -> Result<Option<FetchedEndpoint>>
 where
-    R: UnauthenticatedTransportRead,
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+    M: Into<MethodId>,
 {
+    let method = method.into();
+    method.validate()?;
     reader
-        .fetch_known_contacts(key)
+        .fetch_payment_endpoint_full(payee, &method)
         .await
-        .map_err(|err| map_transport_error("get_known_contacts", err))
+        .map_err(|err| map_transport_error("get_payment_endpoint_full", err))
+}
+
+/// Polls `payee`'s endpoint for `method` every `interval` and yields its content each
+/// time it changes.
+///
+/// # Semantics
+/// - The first successful poll is always yielded, as the baseline; after that a value
+///   is yielded only when it differs from the last one, so appearing (`Some`) and
+///   disappearing (`None`) both count as changes.
+/// - A failed poll is yielded as `Err` and polling continues; the baseline is kept, so
+///   an unchanged endpoint is not re-yielded once the transport recovers.
+/// - An invalid `method` yields `PaykitError::InvalidMethodId` once and ends the stream.
+///   Otherwise the stream never ends; drop it to stop polling.
+///
+/// Requires the `tokio` feature.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use futures::StreamExt;
+/// # use paykit_lib::{watch_payment_endpoint, MethodId, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let updates = watch_payment_endpoint(reader, pk, MethodId::new("lightning")?, Duration::from_secs(30));
+/// let mut updates = std::pin::pin!(updates);
+/// while let Some(update) = updates.next().await {
+///     match update? {
+///         Some(endpoint) => println!("lightning endpoint is now {}", endpoint.0),
+///         None => println!("lightning endpoint removed"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+pub fn watch_payment_endpoint<'a, R, M>(
+    reader: &'a R,
+    payee: &'a PublicKey,
+    method: M,
+    interval: std::time::Duration,
+) -> impl Stream<Item = Result<Option<EndpointData>>> + Send + 'a
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    if let Err(err) = method.validate() {
+        return stream::once(future::ready(Err(err))).left_stream();
+    }
+
+    // State: the method, the last content yielded (`None` before the first success),
+    // and whether to wait before the next poll.
+    let initial: (MethodId, Option<Option<EndpointData>>, bool) = (method, None, false);
+    stream::unfold(initial, move |(method, mut last, mut wait)| async move {
+        loop {
+            if wait {
+                tokio::time::sleep(interval).await;
+            }
+            wait = true;
+            match reader.fetch_payment_endpoint(payee, &method).await {
+                Ok(current) if last.as_ref() == Some(&current) => {}
+                Ok(current) => {
+                    last = Some(current.clone());
+                    return Some((Ok(current), (method, last, wait)));
+                }
+                Err(err) => {
+                    let err = map_transport_error("watch_payment_endpoint", err);
+                    return Some((Err(err), (method, last, wait)));
+                }
+            }
+        }
+    })
+    .right_stream()
+}
+
+/// Retrieves only the requested `methods` for `payee`, fetching them concurrently.
+///
+/// # Semantics
+/// - Methods that are missing or empty are omitted from the map, mirroring the
+///   `Ok(None)` case of [`get_payment_endpoint`]. Duplicate ids are fetched once.
+/// - Every id is validated before any request is sent; the first invalid one returns
+///   `PaykitError::InvalidMethodId`.
+/// - The first transport failure fails the whole call.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_payment_endpoints, MethodId, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn pick(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let wanted = [MethodId::new("lightning")?, MethodId::new("onchain")?];
+/// let endpoints = get_payment_endpoints(reader, pk, &wanted).await?;
+/// for (method, data) in &endpoints {
+///     println!("{} -> {}", method.0, data.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_payment_endpoints<R>(
+    reader: &R,
+    payee: &PublicKey,
+    methods: &[MethodId],
+) -> Result<HashMap<MethodId, EndpointData>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    let mut unique = Vec::with_capacity(methods.len());
+    for method in methods {
+        method.validate()?;
+        if !unique.contains(&method) {
+            unique.push(method);
+        }
+    }
+
+    let fetched = future::try_join_all(unique.into_iter().map(|method| async move {
+        let data = reader.fetch_payment_endpoint(payee, method).await?;
+        Ok::<_, PaykitError>(data.map(|data| (method.clone(), data)))
+    }))
+    .await
+    .map_err(|err| map_transport_error("get_payment_endpoints", err))?;
+
+    Ok(fetched.into_iter().flatten().collect())
 }
 
-fn map_transport_error(label: &'static str, err: PaykitError) -> PaykitError {
-    match err {
-        PaykitError::Transport(msg) => PaykitError::Transport(format!("{label}: {msg}")),
-        _ => err,
+/// Returns the first of `preferred` that `payee` publishes, together with the method id
+/// that matched.
+///
+/// # Semantics
+/// - Methods are tried one at a time in preference order; later ones are only fetched
+///   when every earlier one is missing or empty.
+/// - Returns `Ok(None)` when none of `preferred` is published, including when the slice
+///   is empty.
+/// - Every id is validated before any request is sent; the first invalid one returns
+///   `PaykitError::InvalidMethodId`.
+/// - A transport failure on any attempt fails the call rather than falling through to
+///   the next preference.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_payment_endpoint_or, MethodId, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn pick(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let preferred = [MethodId::new("lightning")?, MethodId::new("onchain")?];
+/// if let Some((method, data)) = get_payment_endpoint_or(reader, pk, &preferred).await? {
+///     println!("paying via {} -> {}", method.0, data.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_payment_endpoint_or<R>(
+    reader: &R,
+    payee: &PublicKey,
+    preferred: &[MethodId],
+) -> Result<Option<(MethodId, EndpointData)>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    for method in preferred {
+        method.validate()?;
+    }
+    for method in preferred {
+        let data = reader
+            .fetch_payment_endpoint(payee, method)
+            .await
+            .map_err(|err| map_transport_error("get_payment_endpoint_or", err))?;
+        if let Some(data) = data {
+            return Ok(Some((method.clone(), data)));
+        }
+    }
+    Ok(None)
+}
+
+/// Checks whether `payee` publishes a non-empty endpoint for `method` without
+/// downloading it.
+///
+/// Uses [`UnauthenticatedTransportRead::fetch_payment_endpoint_exists`]; the Pubky
+/// adapter answers with a `HEAD` request, so large payloads are never transferred.
+///
+/// # Semantics
+/// - Returns `Ok(false)` in every case where [`get_payment_endpoint`] returns `Ok(None)`.
+/// - Returns `PaykitError::InvalidMethodId` when `method` is not a valid identifier.
+pub async fn has_payment_endpoint<R, M>(reader: &R, payee: &PublicKey, method: M) -> Result<bool>
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+    M: Into<MethodId>,
+{
+    let method = method.into();
+    method.validate()?;
+    reader
+        .fetch_payment_endpoint_exists(payee, &method)
+        .await
+        .map_err(|err| map_transport_error("has_payment_endpoint", err))
+}
+
+/// Returns known contacts of a given public key.
+///
+/// # Semantics
+/// - Returns an empty vector when no contacts are stored under the follows path
+///   or the directory does not exist yet.
+/// - Entries whose names are not valid public keys are skipped; use
+///   [`get_known_contacts_with_report`] to inspect them.
+/// - The first-party transports sort contacts lexicographically by key string.
+/// - Returns `Err` only when listing fails due to a transport error.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_known_contacts, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn contacts(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// for contact in get_known_contacts(reader, pk).await? {
+///     println!("known contact: {}", contact);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_known_contacts<R>(reader: &R, key: &PublicKey) -> Result<Vec<PublicKey>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    reader
+        .fetch_known_contacts(key)
+        .await
+        .map_err(|err| map_transport_error("get_known_contacts", err))
+}
+
+/// Returns every known contact of `key`, walking the listing `page_size` entries at a
+/// time.
+///
+/// Unlike a single listing, which homeservers truncate, this follows the cursors from
+/// [`UnauthenticatedTransportRead::fetch_known_contacts_page`] until the last page.
+///
+/// # Semantics
+/// - Fails with `PaykitError::InvalidData` if the transport hands back the cursor it
+///   was given, which would otherwise loop forever.
+/// - The first failing page fails the whole call.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_known_contacts_paged, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn contacts(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let contacts = get_known_contacts_paged(reader, pk, 500).await?;
+/// println!("{} known contacts", contacts.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_known_contacts_paged<R>(
+    reader: &R,
+    key: &PublicKey,
+    page_size: usize,
+) -> Result<Vec<PublicKey>>
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+{
+    let mut contacts = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (page, next) = reader
+            .fetch_known_contacts_page(key, cursor.clone(), page_size)
+            .await
+            .map_err(|err| map_transport_error("get_known_contacts_paged", err))?;
+        contacts.extend(page);
+        match next {
+            Some(next) if cursor.as_ref() == Some(&next) => {
+                return Err(PaykitError::InvalidData(format!(
+                    "get_known_contacts_paged: cursor {next:?} did not advance"
+                )));
+            }
+            Some(next) => cursor = Some(next),
+            None => return Ok(contacts),
+        }
+    }
+}
+
+/// Returns the contacts of each of `key`'s contacts, keyed by the direct contact.
+///
+/// Direct contacts are listed first, then their own follow lists are fetched with at
+/// most `max_fanout` listings in flight (a fanout of `0` is treated as `1`).
+///
+/// # Semantics
+/// - `key` itself never appears in the result, neither as a direct contact nor in
+///   a second-degree list.
+/// - A direct contact whose follow list cannot be read is left out instead of
+///   failing the traversal; a failure to list `key`'s own contacts is returned.
+/// - Each second-degree list is sorted and has no duplicates. Contacts following
+///   nobody map to an empty list.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_contacts_of_contacts, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn suggest(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let graph = get_contacts_of_contacts(reader, pk, 8).await?;
+/// let mut suggestions: Vec<_> = graph
+///     .values()
+///     .flatten()
+///     .filter(|candidate| !graph.contains_key(*candidate))
+///     .collect();
+/// suggestions.sort_by_key(|candidate| candidate.to_string());
+/// suggestions.dedup();
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_contacts_of_contacts<R>(
+    reader: &R,
+    key: &PublicKey,
+    max_fanout: usize,
+) -> Result<HashMap<PublicKey, Vec<PublicKey>>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    let mut direct = reader
+        .fetch_known_contacts(key)
+        .await
+        .map_err(|err| map_transport_error("get_contacts_of_contacts", err))?;
+    direct.retain(|contact| contact != key);
+    direct.sort_by_cached_key(ToString::to_string);
+    direct.dedup();
+
+    let graph = stream::iter(direct)
+        .map(|contact| async move {
+            let mut follows = reader.fetch_known_contacts(&contact).await.ok()?;
+            follows.retain(|follow| follow != key);
+            follows.sort_by_cached_key(ToString::to_string);
+            follows.dedup();
+            Some((contact, follows))
+        })
+        .buffer_unordered(max_fanout.max(1))
+        .filter_map(future::ready)
+        .collect()
+        .await;
+    Ok(graph)
+}
+
+/// Fetches the payment list of each of `owner`'s contacts, keyed by contact.
+///
+/// Contacts are listed first, then their lists are fetched with at most
+/// `concurrency` requests in flight (a concurrency of `0` is treated as `1`).
+///
+/// # Semantics
+/// - A contact whose list cannot be fetched is left out instead of failing the whole
+///   call; a failure to list `owner`'s contacts is returned.
+/// - Contacts that publish nothing map to an empty [`SupportedPayments`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_contacts_payment_lists, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn demo(reader: &(impl UnauthenticatedTransportRead + Sync), pk: &PublicKey) -> paykit_lib::Result<()> {
+/// for (contact, payments) in get_contacts_payment_lists(reader, pk, 8).await? {
+///     println!("{contact} accepts {} methods", payments.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn get_contacts_payment_lists<R>(
+    reader: &R,
+    owner: &PublicKey,
+    concurrency: usize,
+) -> Result<HashMap<PublicKey, SupportedPayments>>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    let contacts = reader
+        .fetch_known_contacts(owner)
+        .await
+        .map_err(|err| map_transport_error("get_contacts_payment_lists", err))?;
+
+    let lists = stream::iter(contacts)
+        .map(|contact| async move {
+            let payments = reader.fetch_supported_payments(&contact).await.ok()?;
+            Some((contact, payments))
+        })
+        .buffer_unordered(concurrency.max(1))
+        .filter_map(future::ready)
+        .collect()
+        .await;
+    Ok(lists)
+}
+
+/// Adds `contact` to the authenticated identity's known contacts.
+///
+/// With the Pubky adapter this writes an empty marker file under
+/// `/pub/pubky.app/follows/`, so the contact is visible to [`get_known_contacts`].
+pub async fn add_contact<S>(client: &S, contact: &PublicKey) -> Result<()>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    client
+        .add_contact(contact)
+        .await
+        .map_err(|err| map_transport_error("add_contact", err))
+}
+
+/// Adds `contact` unless it is already a known contact, and reports whether it wrote.
+///
+/// # Semantics
+/// - Returns `Ok(false)` without writing when `contact` is already followed, and
+///   `Ok(true)` after adding it.
+/// - The check reads `reader`'s view of the contacts of
+///   [`AuthenticatedTransport::public_key`] when given, which requires a transport that
+///   knows its key. Without one it requires a transport that can list its own contacts.
+///   Otherwise returns `PaykitError::Unimplemented`.
+/// - The check and the write are separate requests, so a concurrent writer can still
+///   add the same contact in between; the second write is then merely redundant.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{add_contact_if_absent, AuthenticatedTransport, PublicKey};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # async fn demo(
+/// #     client: &(impl AuthenticatedTransport + Sync),
+/// #     reader: &(impl UnauthenticatedTransportRead + Sync),
+/// #     friend: &PublicKey,
+/// # ) -> paykit_lib::Result<()> {
+/// if !add_contact_if_absent(client, Some(reader), friend).await? {
+///     println!("already following {friend}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub async fn add_contact_if_absent<S>(
+    client: &S,
+    reader: Option<&(dyn UnauthenticatedTransportRead + Sync)>,
+    contact: &PublicKey,
+) -> Result<bool>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    let known = match reader {
+        Some(reader) => match client.public_key() {
+            Ok(owner) => reader.fetch_known_contacts(&owner).await,
+            Err(err) => Err(err),
+        },
+        None => client.list_own_contacts().await,
+    }
+    .map_err(|err| map_transport_error("add_contact_if_absent", err))?;
+    if known.contains(contact) {
+        return Ok(false);
+    }
+
+    client
+        .add_contact(contact)
+        .await
+        .map_err(|err| map_transport_error("add_contact_if_absent", err))?;
+    Ok(true)
+}
+
+/// Removes `contact` from the authenticated identity's known contacts.
+pub async fn remove_contact<S>(client: &S, contact: &PublicKey) -> Result<()>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    client
+        .remove_contact(contact)
+        .await
+        .map_err(|err| map_transport_error("remove_contact", err))
+}
+
+/// Removes every known contact of the authenticated identity and returns how many were
+/// deleted.
+///
+/// # Semantics
+/// - Requires a transport that can list its own contacts; otherwise returns
+///   `PaykitError::Unimplemented`.
+/// - An empty follow list returns `Ok(0)`. A contact that disappears between the listing
+///   and its delete (404/410) is skipped and not counted.
+/// - Stops at the first other failure; contacts deleted before it stay deleted, so
+///   calling again finishes the job.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{clear_contacts, AuthenticatedTransport};
+/// # async fn offboard(client: &(impl AuthenticatedTransport + Sync)) -> paykit_lib::Result<()> {
+/// let removed = clear_contacts(client).await?;
+/// println!("removed {removed} contacts");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn clear_contacts<S>(client: &S) -> Result<usize>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    let contacts = client
+        .list_own_contacts()
+        .await
+        .map_err(|err| map_transport_error("clear_contacts", err))?;
+
+    let mut removed = 0;
+    for contact in &contacts {
+        match client.remove_contact(contact).await {
+            Ok(()) => removed += 1,
+            Err(err) if err.is_not_found() => {}
+            Err(err) => return Err(map_transport_error("clear_contacts", err)),
+        }
+    }
+    Ok(removed)
+}
+
+/// Returns known contacts plus the raw follow entry names that failed to parse.
+///
+/// Useful for diagnosing corrupted or stray files under the follows path without
+/// losing the contacts that did parse.
+pub async fn get_known_contacts_with_report<R>(
+    reader: &R,
+    key: &PublicKey,
+) -> Result<(Vec<PublicKey>, Vec<String>)>
+where
+    R: UnauthenticatedTransportRead + Sync + ?Sized,
+{
+    reader
+        .fetch_known_contacts_with_report(key)
+        .await
+        .map_err(|err| map_transport_error("get_known_contacts_with_report", err))
+}
+
+/// Parses each of `inputs` as a public key, separating the keys that parsed from the
+/// inputs that did not.
+///
+/// # Semantics
+/// - Surrounding whitespace is ignored. With the `pubky` feature any form accepted by
+///   the SDK's key parser works (e.g. a bare key, `pk:`-prefixed, or a `pubky://` URL);
+///   otherwise see `PublicKey::parse`.
+/// - Both lists keep the order of `inputs`, and duplicates are not removed.
+/// - Every failure is `PaykitError::InvalidData`, paired with the input it came from.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::parse_public_keys;
+/// let (keys, rejected) = parse_public_keys(&[
+///     "o4dksfbqk85ogzdb5osziw6befigbuxmuxkuxq8434q89uj56uyy",
+///     "not-a-key",
+/// ]);
+/// assert_eq!(keys.len(), 1);
+/// assert_eq!(rejected[0].0, "not-a-key");
+/// ```
+pub fn parse_public_keys(inputs: &[&str]) -> (Vec<PublicKey>, Vec<(String, PaykitError)>) {
+    let mut keys = Vec::new();
+    let mut rejected = Vec::new();
+    for input in inputs {
+        match parse_public_key(input) {
+            Ok(key) => keys.push(key),
+            Err(err) => rejected.push((input.to_string(), err)),
+        }
+    }
+    (keys, rejected)
+}
+
+#[cfg(feature = "pubky")]
+fn parse_public_key(input: &str) -> Result<PublicKey> {
+    let trimmed = input.trim();
+    trimmed
+        .parse()
+        .map_err(|err| PaykitError::InvalidData(format!("public key {trimmed:?}: {err}")))
+}
+
+#[cfg(not(feature = "pubky"))]
+fn parse_public_key(input: &str) -> Result<PublicKey> {
+    PublicKey::parse(input)
+}
+
+/// Reports a failure that happened before any response arrived as
+/// `PaykitError::Network`, keeping the messages of the whole error chain.
+#[cfg(any(feature = "pubky", feature = "wasm"))]
+fn network_error(err: &dyn std::error::Error) -> PaykitError {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    PaykitError::Network(message)
+}
+
+/// Prefixes `Transport` messages with the helper name; other variants pass through.
+fn map_transport_error(label: &'static str, err: PaykitError) -> PaykitError {
+    match err {
+        PaykitError::Transport(msg) => PaykitError::Transport(format!("{label}: {msg}")),
+        PaykitError::Network(msg) => PaykitError::Network(format!("{label}: {msg}")),
+        PaykitError::Server { status, message } => PaykitError::Server {
+            status,
+            message: format!("{label}: {message}"),
+        },
+        PaykitError::Unauthorized(msg) => PaykitError::Unauthorized(format!("{label}: {msg}")),
+        _ => err,
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use super::*;
+
+    fn http(status: u16) -> PaykitError {
+        PaykitError::Server {
+            status,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn not_found_covers_404_and_410_only() {
+        assert!(http(404).is_not_found());
+        assert!(http(410).is_not_found());
+        assert!(!http(500).is_not_found());
+        assert!(!PaykitError::Transport("404".into()).is_not_found());
+    }
+
+    #[test]
+    fn transient_failures_are_network_level() {
+        assert!(PaykitError::Network("connection refused".into()).is_transient());
+        assert!(PaykitError::Timeout("slow".into()).is_transient());
+        assert!(PaykitError::TransportSource("refused".into()).is_transient());
+        assert!(http(502).is_transient());
+        assert!(http(429).is_transient());
+        let limited = PaykitError::RateLimited { retry_after: None };
+        assert!(limited.is_transient());
+        assert_eq!(limited.status_code(), Some(429));
+
+        assert!(!http(404).is_transient());
+        assert!(!PaykitError::Transport("no homeserver".into()).is_transient());
+        assert!(!PaykitError::InvalidData("utf-8".into()).is_transient());
+        assert!(!PaykitError::Unimplemented("x").is_transient());
+        assert!(!PaykitError::PayloadTooLarge {
+            limit: 1,
+            actual: 2
+        }
+        .is_transient());
+    }
+}
+
+#[cfg(test)]
+mod method_id_tests {
+    use super::*;
+
+    #[test]
+    fn accepts_lightning() {
+        let method = MethodId::new("lightning").unwrap();
+        assert_eq!(method, MethodId("lightning".into()));
+    }
+
+    #[test]
+    fn rejects_traversal() {
+        for id in ["../../secret", "..", ".", "a/b", "a\\b"] {
+            assert!(
+                matches!(MethodId::new(id), Err(PaykitError::InvalidMethodId(_))),
+                "{id:?} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_null_and_control_bytes() {
+        assert!(MethodId::new("light\0ning").is_err());
+        assert!(MethodId::new("light\nning").is_err());
+    }
+
+    #[test]
+    fn displays_and_borrows_as_str() {
+        let method = MethodId::new("lightning").unwrap();
+        assert_eq!(format!("{method}"), "lightning");
+        assert_eq!(method.as_ref(), "lightning");
+        assert!(method.starts_with("light"));
+
+        let mut entries = HashMap::new();
+        entries.insert(method, EndpointData("ln".into()));
+        assert!(entries.contains_key("lightning"));
+    }
+
+    #[test]
+    fn rejects_empty_and_overlong() {
+        assert!(MethodId::new("").is_err());
+        assert!(MethodId::new("a".repeat(MethodId::MAX_LEN)).is_ok());
+        assert!(MethodId::new("a".repeat(MethodId::MAX_LEN + 1)).is_err());
+    }
+}
+
+#[cfg(all(test, not(feature = "pubky")))]
+mod public_key_tests {
+    use super::*;
+
+    const KEY: &str = "o4dksfbqk85ogzdb5osziw6befigbuxmuxkuxq8434q89uj56uyy";
+
+    #[test]
+    fn valid_keys_are_normalized() {
+        assert_eq!(PublicKey::parse(KEY).unwrap().0, KEY);
+        let prefixed = format!("  pubky{}  ", KEY.to_ascii_uppercase());
+        assert_eq!(PublicKey::parse(&prefixed).unwrap().0, KEY);
+    }
+
+    #[test]
+    fn malformed_keys_are_rejected() {
+        for input in [
+            "",
+            "test-key-1",
+            &KEY[..51],
+            // `l` is not in the z-base-32 alphabet.
+            &format!("l{}", &KEY[1..]),
+            // Trailing `b` sets a padding bit.
+            &format!("{}b", &KEY[..51]),
+        ] {
+            let err = PublicKey::parse(input).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{input:?}: {err:?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod endpoint_data_tests {
+    use super::*;
+
+    #[test]
+    fn displays_the_stored_payload() {
+        let endpoint = EndpointData("{\"bolt11\":\"ln...\"}".into());
+        assert_eq!(endpoint.as_ref(), "{\"bolt11\":\"ln...\"}");
+        assert_eq!(endpoint.to_string(), endpoint.0);
+
+        let binary = EndpointData::from_bytes(&[0xff]);
+        assert_eq!(
+            format!("{binary}"),
+            format!("{}/w==", EndpointData::BINARY_PREFIX)
+        );
+    }
+
+    #[test]
+    fn accessors_borrow_the_payload() {
+        let data = EndpointData::with_schema(1, "{}");
+        assert_eq!(data.as_str(), "paykit-schema:1\n{}");
+        assert_eq!(data.as_str().as_ptr(), data.0.as_ptr());
+        assert_eq!(data.len(), data.0.len());
+        assert!(!data.is_empty());
+        assert!(EndpointData(String::new()).is_empty());
+    }
+
+    #[test]
+    fn text_payloads_are_not_binary() {
+        let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+        assert!(!data.is_binary());
+        assert_eq!(&*data.as_bytes(), data.0.as_bytes());
+    }
+
+    #[test]
+    fn binary_payloads_round_trip() {
+        let bytes = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0xff];
+        let data = EndpointData::from_bytes(&bytes);
+        assert!(data.0.starts_with(EndpointData::BINARY_PREFIX));
+        assert_eq!(&*data.as_bytes(), &bytes);
+    }
+
+    #[test]
+    fn versioned_payloads_round_trip() {
+        let body = "{\"bolt11\":\"ln...\"}";
+        let data = EndpointData::with_schema(3, body);
+        assert!(data.0.starts_with(EndpointData::SCHEMA_PREFIX));
+        assert_eq!(data.schema_version(), Some(3));
+        assert_eq!(data.body(), body);
+
+        let empty = EndpointData::with_schema(0, "");
+        assert_eq!(empty.schema_version(), Some(0));
+        assert_eq!(empty.body(), "");
+    }
+
+    #[test]
+    fn unversioned_payloads_have_no_schema() {
+        for raw in [
+            "{\"bolt11\":\"ln...\"}",
+            "",
+            "paykit-schema:2",
+            "paykit-schema:\nbody",
+            "paykit-schema:+2\nbody",
+            "paykit-schema:v2\nbody",
+            "paykit-schema:99999999999\nbody",
+        ] {
+            let data = EndpointData(raw.into());
+            assert_eq!(data.schema_version(), None, "{raw:?}");
+            assert_eq!(data.body(), raw);
+        }
+    }
+
+    #[test]
+    fn malformed_binary_body_reads_as_text() {
+        let data = EndpointData(format!("{}not base64!", EndpointData::BINARY_PREFIX));
+        assert!(!data.is_binary());
+        assert_eq!(&*data.as_bytes(), data.0.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod supported_payments_tests {
+    use super::*;
+
+    fn sample() -> SupportedPayments {
+        SupportedPayments {
+            entries: HashMap::from([
+                (MethodId("lightning".into()), EndpointData("ln".into())),
+                (MethodId("onchain".into()), EndpointData("bc1".into())),
+            ]),
+        }
+    }
+
+    #[test]
+    fn lookups_delegate_to_entries() {
+        let payments = sample();
+        let lightning = MethodId("lightning".into());
+        assert_eq!(payments.len(), 2);
+        assert!(!payments.is_empty());
+        assert!(payments.contains(&lightning));
+        assert_eq!(payments.get(&lightning), Some(&EndpointData("ln".into())));
+        assert!(!payments.contains(&MethodId("paypal".into())));
+        assert!(SupportedPayments::default().is_empty());
+    }
+
+    #[test]
+    fn builder_collects_entries_fluently() {
+        let payments = SupportedPayments::builder()
+            .add(
+                MethodId::new("lightning").unwrap(),
+                EndpointData("ln".into()),
+            )
+            .add(
+                MethodId::new("onchain").unwrap(),
+                EndpointData("bc1".into()),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(payments, sample());
+
+        let mut builder = SupportedPayments::builder();
+        builder.add(MethodId("lightning".into()), EndpointData("ln".into()));
+        builder.add(MethodId("../escape".into()), EndpointData("x".into()));
+        builder.add(MethodId(String::new()), EndpointData("y".into()));
+        let err = builder.build().unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::InvalidMethodId(msg) if msg.contains("escape")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn entries_collect_into_a_list() {
+        let payments: SupportedPayments = [
+            (MethodId("lightning".into()), EndpointData("ln".into())),
+            (MethodId("onchain".into()), EndpointData("bc1".into())),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(payments, sample());
+
+        let round_trip: SupportedPayments = sample().into_iter().collect();
+        assert_eq!(round_trip, sample());
+    }
+
+    #[test]
+    fn merge_keeps_or_overwrites_overlapping_methods() {
+        let incoming = || SupportedPayments {
+            entries: HashMap::from([
+                (MethodId("lightning".into()), EndpointData("ln2".into())),
+                (MethodId("onchain".into()), EndpointData("bc1".into())),
+                (MethodId("lnurl".into()), EndpointData("lnurl1".into())),
+            ]),
+        };
+
+        let mut kept = sample();
+        assert_eq!(kept.merge(incoming(), ConflictPolicy::KeepExisting), 1);
+        assert_eq!(kept.len(), 3);
+        assert_eq!(
+            kept.get(&MethodId("lightning".into())),
+            Some(&EndpointData("ln".into()))
+        );
+
+        // `onchain` is identical on both sides, so only `lightning` and `lnurl` change.
+        let mut overwritten = sample();
+        assert_eq!(overwritten.merge(incoming(), ConflictPolicy::Overwrite), 2);
+        assert_eq!(overwritten.len(), 3);
+        assert_eq!(
+            overwritten.get(&MethodId("lightning".into())),
+            Some(&EndpointData("ln2".into()))
+        );
+    }
+
+    #[test]
+    fn merge_of_disjoint_lists_adds_everything() {
+        let other = SupportedPayments {
+            entries: HashMap::from([(MethodId("bolt12".into()), EndpointData("lno".into()))]),
+        };
+        for policy in [ConflictPolicy::KeepExisting, ConflictPolicy::Overwrite] {
+            let mut payments = sample();
+            assert_eq!(payments.merge(other.clone(), policy), 1);
+            assert_eq!(payments.len(), 3);
+            assert_eq!(payments.merge(SupportedPayments::default(), policy), 0);
+        }
+    }
+
+    #[test]
+    fn iterates_by_reference_and_by_value() {
+        let payments = sample();
+        let mut borrowed: Vec<_> = (&payments)
+            .into_iter()
+            .map(|(method, data)| (method.0.as_str(), data.0.as_str()))
+            .collect();
+        borrowed.sort();
+        assert_eq!(borrowed, [("lightning", "ln"), ("onchain", "bc1")]);
+
+        let owned: HashMap<_, _> = payments.clone().into_iter().collect();
+        assert_eq!(owned, payments.entries);
+    }
+
+    #[test]
+    fn sorted_entries_order_by_method_id() {
+        let mut payments = sample();
+        payments
+            .entries
+            .insert(MethodId("bolt12".into()), EndpointData("lno".into()));
+        let ids: Vec<_> = payments
+            .sorted_entries()
+            .into_iter()
+            .map(|(method, _)| method.0.as_str())
+            .collect();
+        assert_eq!(ids, ["bolt12", "lightning", "onchain"]);
+    }
+
+    #[test]
+    fn filter_keeps_matching_entries() {
+        let payments = sample();
+        let lightning = MethodId("lightning".into());
+        let only = payments.filter(|method, _| *method == lightning);
+        assert_eq!(only.len(), 1);
+        assert_eq!(only.get(&lightning), Some(&EndpointData("ln".into())));
+        assert_eq!(payments.len(), 2);
+
+        assert!(payments.filter(|_, data| data.0.is_empty()).is_empty());
+    }
+
+    #[test]
+    fn try_map_values_transforms_or_fails() {
+        let upper = sample()
+            .try_map_values(|_, data| Ok(EndpointData(data.0.to_uppercase())))
+            .unwrap();
+        assert_eq!(
+            upper.get(&MethodId("lightning".into())),
+            Some(&EndpointData("LN".into()))
+        );
+        assert_eq!(
+            upper.get(&MethodId("onchain".into())),
+            Some(&EndpointData("BC1".into()))
+        );
+
+        let err = sample()
+            .try_map_values(|method, data| {
+                if method.0 == "onchain" {
+                    Err(PaykitError::InvalidData("rejected".into()))
+                } else {
+                    Ok(data.clone())
+                }
+            })
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(msg) if msg == "rejected"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn every_format_round_trips() {
+        let mut payments = sample();
+        // Payloads are opaque: quotes and newlines must survive every layout.
+        payments.entries.insert(
+            MethodId("lnurl".into()),
+            EndpointData("paykit-schema:1\n{\"lnurl\":\"alice@example.com\"}".into()),
+        );
+        for format in [
+            PaymentListFormat::JsonObject,
+            PaymentListFormat::JsonArray,
+            PaymentListFormat::NdJson,
+        ] {
+            let encoded = payments.serialize(format);
+            assert_eq!(encoded, payments.clone().serialize(format), "{format:?}");
+            let decoded = SupportedPayments::deserialize(&encoded, format).unwrap();
+            assert_eq!(decoded, payments, "{format:?}");
+
+            let empty = SupportedPayments::default().serialize(format);
+            assert!(SupportedPayments::deserialize(&empty, format)
+                .unwrap()
+                .is_empty());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn formats_have_the_documented_shape() {
+        let payments = sample();
+        assert_eq!(
+            payments.serialize(PaymentListFormat::JsonObject),
+            r#"{"lightning":"ln","onchain":"bc1"}"#
+        );
+        assert_eq!(
+            payments.serialize(PaymentListFormat::JsonArray),
+            r#"[{"method":"lightning","data":"ln"},{"method":"onchain","data":"bc1"}]"#
+        );
+        assert_eq!(
+            payments.serialize(PaymentListFormat::NdJson),
+            "{\"method\":\"lightning\",\"data\":\"ln\"}\n{\"method\":\"onchain\",\"data\":\"bc1\"}\n"
+        );
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let previous = sample();
+        let mut current = sample();
+        current.entries.remove(&MethodId("onchain".into()));
+        current
+            .entries
+            .insert(MethodId("lightning".into()), EndpointData("ln2".into()));
+        current
+            .entries
+            .insert(MethodId("lnurl".into()), EndpointData("url".into()));
+        current
+            .entries
+            .insert(MethodId("bolt12".into()), EndpointData("lno".into()));
+
+        let diff = current.diff(&previous);
+        assert_eq!(
+            diff.added,
+            [MethodId("bolt12".into()), MethodId("lnurl".into())]
+        );
+        assert_eq!(diff.removed, [MethodId("onchain".into())]);
+        assert_eq!(diff.changed, [MethodId("lightning".into())]);
+        assert!(!diff.is_empty());
+
+        // Reversing the direction swaps additions and removals.
+        let reverse = previous.diff(&current);
+        assert_eq!(reverse.added, diff.removed);
+        assert_eq!(reverse.removed, diff.added);
+        assert_eq!(reverse.changed, diff.changed);
+    }
+
+    #[test]
+    fn diff_of_unchanged_lists_is_empty() {
+        let diff = sample().diff(&sample());
+        assert_eq!(diff, PaymentListDiff::default());
+        assert!(diff.is_empty());
+        assert!(SupportedPayments::default()
+            .diff(&SupportedPayments::default())
+            .is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn malformed_exports_are_rejected() {
+        for (format, input) in [
+            (PaymentListFormat::JsonObject, "not json"),
+            (PaymentListFormat::JsonObject, r#"{"lightning":1}"#),
+            (PaymentListFormat::JsonObject, r#"{"ln":"a","ln":"b"}"#),
+            (PaymentListFormat::JsonObject, r#"{"../up":"a"}"#),
+            (PaymentListFormat::JsonArray, r#"{"lightning":"ln"}"#),
+            (PaymentListFormat::JsonArray, r#"[{"method":"ln"}]"#),
+            (
+                PaymentListFormat::JsonArray,
+                r#"[{"method":"ln","data":"a","extra":1}]"#,
+            ),
+            (
+                PaymentListFormat::JsonArray,
+                r#"[{"method":"","data":"a"}]"#,
+            ),
+            (
+                PaymentListFormat::NdJson,
+                "{\"method\":\"ln\",\"data\":\"a\"}\nnope",
+            ),
+            (
+                PaymentListFormat::NdJson,
+                "{\"method\":\"ln\",\"data\":\"a\"}\n{\"method\":\"ln\",\"data\":\"b\"}",
+            ),
+        ] {
+            let err = SupportedPayments::deserialize(input, format).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{format:?} {input}: {err:?}"
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn endpoint_payloads_parse_as_json() {
+        let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+        let value = data.try_as_json().unwrap();
+        assert_eq!(value, serde_json::json!({ "bolt11": "ln..." }));
+        assert_eq!(EndpointData::from_json(&value), data);
+
+        let versioned = EndpointData::with_schema(2, &data.0);
+        assert_eq!(versioned.try_as_json().unwrap(), value);
+    }
+
+    #[test]
+    fn non_json_payloads_are_invalid_data() {
+        for text in ["lnbc1...", "", "{\"bolt11\":"] {
+            let err = EndpointData(text.into()).try_as_json().unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{text:?}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn supported_payments_json_round_trip() {
+        let payments = SupportedPayments {
+            entries: HashMap::from([
+                (
+                    MethodId("lightning".into()),
+                    EndpointData("{\"bolt11\":\"ln...\"}".into()),
+                ),
+                (
+                    MethodId("onchain".into()),
+                    EndpointData("{\"address\":\"bc1...\"}".into()),
+                ),
+            ]),
+        };
+
+        let json = serde_json::to_value(&payments).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "lightning": "{\"bolt11\":\"ln...\"}",
+                "onchain": "{\"address\":\"bc1...\"}",
+            })
+        );
+        let decoded: SupportedPayments = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, payments);
+    }
+}
+
+#[cfg(test)]
+mod helper_tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+
+    fn entry(method: &str, payload: &str) -> (MethodId, EndpointData) {
+        (MethodId::new(method).unwrap(), EndpointData(payload.into()))
+    }
+
+    #[tokio::test]
+    async fn full_fetch_defaults_to_no_metadata() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, data) = entry("lightning", "ln");
+        mock.insert_endpoint(&owner, method.clone(), data.clone());
+
+        let fetched = get_payment_endpoint_full(&mock, &owner, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(FetchedEndpoint::from(data)));
+    }
+
+    #[tokio::test]
+    async fn second_degree_contacts_skip_owner_and_failures() {
+        let mock = MockTransport::new(random_public_key());
+        let owner = random_public_key();
+        let [alice, bob, carol, dave, erin] = std::array::from_fn(|_| random_public_key());
+        for (from, to) in [
+            (&owner, &alice),
+            (&owner, &bob),
+            (&owner, &carol),
+            (&alice, &owner),
+            (&alice, &dave),
+            (&alice, &erin),
+            (&bob, &dave),
+            (&carol, &erin),
+        ] {
+            mock.insert_contact(from, to);
+        }
+        mock.fail_contacts_of(&carol, "carol's homeserver is down");
+
+        let graph = get_contacts_of_contacts(&mock, &owner, 2).await.unwrap();
+
+        let mut alice_follows = vec![dave.clone(), erin];
+        alice_follows.sort_by_key(ToString::to_string);
+        let expected = HashMap::from([(alice, alice_follows), (bob, vec![dave])]);
+        assert_eq!(graph, expected);
+        assert_eq!(mock.call_count("fetch_known_contacts"), 4);
+    }
+
+    #[tokio::test]
+    async fn contacts_payment_lists_fetch_every_contact() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let [alice, bob] = std::array::from_fn(|_| random_public_key());
+        let (lightning, invoice) = entry("lightning", "lnbc1...");
+        let (onchain, address) = entry("onchain", "bc1q...");
+        mock.insert_contact(&owner, &alice);
+        mock.insert_contact(&owner, &bob);
+        mock.insert_endpoint(&alice, lightning.clone(), invoice.clone());
+        mock.insert_endpoint(&bob, onchain.clone(), address.clone());
+
+        let lists = get_contacts_payment_lists(&mock, &owner, 2).await.unwrap();
+
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[&alice].get(&lightning), Some(&invoice));
+        assert_eq!(lists[&alice].len(), 1);
+        assert_eq!(lists[&bob].get(&onchain), Some(&address));
+        assert_eq!(lists[&bob].len(), 1);
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn helpers_accept_type_erased_readers() {
+        let payee = random_public_key();
+        let direct = MockTransport::new(payee.clone());
+        let (method, data) = entry("lightning", "ln");
+        direct.insert_endpoint(&payee, method.clone(), data.clone());
+        let cached = crate::cache::CachingRead::new(direct.clone(), Duration::from_secs(60));
+        let readers: Vec<DynRead> = vec![Arc::new(direct), Arc::new(cached)];
+
+        for reader in &readers {
+            let payments = get_payment_list(&**reader, &payee).await.unwrap();
+            assert_eq!(payments.get(&method), Some(&data));
+            assert_eq!(
+                get_payment_endpoint(reader.as_ref(), &payee, &method)
+                    .await
+                    .unwrap(),
+                Some(data.clone())
+            );
+        }
+    }
+
+    /// Writer that cannot read back its own endpoints or contacts.
+    struct WriteOnly;
+
+    #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+    impl AuthenticatedTransport for WriteOnly {
+        async fn upsert_payment_endpoint(&self, _: &MethodId, _: &EndpointData) -> Result<()> {
+            Ok(())
+        }
+
+        async fn remove_payment_endpoint(&self, _: &MethodId) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn clear_payment_list_removes_every_endpoint() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for (method, data) in [
+            entry("lightning", "ln"),
+            entry("onchain", "bc1"),
+            entry("lnurl", "lnurl1"),
+        ] {
+            mock.insert_endpoint(&owner, method, data);
+        }
+
+        assert_eq!(clear_payment_list(&mock).await.unwrap(), 3);
+        assert!(get_payment_list(&mock, &owner).await.unwrap().is_empty());
+        assert_eq!(clear_payment_list(&mock).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn clear_payment_list_needs_own_listing() {
+        assert!(matches!(
+            clear_payment_list(&WriteOnly).await,
+            Err(PaykitError::Unimplemented(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn clear_contacts_removes_every_follow() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_contact(&owner, &random_public_key());
+        mock.insert_contact(&owner, &random_public_key());
+
+        assert_eq!(clear_contacts(&mock).await.unwrap(), 2);
+        assert!(get_known_contacts(&mock, &owner).await.unwrap().is_empty());
+        assert_eq!(clear_contacts(&mock).await.unwrap(), 0);
+        assert!(matches!(
+            clear_contacts(&WriteOnly).await,
+            Err(PaykitError::Unimplemented(_))
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn list_deadline_spans_listing_and_bodies() {
+        // Lists method ids, then fetches each body in turn, like a homeserver reader.
+        struct Sequential(MockTransport);
+
+        #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+        #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+        impl UnauthenticatedTransportRead for Sequential {
+            async fn fetch_supported_payments(
+                &self,
+                payee: &PublicKey,
+            ) -> Result<SupportedPayments> {
+                let listed = self.0.fetch_supported_payments(payee).await?;
+                let mut entries = HashMap::new();
+                for method in listed.entries.into_keys() {
+                    if let Some(data) = self.0.fetch_payment_endpoint(payee, &method).await? {
+                        entries.insert(method, data);
+                    }
+                }
+                Ok(SupportedPayments { entries })
+            }
+
+            async fn fetch_payment_endpoint(
+                &self,
+                payee: &PublicKey,
+                method: &MethodId,
+            ) -> Result<Option<EndpointData>> {
+                self.0.fetch_payment_endpoint(payee, method).await
+            }
+
+            async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+                self.0.fetch_known_contacts(owner).await
+            }
+        }
+
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for (method, data) in [
+            entry("lightning", "ln"),
+            entry("onchain", "bc1"),
+            entry("lnurl", "lnurl1"),
+        ] {
+            mock.insert_endpoint(&owner, method, data);
+        }
+        mock.set_latency(Duration::from_millis(40));
+        let reader = Sequential(mock.clone());
+
+        // Each call fits the deadline, but the listing plus three bodies do not.
+        let err = get_payment_list_within(&reader, &owner, Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::Timeout(msg) if msg.contains("partial")),
+            "{err:?}"
+        );
+        assert!(mock.call_count("fetch_payment_endpoint") < 3);
+
+        let payments = get_payment_list_within(&reader, &owner, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(payments.len(), 3);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn watch_yields_only_changes() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, first) = entry("lightning", "ln1");
+        mock.insert_endpoint(&owner, method.clone(), first.clone());
+
+        let updates = watch_payment_endpoint(&mock, &owner, &method, Duration::from_millis(10));
+        let mut updates = std::pin::pin!(updates);
+        assert_eq!(updates.next().await.unwrap().unwrap(), Some(first));
+
+        // Polls that see the same content stay silent.
+        let quiet = tokio::time::timeout(Duration::from_millis(50), updates.next()).await;
+        assert!(quiet.is_err());
+        assert!(mock.call_count("fetch_payment_endpoint") > 1);
+
+        let second = EndpointData("ln2".into());
+        mock.insert_endpoint(&owner, method.clone(), second.clone());
+        assert_eq!(updates.next().await.unwrap().unwrap(), Some(second));
+
+        mock.remove_payment_endpoint(&method).await.unwrap();
+        assert_eq!(updates.next().await.unwrap().unwrap(), None);
+
+        let invalid = watch_payment_endpoint(
+            &mock,
+            &owner,
+            MethodId("../x".into()),
+            Duration::from_millis(10),
+        );
+        let results: Vec<_> = invalid.collect().await;
+        assert!(matches!(
+            results.as_slice(),
+            [Err(PaykitError::InvalidMethodId(_))]
+        ));
+    }
+
+    #[tokio::test]
+    async fn bulk_fetch_returns_only_requested_present_methods() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let lightning = entry("lightning", "ln");
+        let onchain = entry("onchain", "bc1");
+        for (method, data) in [lightning.clone(), onchain] {
+            mock.insert_endpoint(&owner, method, data);
+        }
+
+        let wanted = [lightning.0.clone(), MethodId::new("lnurl").unwrap()];
+        let fetched = get_payment_endpoints(&mock, &owner, &wanted).await.unwrap();
+
+        assert_eq!(fetched, HashMap::from([lightning]));
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 2);
+        assert_eq!(mock.call_count("fetch_supported_payments"), 0);
+    }
+
+    #[tokio::test]
+    async fn helpers_accept_payment_methods() {
+        use crate::methods::PaymentMethod;
+
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        set_payment_endpoint(&mock, PaymentMethod::Lightning, EndpointData("ln".into()))
+            .await
+            .unwrap();
+
+        let fetched = get_payment_endpoint(&mock, &owner, &MethodId::new("lightning").unwrap())
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(EndpointData("ln".into())));
+        let alias = PaymentMethod::from(MethodId::new("LN").unwrap());
+        assert!(has_payment_endpoint(&mock, &owner, &alias).await.unwrap());
+        remove_payment_endpoint(&mock, alias).await.unwrap();
+        assert!(
+            !has_payment_endpoint(&mock, &owner, PaymentMethod::Lightning)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn paged_contacts_walk_every_page() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let mut expected: Vec<_> = (0..7).map(|_| random_public_key()).collect();
+        for contact in &expected {
+            mock.insert_contact(&owner, contact);
+        }
+
+        let mut contacts = get_known_contacts_paged(&mock, &owner, 3).await.unwrap();
+        contacts.sort_by_key(ToString::to_string);
+        expected.sort_by_key(ToString::to_string);
+        assert_eq!(contacts, expected);
+        assert_eq!(mock.call_count("fetch_known_contacts_page"), 3);
+    }
+
+    #[tokio::test]
+    async fn cas_rejects_stale_etags() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, _) = entry("lightning", "");
+
+        let first = set_payment_endpoint_cas(&mock, &method, EndpointData("v1".into()), None)
+            .await
+            .unwrap();
+        let second = set_payment_endpoint_cas(
+            &mock,
+            &method,
+            EndpointData("v2".into()),
+            Some(first.clone()),
+        )
+        .await
+        .unwrap();
+        let err = set_payment_endpoint_cas(&mock, &method, EndpointData("v3".into()), Some(first))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::Conflict { actual: Some(tag), .. } if *tag == second),
+            "{err:?}"
+        );
+        let stored = get_payment_endpoint(&mock, &owner, &method).await.unwrap();
+        assert_eq!(stored, Some(EndpointData("v2".into())));
+    }
+
+    #[tokio::test]
+    async fn create_refuses_existing_endpoint() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, data) = entry("lightning", "v1");
+
+        create_payment_endpoint(&mock, Some(&mock), &method, data.clone())
+            .await
+            .unwrap();
+        let err = create_payment_endpoint(&mock, Some(&mock), &method, EndpointData("v2".into()))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::AlreadyExists(id) if *id == method),
+            "{err:?}"
+        );
+        // Without a reader the check falls back to the client's own listing.
+        let err = create_payment_endpoint(&mock, None, &method, EndpointData("v2".into()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::AlreadyExists(_)), "{err:?}");
+
+        let stored = get_payment_endpoint(&mock, &owner, &method).await.unwrap();
+        assert_eq!(stored, Some(data));
+    }
+
+    #[tokio::test]
+    async fn add_contact_if_absent_skips_known_contacts() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let friend = random_public_key();
+
+        assert!(add_contact_if_absent(&mock, Some(&mock), &friend)
+            .await
+            .unwrap());
+        assert!(!add_contact_if_absent(&mock, Some(&mock), &friend)
+            .await
+            .unwrap());
+        // Without a reader the check falls back to the client's own listing.
+        assert!(!add_contact_if_absent(&mock, None, &friend).await.unwrap());
+        assert_eq!(mock.call_count("add_contact"), 1);
+
+        let contacts = get_known_contacts(&mock, &owner).await.unwrap();
+        assert_eq!(contacts, vec![friend]);
+    }
+
+    #[tokio::test]
+    async fn idempotent_remove_reports_what_was_removed() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, data) = entry("lightning", "ln");
+        mock.insert_endpoint(&owner, method.clone(), data);
+
+        assert!(remove_payment_endpoint_idempotent(&mock, &method)
+            .await
+            .unwrap());
+        assert!(!remove_payment_endpoint_idempotent(&mock, &method)
+            .await
+            .unwrap());
+        assert!(!has_payment_endpoint(&mock, &owner, &method).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn health_check_reports_transport_failures() {
+        let mock = MockTransport::new(random_public_key());
+        AuthenticatedTransport::health_check(&mock).await.unwrap();
+
+        mock.fail_next("unreachable");
+        let err = UnauthenticatedTransportRead::health_check(&mock)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn method_count_skips_body_fetches() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for (method, payload) in [
+            ("lightning", "lnbc1..."),
+            ("onchain", "bc1q..."),
+            ("lnurl", "lnurl1..."),
+        ] {
+            let (method, data) = entry(method, payload);
+            mock.insert_endpoint(&owner, method, data);
+        }
+
+        assert_eq!(get_payment_method_count(&mock, &owner).await.unwrap(), 3);
+        assert_eq!(mock.call_count("count_supported_payments"), 1);
+        assert_eq!(mock.call_count("fetch_supported_payments"), 0);
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 0);
+        assert_eq!(
+            get_payment_method_count(&mock, &random_public_key())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn endpoint_or_falls_back_to_next_preference() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (onchain, data) = entry("onchain", "bc1q...");
+        mock.insert_endpoint(&owner, onchain.clone(), data.clone());
+
+        let preferred = [MethodId::new("lightning").unwrap(), onchain.clone()];
+        let picked = get_payment_endpoint_or(&mock, &owner, &preferred)
+            .await
+            .unwrap();
+        assert_eq!(picked, Some((onchain, data)));
+
+        let none = get_payment_endpoint_or(&mock, &owner, &preferred[..1])
+            .await
+            .unwrap();
+        assert_eq!(none, None);
+
+        mock.fail_next("unreachable");
+        let err = get_payment_endpoint_or(&mock, &owner, &preferred)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn content_type_is_advertised_to_readers() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, data) = entry("lightning", "{\"bolt11\":\"ln\"}");
+
+        set_payment_endpoint_with_type(&mock, &method, data.clone(), "application/json")
+            .await
+            .unwrap();
+        let fetched = get_payment_endpoint_full(&mock, &owner, &method)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.data, data);
+        assert_eq!(fetched.content_type.as_deref(), Some("application/json"));
+
+        set_payment_endpoint(&mock, &method, data).await.unwrap();
+        let fetched = get_payment_endpoint_full(&mock, &owner, &method)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.content_type, None);
+    }
+
+    #[test]
+    fn public_keys_are_partitioned_by_validity() {
+        let valid = random_public_key().to_string();
+        let padded = format!("  {valid}\n");
+        let inputs = [valid.as_str(), "", "not-a-key", padded.as_str(), "pk:short"];
+
+        let (keys, rejected) = parse_public_keys(&inputs);
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().all(|key| key.to_string() == valid));
+        let rejected_inputs: Vec<_> = rejected.iter().map(|(input, _)| input.as_str()).collect();
+        assert_eq!(rejected_inputs, ["", "not-a-key", "pk:short"]);
+        assert!(rejected
+            .iter()
+            .all(|(_, err)| matches!(err, PaykitError::InvalidData(_))));
+    }
+
+    #[tokio::test]
+    async fn existence_check_skips_body_download() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (method, data) = entry("lightning", &"x".repeat(1 << 20));
+        mock.insert_endpoint(&owner, method.clone(), data);
+
+        assert!(has_payment_endpoint(&mock, &owner, &method).await.unwrap());
+        let missing = MethodId::new("onchain").unwrap();
+        assert!(!has_payment_endpoint(&mock, &owner, &missing).await.unwrap());
+        assert_eq!(mock.call_count("fetch_payment_endpoint"), 0);
+        assert_eq!(mock.call_count("fetch_payment_endpoint_exists"), 2);
+    }
+
+    #[tokio::test]
+    async fn batch_upsert_writes_every_entry() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let entries = vec![
+            entry("lightning", "{\"bolt11\":\"ln...\"}"),
+            entry("onchain", "{\"address\":\"bc1...\"}"),
+        ];
+
+        set_payment_endpoints(&mock, entries.clone()).await.unwrap();
+
+        let list = get_payment_list(&mock, &owner).await.unwrap();
+        assert_eq!(list.entries, entries.into_iter().collect());
+    }
+
+    #[tokio::test]
+    async fn lenient_list_keeps_readable_methods() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for (method, data) in [
+            entry("lightning", "ln"),
+            entry("onchain", "bc1"),
+            entry("lnurl", "lnurl1"),
+        ] {
+            mock.insert_endpoint(&owner, method, data);
+        }
+        mock.fail_method(MethodId("onchain".into()), "body unavailable");
+
+        let (payments, failures) = get_payment_list_lenient(&mock, &owner).await.unwrap();
+        assert_eq!(payments.len(), 2);
+        assert!(payments.get(&MethodId("lightning".into())).is_some());
+        assert!(payments.get(&MethodId("lnurl".into())).is_some());
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, MethodId("onchain".into()));
+        assert!(
+            matches!(&failures[0].1, PaykitError::Transport(msg) if msg.contains("body unavailable")),
+            "{:?}",
+            failures[0].1
+        );
+
+        // A failed listing still fails the whole call.
+        mock.fail_next("listing down");
+        assert!(get_payment_list_lenient(&mock, &owner).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn batch_upsert_reports_partial_failure() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let entries = vec![
+            entry("lightning", "ln"),
+            entry("onchain", "bc1"),
+            entry("lnurl", "lnurl1"),
+        ];
+        mock.fail_method(entries[1].0.clone(), "homeserver down");
+
+        let err = set_payment_endpoints(&mock, entries.clone())
+            .await
+            .unwrap_err();
+        let PaykitError::Batch(batch) = err else {
+            panic!("expected batch error, got {err:?}");
+        };
+        assert_eq!(
+            batch.succeeded,
+            vec![entries[0].0.clone(), entries[2].0.clone()]
+        );
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, entries[1].0);
+        assert!(matches!(batch.failed[0].1, PaykitError::Transport(_)));
+    }
+
+    #[tokio::test]
+    async fn batch_remove_deletes_every_entry() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let entries = vec![entry("lightning", "ln"), entry("onchain", "bc1")];
+        set_payment_endpoints(&mock, entries.clone()).await.unwrap();
+        let methods: Vec<_> = entries.into_iter().map(|(method, _)| method).collect();
+
+        let removed = remove_payment_endpoints(&mock, methods.clone(), false)
+            .await
+            .unwrap();
+        assert_eq!(removed, methods);
+        assert!(get_payment_list(&mock, &owner).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn batch_remove_reports_every_failure() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let entries = vec![
+            entry("lightning", "ln"),
+            entry("onchain", "bc1"),
+            entry("lnurl", "lnurl1"),
+        ];
+        set_payment_endpoints(&mock, entries.clone()).await.unwrap();
+        mock.fail_method(entries[0].0.clone(), "homeserver down");
+        mock.fail_method(entries[2].0.clone(), "homeserver down");
+        let methods: Vec<_> = entries.into_iter().map(|(method, _)| method).collect();
+
+        let err = remove_payment_endpoints(&mock, methods.clone(), true)
+            .await
+            .unwrap_err();
+        let PaykitError::Batch(batch) = err else {
+            panic!("expected batch error, got {err:?}");
+        };
+        assert_eq!(batch.succeeded, vec![methods[1].clone()]);
+        let failed: Vec<_> = batch.failed.iter().map(|(method, _)| method).collect();
+        assert_eq!(failed, vec![&methods[0], &methods[2]]);
+        assert!(batch
+            .failed
+            .iter()
+            .all(|(_, err)| matches!(err, PaykitError::Transport(_))));
+    }
+
+    #[tokio::test]
+    async fn batch_remove_handles_missing_methods() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let (present, data) = entry("lightning", "ln");
+        let absent = MethodId::new("onchain").unwrap();
+        let methods = vec![present.clone(), absent.clone()];
+
+        mock.insert_endpoint(&owner, present.clone(), data.clone());
+        let err = remove_payment_endpoints(&mock, methods.clone(), false)
+            .await
+            .unwrap_err();
+        let PaykitError::Batch(batch) = err else {
+            panic!("expected batch error, got {err:?}");
+        };
+        assert_eq!(batch.succeeded, vec![present.clone()]);
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, absent);
+        assert!(batch.failed[0].1.is_not_found(), "{:?}", batch.failed[0].1);
+
+        mock.insert_endpoint(&owner, present, data);
+        let removed = remove_payment_endpoints(&mock, methods.clone(), true)
+            .await
+            .unwrap();
+        assert_eq!(removed, methods);
+        assert!(get_payment_list(&mock, &owner).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn set_payment_list_converges_on_desired() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        set_payment_endpoints(
+            &mock,
+            vec![entry("lightning", "old"), entry("onchain", "bc1")],
+        )
+        .await
+        .unwrap();
+
+        let desired = SupportedPayments {
+            entries: vec![entry("lightning", "new"), entry("lnurl", "lnurl1")]
+                .into_iter()
+                .collect(),
+        };
+        set_payment_list(&mock, desired.clone()).await.unwrap();
+
+        assert_eq!(get_payment_list(&mock, &owner).await.unwrap(), desired);
+    }
+
+    #[tokio::test]
+    async fn batch_upsert_validates_before_writing() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let entries = vec![
+            entry("lightning", "ln"),
+            (MethodId("../escape".into()), EndpointData("x".into())),
+        ];
+
+        let err = set_payment_endpoints(&mock, entries).await.unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)));
+        assert!(get_payment_list(&mock, &owner)
+            .await
+            .unwrap()
+            .entries
+            .is_empty());
+    }
+}
+
+/// Tests
+#[cfg(all(test, feature = "pubky"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::transport::pubky::{PaykitPaths, PAYKIT_PATH_PREFIX, PUBKY_FOLLOWS_PATH};
+    use pubky::PubkySession;
+    use pubky_testnet::{pubky::Keypair, EphemeralTestnet};
+
+    struct TestSetup {
+        _testnet: EphemeralTestnet,
+        session_transport: PubkyAuthenticatedTransport,
+        reader_transport: PubkyUnauthenticatedTransport,
+        raw_session: PubkySession,
+        public_key: PublicKey,
+    }
+
+    impl TestSetup {
+        async fn new() -> Self {
+            let testnet = EphemeralTestnet::start().await.unwrap();
+            let homeserver = testnet.homeserver();
+            let sdk = testnet.sdk().unwrap();
+
+            let pair = Keypair::random();
+            let signer = sdk.signer(pair.clone());
+            let session = signer.signup(&homeserver.public_key(), None).await.unwrap();
+
+            let session_transport = PubkyAuthenticatedTransport::new(session.clone());
+            let reader_transport = PubkyUnauthenticatedTransport::new(sdk.public_storage());
+
+            Self {
+                _testnet: testnet,
+                session_transport,
+                reader_transport,
+                raw_session: session,
+                public_key: pair.public_key(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn session_from_recovery_file() {
+        let testnet = EphemeralTestnet::start().await.unwrap();
+        let homeserver = testnet.homeserver().public_key();
+        let sdk = testnet.sdk().unwrap();
+        let keypair = Keypair::random();
+        let file = pubky::recovery_file::create_recovery_file(&keypair, "correct horse");
+
+        let err = PubkyAuthenticatedTransport::from_recovery_file_with_sdk(
+            &sdk,
+            &file,
+            "wrong horse",
+            &homeserver,
+        )
+        .await;
+        assert!(matches!(err, Err(PaykitError::InvalidData(_))));
+
+        // The first use signs the key up; later ones sign in to the published homeserver.
+        let method = MethodId("lightning".into());
+        let endpoint = EndpointData("lnbc1...".into());
+        let signed_up = PubkyAuthenticatedTransport::from_recovery_file_with_sdk(
+            &sdk,
+            &file,
+            "correct horse",
+            &homeserver,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            signed_up.session().info().public_key(),
+            &keypair.public_key()
+        );
+        set_payment_endpoint(&signed_up, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let signed_in = PubkyAuthenticatedTransport::from_recovery_file_with_sdk(
+            &sdk,
+            &file,
+            "correct horse",
+            &homeserver,
+        )
+        .await
+        .unwrap();
+        let published = signed_in.list_own_endpoints().await.unwrap();
+        assert_eq!(published.get(&method), Some(&endpoint));
+
+        signed_in.session().clone().signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn endpoint_round_trip_and_update() {
+        let setup = TestSetup::new().await;
+
+        let method = MethodId("onchain".into());
+        let endpoint = EndpointData("{\"address\":\"bc1...\"}".into());
+
+        set_payment_endpoint(&setup.session_transport, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let fetched = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(endpoint.clone()));
+
+        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            list,
+            SupportedPayments {
+                entries: vec![(method.clone(), endpoint.clone())]
+                    .into_iter()
+                    .collect()
+            }
+        );
+
+        let new_endpoint = EndpointData("{\"address\":\"1c1...\"}".into());
+        set_payment_endpoint(
+            &setup.session_transport,
+            method.clone(),
+            new_endpoint.clone(),
+        )
+        .await
+        .unwrap();
+
+        let updated = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(updated, Some(new_endpoint.clone()));
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_endpoint_returns_none() {
+        let setup = TestSetup::new().await;
+        let method = MethodId("bolt11".into());
+
+        let missing = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert!(missing.is_none());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_reflects_additions_and_removals() {
+        let setup = TestSetup::new().await;
+
+        let onchain = MethodId("onchain".into());
+        let lightning = MethodId("lightning".into());
+        let onchain_data = EndpointData("{\"address\":\"bc1...\"}".into());
+        let lightning_data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+
+        set_payment_endpoint(
+            &setup.session_transport,
+            onchain.clone(),
+            onchain_data.clone(),
+        )
+        .await
+        .unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            lightning.clone(),
+            lightning_data.clone(),
+        )
+        .await
+        .unwrap();
+
+        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        let mut expected = HashMap::new();
+        expected.insert(onchain.clone(), onchain_data.clone());
+        expected.insert(lightning.clone(), lightning_data.clone());
+        assert_eq!(list.entries, expected);
+
+        remove_payment_endpoint(&setup.session_transport, onchain.clone())
+            .await
+            .unwrap();
+        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(
+            list.entries,
+            vec![(lightning.clone(), lightning_data.clone())]
+                .into_iter()
+                .collect()
+        );
+
+        remove_payment_endpoint(&setup.session_transport, lightning.clone())
+            .await
+            .unwrap();
+        let empty = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert!(empty.entries.is_empty());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn removing_missing_endpoint_is_error() {
+        let setup = TestSetup::new().await;
+        let method = MethodId("unused".into());
+
+        let err = remove_payment_endpoint(&setup.session_transport, &method)
+            .await
+            .expect_err("removing non-existent endpoint should fail");
+        assert!(err.is_not_found(), "{err:?}");
+        let removed = remove_payment_endpoint_idempotent(&setup.session_transport, &method)
+            .await
+            .unwrap();
+        assert!(!removed);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn health_check_succeeds_against_testnet() {
+        let setup = TestSetup::new().await;
+
+        setup.session_transport.health_check().await.unwrap();
+        setup.reader_transport.health_check().await.unwrap();
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reader_health_check_probes_the_mirror() {
+        let setup = TestSetup::new().await;
+        let mirror = setup._testnet.homeserver().icann_http_url();
+        let reader = setup.reader_transport.clone().with_mirror(mirror);
+        reader.health_check().await.unwrap();
+
+        // Nothing listens on port 1.
+        let dead = "http://127.0.0.1:1/".parse().unwrap();
+        let reader = setup.reader_transport.clone().with_mirror(dead);
+        let err = reader.health_check().await.unwrap_err();
+        assert!(matches!(err, PaykitError::Network(_)), "{err:?}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn helpers_reject_unvalidated_method_ids() {
+        let setup = TestSetup::new().await;
+        let escape = MethodId("../escape".into());
+
+        let err = set_payment_endpoint(
+            &setup.session_transport,
+            escape.clone(),
+            EndpointData("x".into()),
+        )
+        .await
+        .expect_err("traversal should be rejected before writing");
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)));
+
+        let err = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &escape)
+            .await
+            .expect_err("traversal should be rejected before reading");
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)));
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn non_utf8_endpoint_is_invalid_data() {
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("binary").unwrap();
+
+        setup
+            .raw_session
+            .storage()
+            .put(
+                format!("{PAYKIT_PATH_PREFIX}{}", method.0),
+                vec![0xff, 0xfe, 0xfd],
+            )
+            .await
+            .unwrap();
+
+        let err = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+            .await
+            .expect_err("non-UTF-8 body should not decode");
+        match err {
+            PaykitError::InvalidData(msg) => assert!(msg.contains("/binary"), "{msg}"),
+            other => panic!("expected InvalidData, got {other:?}"),
+        }
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn lists_known_contacts() {
+        let setup = TestSetup::new().await;
+
+        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert!(contacts.is_empty());
+
+        // Seed two contacts under the follows path using the authenticated session.
+        let contact_a = Keypair::random().public_key();
+        let contact_b = Keypair::random().public_key();
+        setup
+            .raw_session
+            .storage()
+            .put(format!("{PUBKY_FOLLOWS_PATH}{}", contact_a), "")
+            .await
+            .unwrap();
+        setup
+            .raw_session
+            .storage()
+            .put(format!("{PUBKY_FOLLOWS_PATH}{}", contact_b), "")
+            .await
+            .unwrap();
+
+        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+
+        assert!(contacts.contains(&contact_a));
+        assert!(contacts.contains(&contact_b));
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn invalid_contact_entries_are_skipped() {
+        let setup = TestSetup::new().await;
+
+        let contact = Keypair::random().public_key();
+        for name in [contact.to_string(), "not-a-key".to_string()] {
+            setup
+                .raw_session
+                .storage()
+                .put(format!("{PUBKY_FOLLOWS_PATH}{name}"), "")
+                .await
+                .unwrap();
+        }
+
+        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(contacts, vec![contact.clone()]);
+
+        let (contacts, skipped) =
+            get_known_contacts_with_report(&setup.reader_transport, &setup.public_key)
+                .await
+                .unwrap();
+        assert_eq!(contacts, vec![contact]);
+        assert_eq!(skipped, vec!["not-a-key".to_string()]);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn set_payment_list_adds_updates_and_removes() {
+        let setup = TestSetup::new().await;
+
+        let onchain = MethodId::new("onchain").unwrap();
+        let lightning = MethodId::new("lightning").unwrap();
+        let lnurl = MethodId::new("lnurl").unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            onchain.clone(),
+            EndpointData("{\"address\":\"bc1...\"}".into()),
+        )
+        .await
+        .unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            lightning.clone(),
+            EndpointData("{\"bolt11\":\"old\"}".into()),
+        )
+        .await
+        .unwrap();
+
+        let desired = SupportedPayments {
+            entries: HashMap::from([
+                (
+                    lightning.clone(),
+                    EndpointData("{\"bolt11\":\"new\"}".into()),
+                ),
+                (lnurl.clone(), EndpointData("lnurl1...".into())),
+            ]),
+        };
+        set_payment_list(&setup.session_transport, desired.clone())
+            .await
+            .unwrap();
+
+        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(list, desired);
+        assert_eq!(
+            setup.session_transport.list_own_endpoints().await.unwrap(),
+            desired
+        );
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_list_matches_sequential() {
+        let setup = TestSetup::new().await;
+
+        let expected: HashMap<_, _> = (0..20)
+            .map(|i| {
+                (
+                    MethodId::new(format!("method-{i:02}")).unwrap(),
+                    EndpointData(format!("{{\"index\":{i}}}")),
+                )
+            })
+            .collect();
+        set_payment_endpoints(&setup.session_transport, expected.clone())
+            .await
+            .unwrap();
+
+        let sequential = setup.reader_transport.clone().with_fetch_concurrency(1);
+        let concurrent = setup.reader_transport.clone().with_fetch_concurrency(8);
+        let seq_list = get_payment_list(&sequential, &setup.public_key)
+            .await
+            .unwrap();
+        let par_list = get_payment_list(&concurrent, &setup.public_key)
+            .await
+            .unwrap();
+
+        assert_eq!(seq_list.entries, expected);
+        assert_eq!(par_list, seq_list);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn contacts_round_trip() {
+        let setup = TestSetup::new().await;
+        let contact_a = Keypair::random().public_key();
+        let contact_b = Keypair::random().public_key();
+
+        add_contact(&setup.session_transport, &contact_a)
+            .await
+            .unwrap();
+        add_contact(&setup.session_transport, &contact_b)
+            .await
+            .unwrap();
+        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(contacts.len(), 2);
+        assert!(contacts.contains(&contact_a));
+        assert!(contacts.contains(&contact_b));
+
+        remove_contact(&setup.session_transport, &contact_a)
+            .await
+            .unwrap();
+        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(contacts, vec![contact_b]);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn clear_contacts_empties_follow_list() {
+        let setup = TestSetup::new().await;
+        for _ in 0..2 {
+            add_contact(&setup.session_transport, &Keypair::random().public_key())
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(clear_contacts(&setup.session_transport).await.unwrap(), 2);
+        assert!(
+            get_known_contacts(&setup.reader_transport, &setup.public_key)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(clear_contacts(&setup.session_transport).await.unwrap(), 0);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn contacts_under_custom_follows_path() {
+        let setup = TestSetup::new().await;
+        let paths = PaykitPaths::default().with_follows("/pub/wallet.example/contacts/");
+        let writer =
+            PubkyAuthenticatedTransport::new_with_paths(setup.raw_session.clone(), paths.clone());
+        let reader = PubkyUnauthenticatedTransport::new_with_paths(
+            setup.reader_transport.inner().clone(),
+            paths,
+        );
+
+        let contact = Keypair::random().public_key();
+        add_contact(&writer, &contact).await.unwrap();
+
+        let contacts = get_known_contacts(&reader, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(contacts, vec![contact]);
+        let default_contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert!(default_contacts.is_empty());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn custom_paths_are_isolated_from_default() {
+        let setup = TestSetup::new().await;
+        let paths = PaykitPaths::new("/pub/paykit.app/", "v1");
+        let writer =
+            PubkyAuthenticatedTransport::new_with_paths(setup.raw_session.clone(), paths.clone());
+        let custom_reader = PubkyUnauthenticatedTransport::new_with_paths(
+            setup.reader_transport.inner().clone(),
+            paths,
+        );
+
+        let method = MethodId::new("lightning").unwrap();
+        let endpoint = EndpointData("{\"bolt11\":\"ln-v1\"}".into());
+        set_payment_endpoint(&writer, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let fetched = get_payment_endpoint(&custom_reader, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(endpoint.clone()));
+        let list = get_payment_list(&custom_reader, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(list.entries, HashMap::from([(method.clone(), endpoint)]));
+
+        let default_list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert!(default_list.entries.is_empty());
+        assert!(
+            get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn method_count_matches_published_endpoints() {
+        let setup = TestSetup::new().await;
+        assert_eq!(
+            get_payment_method_count(&setup.reader_transport, &setup.public_key)
+                .await
+                .unwrap(),
+            0
+        );
+
+        for method in ["lightning", "onchain", "lnurl"] {
+            set_payment_endpoint(
+                &setup.session_transport,
+                MethodId::new(method).unwrap(),
+                EndpointData(format!("{{\"{method}\":\"...\"}}")),
+            )
+            .await
+            .unwrap();
+        }
+        let count = get_payment_method_count(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn pubky_resolver_points_pooled_reads_at_the_homeserver() {
+        use crate::pool::{PooledRead, PubkyHomeserverResolver};
+
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        let endpoint = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+        set_payment_endpoint(&setup.session_transport, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let resolver = PubkyHomeserverResolver::new(setup._testnet.sdk().unwrap());
+        let url = resolver.homeserver_url(&setup.public_key).await.unwrap();
+        // The testnet homeserver publishes `localhost` for its 127.0.0.1 listener.
+        let listener = setup._testnet.homeserver().icann_http_url();
+        assert_eq!(
+            url.as_str(),
+            format!("http://localhost:{}/", listener.port().unwrap())
+        );
+        assert!(resolver
+            .homeserver_url(&Keypair::random().public_key())
+            .await
+            .is_err());
+
+        let pooled = PooledRead::new(resolver, std::time::Duration::from_secs(60));
+        let fetched = get_payment_endpoint(&pooled, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(endpoint.clone()));
+        let payments = get_payment_list(&pooled, &setup.public_key).await.unwrap();
+        assert_eq!(payments.get(&method), Some(&endpoint));
+        assert_eq!(pooled.len(), 1);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn endpoints_read_from_mirror_url() {
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        let endpoint = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+        set_payment_endpoint(&setup.session_transport, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let mirror = setup._testnet.homeserver().icann_http_url();
+        let direct = setup
+            .reader_transport
+            .fetch_payment_endpoint_from(&mirror, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(direct, Some(endpoint.clone()));
+        let missing = setup
+            .reader_transport
+            .fetch_payment_endpoint_from(
+                &mirror,
+                &setup.public_key,
+                &MethodId::new("onchain").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+
+        let mirrored = setup.reader_transport.clone().with_mirror(mirror);
+        let fetched = get_payment_endpoint(&mirrored, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(endpoint.clone()));
+        let full = mirrored
+            .fetch_payment_endpoint_full(&setup.public_key, &method)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(full.data, endpoint);
+        assert!(full.last_modified.is_some());
+        assert!(mirrored
+            .fetch_payment_endpoint_exists(&setup.public_key, &method)
+            .await
+            .unwrap());
+        let onchain = MethodId::new("onchain").unwrap();
+        assert!(!mirrored
+            .fetch_payment_endpoint_exists(&setup.public_key, &onchain)
+            .await
+            .unwrap());
+
+        // Listings, their bodies, and contacts come from the mirror as well.
+        let contact = Keypair::random().public_key();
+        add_contact(&setup.session_transport, &contact)
+            .await
+            .unwrap();
+        let payments = get_payment_list(&mirrored, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(payments.get(&method), Some(&endpoint));
+        assert_eq!(payments.len(), 1);
+        assert_eq!(
+            mirrored
+                .count_supported_payments(&setup.public_key)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            get_known_contacts(&mirrored, &setup.public_key)
+                .await
+                .unwrap(),
+            vec![contact.clone()]
+        );
+        let (page, next) = mirrored
+            .fetch_known_contacts_page(&setup.public_key, None, 10)
+            .await
+            .unwrap();
+        assert_eq!((page, next), (vec![contact], None));
+
+        // A set mirror is used instead of resolution, so an unreachable one fails.
+        let unreachable = setup
+            .reader_transport
+            .clone()
+            .with_mirror("http://127.0.0.1:1".parse().unwrap());
+        assert!(
+            get_payment_endpoint(&unreachable, &setup.public_key, &method)
+                .await
+                .is_err()
+        );
+        assert!(unreachable
+            .fetch_payment_endpoint_full(&setup.public_key, &method)
+            .await
+            .is_err());
+        assert!(unreachable
+            .fetch_payment_endpoint_exists(&setup.public_key, &method)
+            .await
+            .is_err());
+        let err = get_payment_list(&unreachable, &setup.public_key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::Network(_)), "{err:?}");
+        assert!(get_known_contacts(&unreachable, &setup.public_key)
+            .await
+            .is_err());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_through_injected_http_clients() {
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        let endpoint = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+        set_payment_endpoint(&setup.session_transport, method.clone(), endpoint.clone())
+            .await
+            .unwrap();
+
+        let sdk_client = setup._testnet.sdk().unwrap().client().clone();
+        let http = reqwest::Client::builder()
+            .user_agent("paykit-test")
+            .build()
+            .unwrap();
+        let reader = PubkyUnauthenticatedTransport::with_http_client(sdk_client.clone(), http);
+        let fetched = get_payment_endpoint(&reader, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(fetched, Some(endpoint.clone()));
+
+        let mirror = setup._testnet.homeserver().icann_http_url();
+        let direct = reader
+            .fetch_payment_endpoint_from(&mirror, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(direct, Some(endpoint));
+
+        // Direct reads go through the injected client, proxy included.
+        let proxied = reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all("http://127.0.0.1:1").unwrap())
+            .build()
+            .unwrap();
+        let reader = PubkyUnauthenticatedTransport::with_http_client(sdk_client, proxied);
+        assert!(reader
+            .fetch_payment_endpoint_from(&mirror, &setup.public_key, &method)
+            .await
+            .is_err());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn strict_empty_reports_blank_endpoints() {
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            method.clone(),
+            EndpointData(String::new()),
+        )
+        .await
+        .unwrap();
+
+        let lenient = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+            .await
+            .unwrap();
+        assert_eq!(lenient, None);
+
+        let strict = setup.reader_transport.clone().with_strict_empty(true);
+        let err = get_payment_endpoint(&strict, &setup.public_key, &method)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::InvalidData(msg) if msg.ends_with("empty endpoint body")),
+            "{err:?}"
+        );
+        let missing = get_payment_endpoint(
+            &strict,
+            &setup.public_key,
+            &MethodId::new("onchain").unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(missing, None);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn session_transport_reports_its_public_key() {
+        let setup = TestSetup::new().await;
+        let key = setup.session_transport.public_key().unwrap();
+        assert_eq!(key, setup.public_key);
+
+        let shared = crate::shared::SharedAuth::new(setup.session_transport.clone());
+        assert_eq!(shared.public_key().unwrap(), setup.public_key);
+        let err = crate::noop::NoopTransport.public_key().unwrap_err();
+        assert!(matches!(err, PaykitError::Unimplemented(_)), "{err:?}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn raw_files_are_read_from_the_paykit_directory() {
+        let setup = TestSetup::new().await;
+        setup
+            .raw_session
+            .storage()
+            .put(
+                format!("{PAYKIT_PATH_PREFIX}profile.json"),
+                "{\"name\":\"alice\"}",
+            )
+            .await
+            .unwrap();
+        let reader = &setup.reader_transport;
+
+        let profile = reader
+            .fetch_raw(&setup.public_key, "profile.json")
+            .await
+            .unwrap();
+        assert_eq!(profile, Some(EndpointData("{\"name\":\"alice\"}".into())));
+        let missing = reader
+            .fetch_raw(&setup.public_key, "extensions/missing.json")
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+
+        let err = reader
+            .fetch_raw(&setup.public_key, "../../pubky.app/follows")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[cfg(feature = "integrity")]
+    #[tokio::test]
+    async fn checksum_sidecars_stay_out_of_listings() {
+        use crate::integrity::{get_payment_endpoint_verified, set_payment_endpoint_with_checksum};
+
+        let setup = TestSetup::new().await;
+        let lightning = MethodId::new("lightning").unwrap();
+        set_payment_endpoint_with_checksum(
+            &setup.session_transport,
+            lightning.clone(),
+            EndpointData("lnbc1...".into()),
+        )
+        .await
+        .unwrap();
+
+        let reader = &setup.reader_transport;
+        let payments = get_payment_list(reader, &setup.public_key).await.unwrap();
+        assert_eq!(payments.len(), 1);
+        assert!(payments.contains(&lightning));
+        assert_eq!(
+            get_payment_method_count(reader, &setup.public_key)
+                .await
+                .unwrap(),
+            1
+        );
+        let checked = get_payment_endpoint_verified(reader, &setup.public_key, lightning)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(checked.verified);
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[cfg(feature = "integrity")]
+    #[tokio::test]
+    async fn removing_an_endpoint_deletes_its_sidecar() {
+        use crate::integrity::{set_payment_endpoint_with_checksum, sidecar_method};
+
+        let setup = TestSetup::new().await;
+        let lightning = MethodId::new("lightning").unwrap();
+        let onchain = MethodId::new("onchain").unwrap();
+        set_payment_endpoint_with_checksum(
+            &setup.session_transport,
+            lightning.clone(),
+            EndpointData("lnbc1...".into()),
+        )
+        .await
+        .unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            onchain.clone(),
+            EndpointData("bc1q...".into()),
+        )
+        .await
+        .unwrap();
+        let reader = &setup.reader_transport;
+        assert_eq!(
+            get_payment_list(reader, &setup.public_key)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        remove_payment_endpoint(&setup.session_transport, lightning.clone())
+            .await
+            .unwrap();
+        // No sidecar was published for onchain.
+        remove_payment_endpoint(&setup.session_transport, onchain)
+            .await
+            .unwrap();
+
+        let sidecar = sidecar_method(&lightning).unwrap();
+        for method in [&lightning, &sidecar] {
+            let endpoint = get_payment_endpoint(reader, &setup.public_key, method)
+                .await
+                .unwrap();
+            assert_eq!(endpoint, None, "{method}");
+        }
+        assert!(get_payment_list(reader, &setup.public_key)
+            .await
+            .unwrap()
+            .is_empty());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn recursive_listing_finds_nested_methods() {
+        let setup = TestSetup::new().await;
+        set_payment_endpoint(
+            &setup.session_transport,
+            MethodId::new("onchain").unwrap(),
+            EndpointData("bc1q...".into()),
+        )
+        .await
+        .unwrap();
+        setup
+            .raw_session
+            .storage()
+            .put(format!("{PAYKIT_PATH_PREFIX}lightning/mainnet"), "lnbc1...")
+            .await
+            .unwrap();
+        let nested = MethodId("lightning/mainnet".into());
+
+        let shallow = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(shallow.len(), 1);
+        assert!(!shallow.contains(&nested));
+
+        let recursive = setup.reader_transport.clone().with_recursive(true);
+        let deep = get_payment_list(&recursive, &setup.public_key)
+            .await
+            .unwrap();
+        assert_eq!(deep.len(), 2);
+        assert_eq!(deep.get(&nested), Some(&EndpointData("lnbc1...".into())));
+        assert_eq!(
+            get_payment_method_count(&recursive, &setup.public_key)
+                .await
+                .unwrap(),
+            2
+        );
+
+        // Nested ids can only be listed; the single-endpoint helpers reject them.
+        let err = get_payment_endpoint(&recursive, &setup.public_key, &nested)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)), "{err:?}");
+        let err = set_payment_endpoint(
+            &setup.session_transport,
+            nested,
+            EndpointData("lnbc2...".into()),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidMethodId(_)), "{err:?}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn case_insensitive_listing_folds_method_ids() {
+        let setup = TestSetup::new().await;
+        let folding = setup
+            .reader_transport
+            .clone()
+            .with_case_insensitive_methods(true);
+
+        let endpoint = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+        set_payment_endpoint(
+            &setup.session_transport,
+            MethodId::new("Lightning").unwrap(),
+            endpoint.clone(),
+        )
+        .await
+        .unwrap();
+
+        let lightning = MethodId::new("lightning").unwrap();
+        let exact = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .await
+            .unwrap();
+        assert!(exact.get(&lightning).is_none());
+        let folded = get_payment_list(&folding, &setup.public_key).await.unwrap();
+        assert_eq!(folded.get(&lightning), Some(&endpoint));
+
+        set_payment_endpoint(&setup.session_transport, lightning, endpoint)
+            .await
+            .unwrap();
+        let err = get_payment_list(&folding, &setup.public_key)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+
+        setup.raw_session.signout().await.unwrap();
     }
-}
 
-/// Tests
-#[cfg(all(test, feature = "pubky"))]
-mod tests {
-    use std::collections::HashMap;
+    #[tokio::test]
+    async fn filtered_list_only_returns_matching_methods() {
+        let setup = TestSetup::new().await;
+        let entries = [
+            ("lightning.bolt11", "lnbc1..."),
+            ("lightning.lnurl", "lnurl1..."),
+            ("lightning", "plain"),
+            ("onchain", "bc1q..."),
+        ];
+        for (method, payload) in entries {
+            set_payment_endpoint(
+                &setup.session_transport,
+                MethodId::new(method).unwrap(),
+                EndpointData(payload.into()),
+            )
+            .await
+            .unwrap();
+        }
 
-    use super::*;
-    use crate::transport::pubky::PUBKY_FOLLOWS_PATH;
-    use pubky::PubkySession;
-    use pubky_testnet::{pubky::Keypair, EphemeralTestnet};
+        let filtered =
+            get_payment_list_filtered(&setup.reader_transport, &setup.public_key, "lightning.")
+                .await
+                .unwrap();
+        assert_eq!(
+            filtered.entries,
+            HashMap::from([
+                (
+                    MethodId("lightning.bolt11".into()),
+                    EndpointData("lnbc1...".into())
+                ),
+                (
+                    MethodId("lightning.lnurl".into()),
+                    EndpointData("lnurl1...".into())
+                ),
+            ])
+        );
 
-    struct TestSetup {
-        _testnet: EphemeralTestnet,
-        session_transport: PubkyAuthenticatedTransport,
-        reader_transport: PubkyUnauthenticatedTransport,
-        raw_session: PubkySession,
-        public_key: PublicKey,
+        let everything = get_payment_list_filtered(&setup.reader_transport, &setup.public_key, "")
+            .await
+            .unwrap();
+        assert_eq!(everything.len(), entries.len());
+
+        setup.raw_session.signout().await.unwrap();
     }
 
-    impl TestSetup {
-        async fn new() -> Self {
-            let testnet = EphemeralTestnet::start().await.unwrap();
-            let homeserver = testnet.homeserver();
-            let sdk = testnet.sdk().unwrap();
+    #[tokio::test]
+    async fn versioned_reads_prefer_newer_payloads() {
+        let setup = TestSetup::new().await;
+        let v1_writer = PubkyAuthenticatedTransport::new_with_paths(
+            setup.raw_session.clone(),
+            PaykitPaths::default().with_version("v1"),
+        );
 
-            let pair = Keypair::random();
-            let signer = sdk.signer(pair.clone());
-            let session = signer.signup(&homeserver.public_key(), None).await.unwrap();
+        let lightning = MethodId::new("lightning").unwrap();
+        let onchain = MethodId::new("onchain").unwrap();
+        let v0_lightning = EndpointData("{\"bolt11\":\"ln-v0\"}".into());
+        let v1_lightning = EndpointData("{\"bolt11\":\"ln-v1\"}".into());
+        let v0_onchain = EndpointData("{\"address\":\"bc1-v0\"}".into());
 
-            let session_transport = PubkyAuthenticatedTransport::new(session.clone());
-            let reader_transport = PubkyUnauthenticatedTransport::new(sdk.public_storage());
+        set_payment_endpoint(&setup.session_transport, lightning.clone(), v0_lightning)
+            .await
+            .unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            onchain.clone(),
+            v0_onchain.clone(),
+        )
+        .await
+        .unwrap();
+        set_payment_endpoint(&v1_writer, lightning.clone(), v1_lightning.clone())
+            .await
+            .unwrap();
 
-            Self {
-                _testnet: testnet,
-                session_transport,
-                reader_transport,
-                raw_session: session,
-                public_key: pair.public_key(),
-            }
-        }
+        let merged =
+            get_payment_list_versioned(&setup.reader_transport, &setup.public_key, &["v1", "v0"])
+                .await
+                .unwrap();
+        assert_eq!(
+            merged.entries,
+            HashMap::from([(lightning, v1_lightning), (onchain, v0_onchain)])
+        );
+
+        let missing =
+            get_payment_list_versioned(&setup.reader_transport, &setup.public_key, &["v9"])
+                .await
+                .unwrap();
+        assert!(missing.entries.is_empty());
+
+        setup.raw_session.signout().await.unwrap();
     }
 
     #[tokio::test]
-    async fn endpoint_round_trip_and_update() {
+    async fn binary_endpoint_round_trip() {
         let setup = TestSetup::new().await;
-
-        let method = MethodId("onchain".into());
-        let endpoint = EndpointData("{\"address\":\"bc1...\"}".into());
+        let method = MethodId::new("qr").unwrap();
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        png.extend((0..=255u8).rev());
+        let endpoint = EndpointData::from_bytes(&png);
 
         set_payment_endpoint(&setup.session_transport, method.clone(), endpoint.clone())
             .await
             .unwrap();
-
         let fetched = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
             .await
+            .unwrap()
             .unwrap();
-        assert_eq!(fetched, Some(endpoint.clone()));
+        assert_eq!(fetched, endpoint);
+        assert!(fetched.is_binary());
+        assert_eq!(&*fetched.as_bytes(), png.as_slice());
 
-        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn existence_check_matches_published_state() {
+        let setup = TestSetup::new().await;
+        let large = MethodId::new("lightning").unwrap();
+        let blank = MethodId::new("blank").unwrap();
+        let missing = MethodId::new("onchain").unwrap();
+
+        set_payment_endpoint(
+            &setup.session_transport,
+            large.clone(),
+            EndpointData("x".repeat(512 * 1024)),
+        )
+        .await
+        .unwrap();
+        setup
+            .raw_session
+            .storage()
+            .put(format!("{PAYKIT_PATH_PREFIX}{}", blank.0), "")
             .await
             .unwrap();
-        assert_eq!(
-            list,
-            SupportedPayments {
-                entries: vec![(method.clone(), endpoint.clone())]
-                    .into_iter()
-                    .collect()
-            }
-        );
 
-        let new_endpoint = EndpointData("{\"address\":\"1c1...\"}".into());
+        let reader = &setup.reader_transport;
+        assert!(has_payment_endpoint(reader, &setup.public_key, &large)
+            .await
+            .unwrap());
+        assert!(!has_payment_endpoint(reader, &setup.public_key, &blank)
+            .await
+            .unwrap());
+        assert!(!has_payment_endpoint(reader, &setup.public_key, &missing)
+            .await
+            .unwrap());
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn builder_reads_from_ephemeral_testnet() {
+        let testnet = EphemeralTestnet::start().await.unwrap();
+        let sdk = testnet.sdk().unwrap();
+        let pair = Keypair::random();
+        let session = sdk
+            .signer(pair.clone())
+            .signup(&testnet.homeserver().public_key(), None)
+            .await
+            .unwrap();
+
+        let method = MethodId::new("lightning").unwrap();
+        let endpoint = EndpointData("{\"bolt11\":\"ln...\"}".into());
         set_payment_endpoint(
-            &setup.session_transport,
+            &PubkyAuthenticatedTransport::new(session.clone()),
             method.clone(),
-            new_endpoint.clone(),
+            endpoint.clone(),
         )
         .await
         .unwrap();
 
-        let updated = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+        let relays = testnet.testnet.dht_relay_urls();
+        let nodes = testnet.testnet.dht_bootstrap_nodes();
+        let reader = PubkyUnauthenticatedTransport::builder()
+            .relays(relays.iter().map(|url| url.to_string()))
+            .bootstrap(nodes.iter().map(|node| node.to_string()))
+            .request_timeout(std::time::Duration::from_secs(5))
+            .fetch_concurrency(2)
+            .build()
+            .unwrap();
+
+        let fetched = get_payment_endpoint(&reader, &pair.public_key(), &method)
             .await
             .unwrap();
-        assert_eq!(updated, Some(new_endpoint.clone()));
+        assert_eq!(fetched, Some(endpoint));
 
-        setup.raw_session.signout().await.unwrap();
+        session.signout().await.unwrap();
+    }
+
+    #[test]
+    fn builder_rejects_invalid_relay() {
+        let err = PubkyUnauthenticatedTransport::builder()
+            .relays(["not a url"])
+            .build()
+            .err()
+            .expect("relay URL is invalid");
+        assert!(matches!(err, PaykitError::Transport(_)), "{err:?}");
     }
 
     #[tokio::test]
-    async fn missing_endpoint_returns_none() {
+    async fn streamed_list_matches_collected_list() {
         let setup = TestSetup::new().await;
-        let method = MethodId("bolt11".into());
+        for i in 0..6 {
+            set_payment_endpoint(
+                &setup.session_transport,
+                MethodId::new(format!("method-{i}")).unwrap(),
+                EndpointData(format!("payload-{i}")),
+            )
+            .await
+            .unwrap();
+        }
 
-        let missing = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
+        let streamed: HashMap<_, _> =
+            stream_payment_list(&setup.reader_transport, &setup.public_key)
+                .try_collect()
+                .await
+                .unwrap();
+        let collected = get_payment_list(&setup.reader_transport, &setup.public_key)
             .await
             .unwrap();
-        assert!(missing.is_none());
+        assert_eq!(streamed.len(), 6);
+        assert_eq!(streamed, collected.entries);
 
         setup.raw_session.signout().await.unwrap();
     }
 
     #[tokio::test]
-    async fn list_reflects_additions_and_removals() {
+    async fn oversized_endpoints_are_rejected() {
         let setup = TestSetup::new().await;
-
-        let onchain = MethodId("onchain".into());
-        let lightning = MethodId("lightning".into());
-        let onchain_data = EndpointData("{\"address\":\"bc1...\"}".into());
-        let lightning_data = EndpointData("{\"bolt11\":\"ln...\"}".into());
-
+        let small = MethodId::new("small").unwrap();
+        let large = MethodId::new("large").unwrap();
         set_payment_endpoint(
             &setup.session_transport,
-            onchain.clone(),
-            onchain_data.clone(),
+            small.clone(),
+            EndpointData("x".repeat(16)),
         )
         .await
         .unwrap();
         set_payment_endpoint(
             &setup.session_transport,
-            lightning.clone(),
-            lightning_data.clone(),
+            large.clone(),
+            EndpointData("x".repeat(64)),
         )
         .await
         .unwrap();
 
-        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+        let reader = setup.reader_transport.clone().with_max_endpoint_bytes(32);
+        let fetched = get_payment_endpoint(&reader, &setup.public_key, &small)
             .await
             .unwrap();
-        let mut expected = HashMap::new();
-        expected.insert(onchain.clone(), onchain_data.clone());
-        expected.insert(lightning.clone(), lightning_data.clone());
-        assert_eq!(list.entries, expected);
+        assert_eq!(fetched, Some(EndpointData("x".repeat(16))));
 
-        remove_payment_endpoint(&setup.session_transport, onchain.clone())
+        let err = get_payment_endpoint(&reader, &setup.public_key, &large)
             .await
-            .unwrap();
-        let list = get_payment_list(&setup.reader_transport, &setup.public_key)
+            .unwrap_err();
+        assert!(
+            matches!(err, PaykitError::PayloadTooLarge { limit: 32, actual } if actual >= 64),
+            "{err:?}"
+        );
+        assert!(matches!(
+            get_payment_list(&reader, &setup.public_key).await,
+            Err(PaykitError::PayloadTooLarge { .. })
+        ));
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn lenient_list_reports_oversized_bodies() {
+        let setup = TestSetup::new().await;
+        let small = MethodId::new("small").unwrap();
+        let large = MethodId::new("large").unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            small.clone(),
+            EndpointData("x".repeat(16)),
+        )
+        .await
+        .unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            large.clone(),
+            EndpointData("x".repeat(64)),
+        )
+        .await
+        .unwrap();
+
+        let reader = setup.reader_transport.clone().with_max_endpoint_bytes(32);
+        let (payments, failures) = get_payment_list_lenient(&reader, &setup.public_key)
             .await
             .unwrap();
-        assert_eq!(
-            list.entries,
-            vec![(lightning.clone(), lightning_data.clone())]
-                .into_iter()
-                .collect()
+        assert_eq!(payments.get(&small), Some(&EndpointData("x".repeat(16))));
+        assert_eq!(payments.len(), 1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, large);
+        assert!(
+            matches!(
+                failures[0].1,
+                PaykitError::PayloadTooLarge { limit: 32, .. }
+            ),
+            "{:?}",
+            failures[0].1
         );
 
-        remove_payment_endpoint(&setup.session_transport, lightning.clone())
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn contacts_are_listed_across_pages() {
+        let setup = TestSetup::new().await;
+        let mut expected: Vec<_> = (0..7).map(|_| Keypair::random().public_key()).collect();
+        for contact in &expected {
+            add_contact(&setup.session_transport, contact)
+                .await
+                .unwrap();
+        }
+
+        let (first, cursor) = setup
+            .reader_transport
+            .fetch_known_contacts_page(&setup.public_key, None, 3)
             .await
             .unwrap();
-        let empty = get_payment_list(&setup.reader_transport, &setup.public_key)
+        assert_eq!(first.len(), 3);
+        assert!(cursor.is_some());
+
+        let mut contacts = get_known_contacts_paged(&setup.reader_transport, &setup.public_key, 3)
             .await
             .unwrap();
-        assert!(empty.entries.is_empty());
+        contacts.sort_by_key(ToString::to_string);
+        expected.sort_by_key(ToString::to_string);
+        assert_eq!(contacts, expected);
 
         setup.raw_session.signout().await.unwrap();
     }
 
     #[tokio::test]
-    async fn removing_missing_endpoint_is_error() {
+    async fn conditional_writes_detect_stale_etags() {
         let setup = TestSetup::new().await;
-        let method = MethodId("unused".into());
+        let method = MethodId::new("lightning").unwrap();
+        let cas = |data: &str, etag: Option<String>| {
+            set_payment_endpoint_cas(
+                &setup.session_transport,
+                &method,
+                EndpointData(data.into()),
+                etag,
+            )
+        };
 
-        remove_payment_endpoint(&setup.session_transport, method)
+        let created = cas("v1", None).await.unwrap();
+        assert!(matches!(
+            cas("v1-again", None).await,
+            Err(PaykitError::Conflict {
+                expected: None,
+                actual: Some(_)
+            })
+        ));
+        let updated = cas("v2", Some(created.clone())).await.unwrap();
+        assert_ne!(created, updated);
+
+        let err = cas("v3", Some(created)).await.unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::Conflict { actual: Some(tag), .. } if *tag == updated),
+            "{err:?}"
+        );
+        let stored = get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method)
             .await
-            .expect_err("removing non-existent endpoint should fail");
+            .unwrap();
+        assert_eq!(stored, Some(EndpointData("v2".into())));
 
         setup.raw_session.signout().await.unwrap();
     }
 
     #[tokio::test]
-    async fn lists_known_contacts() {
+    async fn typed_writes_store_payload_on_homeserver() {
         let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        let data = EndpointData("{\"bolt11\":\"ln\"}".into());
 
-        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
+        set_payment_endpoint_with_type(
+            &setup.session_transport,
+            &method,
+            data.clone(),
+            "application/json",
+        )
+        .await
+        .unwrap();
+
+        // The homeserver picks the content type itself; only the payload round-trips.
+        let fetched =
+            get_payment_endpoint_full(&setup.reader_transport, &setup.public_key, &method)
+                .await
+                .unwrap()
+                .expect("endpoint was just written");
+        assert_eq!(fetched.data, data);
+        assert!(fetched.content_type.is_some(), "{fetched:?}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn full_reads_surface_response_metadata() {
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        let before = std::time::SystemTime::now() - std::time::Duration::from_secs(60);
+        set_payment_endpoint(&setup.session_transport, &method, EndpointData("ln".into()))
             .await
             .unwrap();
-        assert!(contacts.is_empty());
 
-        // Seed two contacts under the follows path using the authenticated session.
-        let contact_a = Keypair::random().public_key();
-        let contact_b = Keypair::random().public_key();
-        setup
-            .raw_session
-            .storage()
-            .put(format!("{PUBKY_FOLLOWS_PATH}{}", contact_a), "")
+        let fetched =
+            get_payment_endpoint_full(&setup.reader_transport, &setup.public_key, &method)
+                .await
+                .unwrap()
+                .expect("endpoint was just written");
+        assert_eq!(fetched.data, EndpointData("ln".into()));
+        assert!(fetched.content_type.is_some(), "{fetched:?}");
+        let last_modified = fetched
+            .last_modified
+            .expect("homeserver sends Last-Modified");
+        assert!(last_modified >= before, "{last_modified:?}");
+
+        let missing = MethodId::new("onchain").unwrap();
+        assert!(
+            get_payment_endpoint_full(&setup.reader_transport, &setup.public_key, missing)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tokio::test]
+    async fn tracing_spans_cover_endpoint_reads() {
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id};
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+        /// Records the name and fields of every span that closes.
+        #[derive(Clone, Default)]
+        struct Spans(Arc<Mutex<Vec<(String, String)>>>);
+
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push_str(&format!("{}={value:?} ", field.name()));
+            }
+        }
+
+        impl<S> Layer<S> for Spans
+        where
+            S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+        {
+            fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+                let mut fields = Fields(String::new());
+                attrs.record(&mut fields);
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((attrs.metadata().name().to_string(), fields.0));
+            }
+        }
+
+        let setup = TestSetup::new().await;
+        let method = MethodId::new("lightning").unwrap();
+        set_payment_endpoint(
+            &setup.session_transport,
+            &method,
+            EndpointData("secret".into()),
+        )
+        .await
+        .unwrap();
+
+        let spans = Spans::default();
+        let subscriber = tracing_subscriber::registry().with(spans.clone());
+        let fetched = {
+            let _guard = tracing::subscriber::set_default(subscriber);
+            get_payment_endpoint(&setup.reader_transport, &setup.public_key, &method).await
+        };
+        assert_eq!(fetched.unwrap(), Some(EndpointData("secret".into())));
+
+        let recorded = spans.0.lock().unwrap().clone();
+        let (_, fields) = recorded
+            .iter()
+            .find(|(name, _)| name == "fetch_payment_endpoint")
+            .unwrap_or_else(|| panic!("no fetch span in {recorded:?}"));
+        assert!(fields.contains("method=lightning"), "{fields}");
+        assert!(fields.contains(&setup.public_key.to_string()), "{fields}");
+        assert!(!fields.contains("secret"), "{fields}");
+
+        setup.raw_session.signout().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn capability_check_matches_homeserver_authorization() {
+        let testnet = EphemeralTestnet::start().await.unwrap();
+        let signer = testnet.sdk().unwrap().signer(Keypair::random());
+        let root = signer
+            .signup(&testnet.homeserver().public_key(), None)
             .await
             .unwrap();
-        setup
-            .raw_session
-            .storage()
-            .put(format!("{PUBKY_FOLLOWS_PATH}{}", contact_b), "")
-            .await
+        PubkyAuthenticatedTransport::new(root)
+            .ensure_capability()
             .unwrap();
 
-        let contacts = get_known_contacts(&setup.reader_transport, &setup.public_key)
-            .await
-            .unwrap();
+        let scoped_session = |scope: &'static str| {
+            let caps = pubky::Capabilities::builder().read_write(scope).finish();
+            let flow = pubky::PubkyAuthFlow::builder(&caps)
+                .client(testnet.client().unwrap())
+                .relay(testnet.http_relay().local_link_url())
+                .start()
+                .unwrap();
+            let signer = &signer;
+            async move {
+                signer.approve_auth(flow.authorization_url()).await.unwrap();
+                PubkyAuthenticatedTransport::new(flow.await_approval().await.unwrap())
+            }
+        };
 
-        assert!(contacts.contains(&contact_a));
-        assert!(contacts.contains(&contact_b));
+        let paykit = scoped_session("/pub/paykit.app/").await;
+        paykit.ensure_capability().unwrap();
 
-        setup.raw_session.signout().await.unwrap();
+        let social = scoped_session("/pub/pubky.app/").await;
+        let err = social.ensure_capability().unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::Unauthorized(msg) if msg.contains("/pub/pubky.app/:rw")),
+            "{err:?}"
+        );
+        // The homeserver agrees: the write the check warned about is refused.
+        let write = set_payment_endpoint(
+            &social,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        )
+        .await;
+        assert!(
+            matches!(write, Err(PaykitError::Unauthorized(_))),
+            "{write:?}"
+        );
     }
 }
@@ -0,0 +1,190 @@
+use super::{LightningEndpoint, OnchainEndpoint, PaymentMethod};
+use crate::{MethodId, PaykitError, Result, SupportedPayments};
+
+const SATS_PER_BTC: u64 = 100_000_000;
+
+/// Assemble a BIP21 `bitcoin:` URI from the `onchain` and `lightning` entries of a
+/// payment list, e.g. `bitcoin:bc1q...?amount=0.0005&lightning=lnbc...`.
+///
+/// `amount` is in satoshis and rendered in BTC. The `lightning` parameter carries the
+/// endpoint's `bolt11` invoice, or its `lnurl` when no invoice is published. A
+/// lightning-only list yields an empty address (`bitcoin:?lightning=...`), as unified
+/// QR codes do.
+///
+/// Entries that fail to parse are ignored; if neither method is usable, returns
+/// `PaykitError::InvalidData`.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::methods::{build_bip21, OnchainEndpoint, BitcoinNetwork};
+/// # use paykit_lib::{MethodId, SupportedPayments};
+/// let onchain = OnchainEndpoint {
+///     address: "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq".into(),
+///     network: BitcoinNetwork::Mainnet,
+/// };
+/// let mut payments = SupportedPayments::default();
+/// payments.entries.insert(MethodId("onchain".into()), onchain.to_endpoint_data());
+///
+/// let uri = build_bip21(&payments, Some(50_000))?;
+/// assert_eq!(uri, "bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=0.0005");
+/// # Ok::<(), paykit_lib::PaykitError>(())
+/// ```
+pub fn build_bip21(payments: &SupportedPayments, amount: Option<u64>) -> Result<String> {
+    let onchain = payments
+        .get(&MethodId::from(PaymentMethod::Onchain))
+        .and_then(|data| OnchainEndpoint::try_from(data).ok());
+    let lightning = payments
+        .get(&MethodId::from(PaymentMethod::Lightning))
+        .and_then(|data| LightningEndpoint::try_from(data).ok())
+        .and_then(|endpoint| non_empty(endpoint.bolt11).or_else(|| non_empty(endpoint.lnurl)));
+
+    if onchain.is_none() && lightning.is_none() {
+        return Err(PaykitError::InvalidData(
+            "bip21: no usable onchain or lightning endpoint".into(),
+        ));
+    }
+
+    let mut params = Vec::new();
+    if let Some(sats) = amount {
+        params.push(format!("amount={}", format_btc(sats)));
+    }
+    if let Some(lightning) = lightning {
+        params.push(format!("lightning={}", encode(&lightning)));
+    }
+
+    let mut uri = format!(
+        "bitcoin:{}",
+        onchain.map(|endpoint| endpoint.address).unwrap_or_default()
+    );
+    if !params.is_empty() {
+        uri.push('?');
+        uri.push_str(&params.join("&"));
+    }
+    Ok(uri)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
+
+/// Satoshis as a decimal BTC amount without trailing zeros, e.g. `0.0005`.
+fn format_btc(sats: u64) -> String {
+    let whole = sats / SATS_PER_BTC;
+    let frac = sats % SATS_PER_BTC;
+    if frac == 0 {
+        return whole.to_string();
+    }
+    let frac = format!("{frac:08}");
+    format!("{whole}.{}", frac.trim_end_matches('0'))
+}
+
+/// Percent-encode everything outside the RFC 3986 unreserved set.
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::methods::BitcoinNetwork;
+    use crate::EndpointData;
+
+    const ADDRESS: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+    fn payments(entries: &[(&str, EndpointData)]) -> SupportedPayments {
+        let mut payments = SupportedPayments::default();
+        for (method, data) in entries {
+            payments
+                .entries
+                .insert(MethodId((*method).into()), data.clone());
+        }
+        payments
+    }
+
+    fn onchain() -> EndpointData {
+        OnchainEndpoint {
+            address: ADDRESS.into(),
+            network: BitcoinNetwork::Mainnet,
+        }
+        .to_endpoint_data()
+    }
+
+    fn lightning(bolt11: Option<&str>, lnurl: Option<&str>) -> EndpointData {
+        LightningEndpoint {
+            bolt11: bolt11.map(Into::into),
+            lnurl: lnurl.map(Into::into),
+        }
+        .to_endpoint_data()
+    }
+
+    #[test]
+    fn onchain_only() {
+        let list = payments(&[("onchain", onchain())]);
+        assert_eq!(
+            build_bip21(&list, None).unwrap(),
+            format!("bitcoin:{ADDRESS}")
+        );
+        assert_eq!(
+            build_bip21(&list, Some(150_000_000)).unwrap(),
+            format!("bitcoin:{ADDRESS}?amount=1.5")
+        );
+    }
+
+    #[test]
+    fn lightning_only() {
+        let list = payments(&[("lightning", lightning(None, Some("alice@example.com")))]);
+        assert_eq!(
+            build_bip21(&list, Some(1)).unwrap(),
+            "bitcoin:?amount=0.00000001&lightning=alice%40example.com"
+        );
+    }
+
+    #[test]
+    fn combined_prefers_bolt11() {
+        let list = payments(&[
+            ("onchain", onchain()),
+            (
+                "lightning",
+                lightning(Some("lnbc500u1p3xyz"), Some("lnurl1dp68")),
+            ),
+        ]);
+        assert_eq!(
+            build_bip21(&list, Some(50_000)).unwrap(),
+            format!("bitcoin:{ADDRESS}?amount=0.0005&lightning=lnbc500u1p3xyz")
+        );
+    }
+
+    #[test]
+    fn unusable_lists_are_rejected() {
+        let garbage = payments(&[
+            ("onchain", EndpointData("{\"address\":\"nope\"}".into())),
+            ("lightning", EndpointData("not json".into())),
+            (
+                "lnurl",
+                EndpointData("{\"lnurl\":\"alice@example.com\"}".into()),
+            ),
+        ]);
+        for list in [SupportedPayments::default(), garbage] {
+            let err = build_bip21(&list, None).unwrap_err();
+            assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+        }
+
+        // An unparsable method is skipped when the other one is usable.
+        let partial = payments(&[
+            ("onchain", EndpointData("{\"address\":\"nope\"}".into())),
+            ("lightning", lightning(Some("lnbc1"), None)),
+        ]);
+        assert_eq!(
+            build_bip21(&partial, None).unwrap(),
+            "bitcoin:?lightning=lnbc1"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use super::{parse_json, to_json};
+use crate::{EndpointData, PaykitError};
+
+/// Lightning endpoint payload, e.g. `{"bolt11":"lnbc..."}`.
+///
+/// At least one of `bolt11` or `lnurl` must be set; absent fields are omitted when
+/// serializing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LightningEndpoint {
+    /// BOLT11 invoice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bolt11: Option<String>,
+    /// LNURL string the payer can resolve to an invoice.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lnurl: Option<String>,
+}
+
+impl LightningEndpoint {
+    /// Serialize into the JSON payload stored under the `lightning` method.
+    pub fn to_endpoint_data(&self) -> EndpointData {
+        to_json(self)
+    }
+}
+
+impl TryFrom<&EndpointData> for LightningEndpoint {
+    type Error = PaykitError;
+
+    fn try_from(data: &EndpointData) -> Result<Self, Self::Error> {
+        let endpoint: Self = parse_json("lightning", data)?;
+        let present = |field: &Option<String>| field.as_deref().is_some_and(|v| !v.is_empty());
+        if !present(&endpoint.bolt11) && !present(&endpoint.lnurl) {
+            return Err(PaykitError::InvalidData(
+                "lightning endpoint: neither bolt11 nor lnurl is set".into(),
+            ));
+        }
+        Ok(endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bolt11_only_round_trips() {
+        let data = EndpointData("{\"bolt11\":\"lnbc10u1p3...\"}".into());
+        let endpoint = LightningEndpoint::try_from(&data).unwrap();
+        assert_eq!(endpoint.bolt11.as_deref(), Some("lnbc10u1p3..."));
+        assert_eq!(endpoint.lnurl, None);
+        assert_eq!(endpoint.to_endpoint_data(), data);
+    }
+
+    #[test]
+    fn versioned_body_parses() {
+        let data = EndpointData::with_schema(1, "{\"bolt11\":\"lnbc10u1p3...\"}");
+        let endpoint = LightningEndpoint::try_from(&data).unwrap();
+        assert_eq!(endpoint.bolt11.as_deref(), Some("lnbc10u1p3..."));
+    }
+
+    #[test]
+    fn lnurl_only_round_trips() {
+        let endpoint = LightningEndpoint {
+            bolt11: None,
+            lnurl: Some("lnurl1dp68gurn8ghj7...".into()),
+        };
+        let data = endpoint.to_endpoint_data();
+        assert_eq!(data.0, "{\"lnurl\":\"lnurl1dp68gurn8ghj7...\"}");
+        assert_eq!(LightningEndpoint::try_from(&data).unwrap(), endpoint);
+    }
+
+    #[test]
+    fn rejects_missing_fields_and_bad_json() {
+        for body in ["{}", "{\"bolt11\":\"\"}", "not json"] {
+            let err = LightningEndpoint::try_from(&EndpointData(body.into())).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{body}: {err:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+
+use super::{parse_json, to_json, BECH32_CHARSET};
+use crate::{EndpointData, PaykitError};
+
+/// LNURL-pay endpoint payload, e.g. `{"lnurl":"lnurl1dp68gurn8ghj7..."}` or
+/// `{"lnurl":"alice@example.com"}`.
+///
+/// The value is either a bech32-encoded LNURL or a lightning address. Parsing checks
+/// the `lnurl1` prefix, case, and character set of the former (not its checksum) and
+/// the `user@domain` shape of the latter; plain URLs are rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LnurlEndpoint {
+    /// Bech32-encoded LNURL, stored as published.
+    Bech32(String),
+    /// Lightning address (`user@domain`), resolved via the domain's LNURL-pay service.
+    Address(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct LnurlWire {
+    lnurl: String,
+}
+
+impl LnurlEndpoint {
+    /// The LNURL or lightning address as published.
+    pub fn as_str(&self) -> &str {
+        match self {
+            LnurlEndpoint::Bech32(value) | LnurlEndpoint::Address(value) => value,
+        }
+    }
+
+    /// Serialize into the JSON payload stored under the `lnurl` method.
+    pub fn to_endpoint_data(&self) -> EndpointData {
+        to_json(&LnurlWire {
+            lnurl: self.as_str().to_string(),
+        })
+    }
+}
+
+impl TryFrom<&EndpointData> for LnurlEndpoint {
+    type Error = PaykitError;
+
+    fn try_from(data: &EndpointData) -> Result<Self, Self::Error> {
+        let wire: LnurlWire = parse_json("lnurl", data)?;
+        if is_bech32_lnurl(&wire.lnurl) {
+            Ok(LnurlEndpoint::Bech32(wire.lnurl))
+        } else if is_lightning_address(&wire.lnurl) {
+            Ok(LnurlEndpoint::Address(wire.lnurl))
+        } else {
+            Err(PaykitError::InvalidData(format!(
+                "lnurl endpoint: {:?} is neither an lnurl1 string nor a lightning address",
+                wire.lnurl
+            )))
+        }
+    }
+}
+
+pub(super) fn is_bech32_lnurl(value: &str) -> bool {
+    let lower = value.to_ascii_lowercase();
+    let Some(data) = lower.strip_prefix("lnurl1") else {
+        return false;
+    };
+    // Bech32 forbids mixed case; the data part ends with a 6-character checksum.
+    let single_case = value == lower || value == value.to_ascii_uppercase();
+    single_case && data.len() > 6 && data.chars().all(|c| BECH32_CHARSET.contains(c))
+}
+
+fn is_lightning_address(value: &str) -> bool {
+    let Some((user, domain)) = value.split_once('@') else {
+        return false;
+    };
+    // LUD-16 usernames are lowercase alphanumerics plus `-_.`.
+    let user_ok = !user.is_empty()
+        && user
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c));
+    let label_ok = |label: &str| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    user_ok && domain.contains('.') && domain.split('.').all(label_ok)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LNURL: &str = "lnurl1dp68gurn8ghj7um9wfmxjcm99e3k7mf0v9cxj0m385ekvcenxc6r2c35xvukxefcv5mkvv34x5ekzd3ev56nyd3hxqurzepexejxxepnxscrvwfnv9nxzcn9xq6xyefhvgcxxcmyxymnserxfq5fns";
+
+    #[test]
+    fn parses_bech32_lnurl() {
+        let data = EndpointData(format!("{{\"lnurl\":\"{LNURL}\"}}"));
+        let endpoint = LnurlEndpoint::try_from(&data).unwrap();
+        assert_eq!(endpoint, LnurlEndpoint::Bech32(LNURL.into()));
+        assert_eq!(endpoint.to_endpoint_data(), data);
+
+        let upper = EndpointData(format!("{{\"lnurl\":\"{}\"}}", LNURL.to_uppercase()));
+        assert!(matches!(
+            LnurlEndpoint::try_from(&upper),
+            Ok(LnurlEndpoint::Bech32(_))
+        ));
+    }
+
+    #[test]
+    fn parses_lightning_address() {
+        let data = EndpointData("{\"lnurl\":\"alice@pay.example.com\"}".into());
+        let endpoint = LnurlEndpoint::try_from(&data).unwrap();
+        assert_eq!(
+            endpoint,
+            LnurlEndpoint::Address("alice@pay.example.com".into())
+        );
+        assert_eq!(endpoint.as_str(), "alice@pay.example.com");
+        assert_eq!(
+            LnurlEndpoint::try_from(&endpoint.to_endpoint_data()).unwrap(),
+            endpoint
+        );
+    }
+
+    #[test]
+    fn rejects_urls_and_malformed_values() {
+        for value in [
+            "https://example.com/.well-known/lnurlp/alice",
+            "",
+            "lnurl1",
+            "lnurl1bOgus",
+            "LNURL1dp68gurn8ghj7",
+            "@example.com",
+            "alice@localhost",
+            "Alice@example.com",
+        ] {
+            let data = EndpointData(format!("{{\"lnurl\":\"{value}\"}}"));
+            let err = LnurlEndpoint::try_from(&data).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{value}: {err:?}"
+            );
+        }
+    }
+}
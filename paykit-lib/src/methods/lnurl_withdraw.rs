@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::lnurl::is_bech32_lnurl;
+use super::{parse_json, to_json};
+use crate::{EndpointData, PaykitError};
+
+/// LNURL-withdraw endpoint payload, e.g. `{"lnurl":"lnurl1dp68gurn8ghj7..."}`.
+///
+/// The link lets the payer pull funds from the publisher (LUD-03), e.g. a voucher or
+/// a faucet. Unlike [`super::LnurlEndpoint`], lightning addresses are rejected because
+/// they only resolve to pay services; the bech32 LNURL is checked for its `lnurl1`
+/// prefix, case, and character set, not its checksum.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LnurlWithdrawEndpoint {
+    /// Bech32-encoded LNURL of the withdraw service, stored as published.
+    pub lnurl: String,
+}
+
+impl LnurlWithdrawEndpoint {
+    /// Serialize into the JSON payload stored under the `lnurl-withdraw` method.
+    pub fn to_endpoint_data(&self) -> EndpointData {
+        to_json(self)
+    }
+}
+
+impl TryFrom<&EndpointData> for LnurlWithdrawEndpoint {
+    type Error = PaykitError;
+
+    fn try_from(data: &EndpointData) -> Result<Self, Self::Error> {
+        let endpoint: Self = parse_json("lnurl-withdraw", data)?;
+        if !is_bech32_lnurl(&endpoint.lnurl) {
+            return Err(PaykitError::InvalidData(format!(
+                "lnurl-withdraw endpoint: {:?} is not an lnurl1 string",
+                endpoint.lnurl
+            )));
+        }
+        Ok(endpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LNURL: &str = "lnurl1dp68gurn8ghj7um9wfmxjcm99e3k7mf0v9cxj0m385ekvcenxc6r2c35xvukxefcv5mkvv34x5ekzd3ev56nyd3hxqurzepexejxxepnxscrvwfnv9nxzcn9xq6xyefhvgcxxcmyxymnserxfq5fns";
+
+    #[test]
+    fn bech32_lnurl_round_trips() {
+        let data = EndpointData(format!("{{\"lnurl\":\"{LNURL}\"}}"));
+        let endpoint = LnurlWithdrawEndpoint::try_from(&data).unwrap();
+        assert_eq!(endpoint.lnurl, LNURL);
+        assert_eq!(endpoint.to_endpoint_data(), data);
+    }
+
+    #[test]
+    fn rejects_addresses_and_malformed_values() {
+        for body in [
+            "{\"lnurl\":\"alice@example.com\"}",
+            "{\"lnurl\":\"lnurl1bOgus\"}",
+            "{}",
+            "not json",
+        ] {
+            let err = LnurlWithdrawEndpoint::try_from(&EndpointData(body.into())).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{body}: {err:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,70 @@
+//! Typed views over well-known endpoint payloads.
+//!
+//! [`crate::EndpointData`] stays an opaque string on the wire; with the `serde` feature
+//! the types in this module parse the JSON bodies published under common method ids so
+//! consumers do not have to re-implement the decoding. Conversions are fallible in the
+//! reading direction (`TryFrom<&EndpointData>`) and infallible when serializing back via
+//! `to_endpoint_data()`. `parse_endpoint` picks the type from the method id and returns
+//! a `TypedEndpoint`; `build_bip21` combines a payee's on-chain and lightning entries
+//! into a single `bitcoin:` URI, and `validate_payment_list` checks a list's payloads
+//! before it is published.
+//!
+//! [`PaymentMethod`] names the well-known method ids themselves and canonicalizes their
+//! spelling. It is available without `serde`.
+
+#[cfg(feature = "serde")]
+mod bip21;
+#[cfg(feature = "serde")]
+mod lightning;
+#[cfg(feature = "serde")]
+mod lnurl;
+#[cfg(feature = "serde")]
+mod lnurl_withdraw;
+#[cfg(feature = "serde")]
+mod onchain;
+mod payment_method;
+#[cfg(feature = "serde")]
+mod typed;
+#[cfg(feature = "serde")]
+mod validate;
+
+#[cfg(feature = "serde")]
+pub use bip21::build_bip21;
+#[cfg(feature = "serde")]
+pub use lightning::LightningEndpoint;
+#[cfg(feature = "serde")]
+pub use lnurl::LnurlEndpoint;
+#[cfg(feature = "serde")]
+pub use lnurl_withdraw::LnurlWithdrawEndpoint;
+#[cfg(feature = "serde")]
+pub use onchain::{BitcoinNetwork, OnchainEndpoint};
+pub use payment_method::PaymentMethod;
+#[cfg(feature = "serde")]
+pub use typed::{parse_endpoint, TypedEndpoint};
+#[cfg(feature = "serde")]
+pub use validate::validate_payment_list;
+
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::{EndpointData, PaykitError, Result};
+
+/// Data characters of bech32 strings (lowercase form).
+#[cfg(feature = "serde")]
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Decode a JSON endpoint body, labelling failures with the payload `kind`. A schema
+/// header (see [`EndpointData::with_schema`]) is skipped.
+#[cfg(feature = "serde")]
+fn parse_json<T: DeserializeOwned>(kind: &str, data: &EndpointData) -> Result<T> {
+    serde_json::from_str(data.body())
+        .map_err(|err| PaykitError::InvalidData(format!("{kind} endpoint: {err}")))
+}
+
+/// Encode a typed endpoint back into its JSON wire form.
+#[cfg(feature = "serde")]
+fn to_json<T: Serialize>(value: &T) -> EndpointData {
+    // The endpoint types only hold strings and plain enums, which always serialize.
+    EndpointData(serde_json::to_string(value).expect("endpoint types serialize to JSON"))
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{parse_json, to_json, BECH32_CHARSET};
+use crate::{EndpointData, PaykitError};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Bitcoin network an on-chain address belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinNetwork {
+    /// Bitcoin mainnet (`bc1`, `1`, `3`).
+    Mainnet,
+    /// Testnet and signet (`tb1`, `m`, `n`, `2`).
+    Testnet,
+    /// Local regtest (`bcrt1`, `m`, `n`, `2`).
+    Regtest,
+}
+
+impl fmt::Display for BitcoinNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BitcoinNetwork::Mainnet => "mainnet",
+            BitcoinNetwork::Testnet => "testnet",
+            BitcoinNetwork::Regtest => "regtest",
+        })
+    }
+}
+
+/// On-chain endpoint payload, e.g. `{"address":"bc1q..."}`.
+///
+/// The `network` field is optional on the wire and inferred from the address prefix
+/// when absent; legacy testnet-style prefixes (`m`, `n`, `2`) infer
+/// [`BitcoinNetwork::Testnet`]. Parsing checks the prefix, length, and character set
+/// of the address but does not verify its checksum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OnchainEndpoint {
+    /// Bitcoin address funds should be sent to.
+    pub address: String,
+    /// Network the address belongs to.
+    pub network: BitcoinNetwork,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OnchainWire {
+    address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    network: Option<BitcoinNetwork>,
+}
+
+impl OnchainEndpoint {
+    /// Serialize into the JSON payload stored under the `onchain` method.
+    pub fn to_endpoint_data(&self) -> EndpointData {
+        to_json(&OnchainWire {
+            address: self.address.clone(),
+            network: Some(self.network),
+        })
+    }
+}
+
+impl TryFrom<&EndpointData> for OnchainEndpoint {
+    type Error = PaykitError;
+
+    fn try_from(data: &EndpointData) -> Result<Self, Self::Error> {
+        let wire: OnchainWire = parse_json("onchain", data)?;
+        let candidates = address_networks(&wire.address).ok_or_else(|| {
+            PaykitError::InvalidData(format!(
+                "onchain endpoint: {:?} is not a bitcoin address",
+                wire.address
+            ))
+        })?;
+        let network = match wire.network {
+            Some(declared) if candidates.contains(&declared) => declared,
+            Some(declared) => {
+                return Err(PaykitError::InvalidData(format!(
+                    "onchain endpoint: {:?} is not a {declared} address",
+                    wire.address
+                )))
+            }
+            None => candidates[0],
+        };
+        Ok(Self {
+            address: wire.address,
+            network,
+        })
+    }
+}
+
+/// Networks `address` may belong to, most likely first, or `None` if it is malformed.
+fn address_networks(address: &str) -> Option<&'static [BitcoinNetwork]> {
+    use BitcoinNetwork::*;
+
+    let lower = address.to_ascii_lowercase();
+    for (hrp, networks) in [
+        ("bcrt1", &[Regtest][..]),
+        ("bc1", &[Mainnet][..]),
+        ("tb1", &[Testnet][..]),
+    ] {
+        if let Some(data) = lower.strip_prefix(hrp) {
+            // Bech32 forbids mixed case; segwit programs are 14 to 74 bytes long.
+            let single_case = address == lower || address == address.to_ascii_uppercase();
+            let valid = single_case
+                && (14..=74).contains(&data.len())
+                && data.chars().all(|c| BECH32_CHARSET.contains(c));
+            return valid.then_some(networks);
+        }
+    }
+
+    let networks: &'static [BitcoinNetwork] = match address.chars().next()? {
+        '1' | '3' => &[Mainnet],
+        'm' | 'n' | '2' => &[Testnet, Regtest],
+        _ => return None,
+    };
+    let valid =
+        (26..=35).contains(&address.len()) && address.chars().all(|c| BASE58_ALPHABET.contains(c));
+    valid.then_some(networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mainnet_bech32_address() {
+        let data =
+            EndpointData("{\"address\":\"bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq\"}".into());
+        let endpoint = OnchainEndpoint::try_from(&data).unwrap();
+        assert_eq!(endpoint.network, BitcoinNetwork::Mainnet);
+
+        let round_trip = OnchainEndpoint::try_from(&endpoint.to_endpoint_data()).unwrap();
+        assert_eq!(round_trip, endpoint);
+    }
+
+    #[test]
+    fn infers_and_checks_network() {
+        let legacy = EndpointData(
+            "{\"address\":\"mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn\",\"network\":\"regtest\"}".into(),
+        );
+        let endpoint = OnchainEndpoint::try_from(&legacy).unwrap();
+        assert_eq!(endpoint.network, BitcoinNetwork::Regtest);
+
+        let mismatched = EndpointData(
+            "{\"address\":\"bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq\",\"network\":\"testnet\"}"
+                .into(),
+        );
+        assert!(matches!(
+            OnchainEndpoint::try_from(&mismatched),
+            Err(PaykitError::InvalidData(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_garbage_addresses() {
+        for address in ["", "hello world", "bc1...", "1OOOOOOOOOOOOOOOOOOOOOOOOOO"] {
+            let data = EndpointData(format!("{{\"address\":\"{address}\"}}"));
+            let err = OnchainEndpoint::try_from(&data).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{address}: {err:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::MethodId;
+
+/// Well-known payment method ids, with a catch-all for everything else.
+///
+/// Converting from a [`MethodId`] trims it, ignores ASCII case, and maps common aliases
+/// onto the canonical spelling, so `"Lightning"` and `"ln"` both become
+/// [`PaymentMethod::Lightning`]. Converting back always yields the canonical id.
+/// Unrecognized ids are kept verbatim in [`PaymentMethod::Other`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::methods::PaymentMethod;
+/// # use paykit_lib::MethodId;
+/// let method = PaymentMethod::from(&MethodId("On-Chain".into()));
+/// assert_eq!(method, PaymentMethod::Onchain);
+/// assert_eq!(MethodId::from(method).0, "onchain");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PaymentMethod {
+    /// Canonical id `lightning`; also matches `ln`.
+    Lightning,
+    /// Canonical id `bolt11`, a bare BOLT 11 invoice.
+    Bolt11,
+    /// Canonical id `bolt12`, a BOLT 12 offer.
+    Bolt12,
+    /// Canonical id `lnurl`.
+    Lnurl,
+    /// Canonical id `lnurl-withdraw`; also matches `lnurlw` and `lnurl_withdraw`.
+    LnurlWithdraw,
+    /// Canonical id `onchain`; also matches `on-chain`, `on_chain`, `bitcoin`, and `btc`.
+    Onchain,
+    /// Any other method id, kept as published.
+    Other(String),
+}
+
+impl PaymentMethod {
+    /// Canonical method id for this variant.
+    pub fn as_str(&self) -> &str {
+        match self {
+            PaymentMethod::Lightning => "lightning",
+            PaymentMethod::Bolt11 => "bolt11",
+            PaymentMethod::Bolt12 => "bolt12",
+            PaymentMethod::Lnurl => "lnurl",
+            PaymentMethod::LnurlWithdraw => "lnurl-withdraw",
+            PaymentMethod::Onchain => "onchain",
+            PaymentMethod::Other(id) => id,
+        }
+    }
+
+    fn parse(id: &str) -> Self {
+        match id.trim().to_ascii_lowercase().as_str() {
+            "lightning" | "ln" => PaymentMethod::Lightning,
+            "bolt11" => PaymentMethod::Bolt11,
+            "bolt12" => PaymentMethod::Bolt12,
+            "lnurl" => PaymentMethod::Lnurl,
+            "lnurl-withdraw" | "lnurlw" | "lnurl_withdraw" => PaymentMethod::LnurlWithdraw,
+            "onchain" | "on-chain" | "on_chain" | "bitcoin" | "btc" => PaymentMethod::Onchain,
+            _ => PaymentMethod::Other(id.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for PaymentMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&MethodId> for PaymentMethod {
+    fn from(method: &MethodId) -> Self {
+        Self::parse(&method.0)
+    }
+}
+
+impl From<MethodId> for PaymentMethod {
+    fn from(method: MethodId) -> Self {
+        Self::parse(&method.0)
+    }
+}
+
+impl From<PaymentMethod> for MethodId {
+    fn from(method: PaymentMethod) -> Self {
+        match method {
+            PaymentMethod::Other(id) => MethodId(id),
+            known => MethodId(known.as_str().to_string()),
+        }
+    }
+}
+
+impl From<&PaymentMethod> for MethodId {
+    fn from(method: &PaymentMethod) -> Self {
+        MethodId(method.as_str().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_ids_ignore_case_and_aliases() {
+        for (id, expected) in [
+            ("lightning", PaymentMethod::Lightning),
+            ("LIGHTNING", PaymentMethod::Lightning),
+            ("Ln", PaymentMethod::Lightning),
+            ("BOLT11", PaymentMethod::Bolt11),
+            ("bolt12", PaymentMethod::Bolt12),
+            ("LnUrl", PaymentMethod::Lnurl),
+            ("LNURLW", PaymentMethod::LnurlWithdraw),
+            (" On-Chain ", PaymentMethod::Onchain),
+            ("bitcoin", PaymentMethod::Onchain),
+        ] {
+            let method = PaymentMethod::from(&MethodId(id.into()));
+            assert_eq!(method, expected, "{id}");
+            assert_eq!(MethodId::from(&method).0, expected.as_str());
+        }
+    }
+
+    #[test]
+    fn unknown_ids_round_trip_verbatim() {
+        let id = MethodId("PayPal-Classic".into());
+        let method = PaymentMethod::from(&id);
+        assert_eq!(method, PaymentMethod::Other("PayPal-Classic".into()));
+        assert_eq!(MethodId::from(method), id);
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    LightningEndpoint, LnurlEndpoint, LnurlWithdrawEndpoint, OnchainEndpoint, PaymentMethod,
+};
+use crate::{EndpointData, MethodId, Result};
+
+/// An endpoint payload parsed according to its method id.
+///
+/// Returned by [`parse_endpoint`]. Methods without a typed payload, including
+/// `bolt11`, `bolt12`, and ids this crate does not know, stay [`TypedEndpoint::Raw`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypedEndpoint {
+    /// Payload of a `lightning` endpoint.
+    Lightning(LightningEndpoint),
+    /// Payload of an `onchain` endpoint.
+    Onchain(OnchainEndpoint),
+    /// Payload of an `lnurl` (LNURL-pay) endpoint.
+    Lnurl(LnurlEndpoint),
+    /// Payload of an `lnurl-withdraw` endpoint.
+    LnurlWithdraw(LnurlWithdrawEndpoint),
+    /// Payload of any other method, as published.
+    Raw(EndpointData),
+}
+
+/// Parse `data` as the typed endpoint for `method`.
+///
+/// `method` is recognized like [`PaymentMethod`], so aliases such as `ln` or `bitcoin`
+/// select the canonical type. Payloads of a recognized method that do not parse fail
+/// with `PaykitError::InvalidData`; other methods never fail and come back as
+/// [`TypedEndpoint::Raw`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::methods::{parse_endpoint, TypedEndpoint};
+/// # use paykit_lib::{EndpointData, MethodId};
+/// let data = EndpointData("{\"bolt11\":\"lnbc1...\"}".into());
+/// match parse_endpoint(&MethodId("lightning".into()), &data)? {
+///     TypedEndpoint::Lightning(endpoint) => assert_eq!(endpoint.bolt11.as_deref(), Some("lnbc1...")),
+///     other => panic!("unexpected {other:?}"),
+/// }
+/// # Ok::<(), paykit_lib::PaykitError>(())
+/// ```
+pub fn parse_endpoint(method: &MethodId, data: &EndpointData) -> Result<TypedEndpoint> {
+    Ok(match PaymentMethod::from(method) {
+        PaymentMethod::Lightning => TypedEndpoint::Lightning(data.try_into()?),
+        PaymentMethod::Onchain => TypedEndpoint::Onchain(data.try_into()?),
+        PaymentMethod::Lnurl => TypedEndpoint::Lnurl(data.try_into()?),
+        PaymentMethod::LnurlWithdraw => TypedEndpoint::LnurlWithdraw(data.try_into()?),
+        PaymentMethod::Bolt11 | PaymentMethod::Bolt12 | PaymentMethod::Other(_) => {
+            TypedEndpoint::Raw(data.clone())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PaykitError;
+
+    const LNURL: &str = "lnurl1dp68gurn8ghj7um9wfmxjcm99e3k7mf0v9cxj0m385ekvcenxc6r2c35xvukxefcv5mkvv34x5ekzd3ev56nyd3hxqurzepexejxxepnxscrvwfnv9nxzcn9xq6xyefhvgcxxcmyxymnserxfq5fns";
+
+    fn parse(method: &str, body: &str) -> Result<TypedEndpoint> {
+        parse_endpoint(&MethodId(method.into()), &EndpointData(body.into()))
+    }
+
+    #[test]
+    fn dispatches_known_methods() {
+        let lightning = parse("lightning", "{\"bolt11\":\"lnbc10u1p3...\"}").unwrap();
+        assert!(
+            matches!(lightning, TypedEndpoint::Lightning(_)),
+            "{lightning:?}"
+        );
+
+        let onchain = parse(
+            "bitcoin",
+            "{\"address\":\"bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq\"}",
+        )
+        .unwrap();
+        assert!(matches!(onchain, TypedEndpoint::Onchain(_)), "{onchain:?}");
+
+        let pay = parse("lnurl", "{\"lnurl\":\"alice@example.com\"}").unwrap();
+        assert_eq!(
+            pay,
+            TypedEndpoint::Lnurl(LnurlEndpoint::Address("alice@example.com".into()))
+        );
+
+        let withdraw = parse("lnurl-withdraw", &format!("{{\"lnurl\":\"{LNURL}\"}}")).unwrap();
+        assert_eq!(
+            withdraw,
+            TypedEndpoint::LnurlWithdraw(LnurlWithdrawEndpoint {
+                lnurl: LNURL.into()
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_methods_stay_raw() {
+        let raw = parse("paypal", "not json").unwrap();
+        assert_eq!(raw, TypedEndpoint::Raw(EndpointData("not json".into())));
+    }
+
+    #[test]
+    fn malformed_known_payloads_fail() {
+        let err = parse("lnurl-withdraw", "{\"lnurl\":\"alice@example.com\"}").unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,72 @@
+use super::parse_endpoint;
+use crate::{MethodId, PaykitError, SupportedPayments};
+
+/// Check that every payload in `payments` parses as its method's typed endpoint.
+///
+/// Each entry goes through [`parse_endpoint`], so `lightning`, `onchain`, `lnurl`, and
+/// `lnurl-withdraw` payloads (aliases included) must parse as their typed endpoint.
+/// Other methods are left unchecked. Returns the failing methods with their
+/// `PaykitError::InvalidData`, sorted by method id; an empty result means the list is
+/// ready to publish.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::methods::validate_payment_list;
+/// # use paykit_lib::{EndpointData, MethodId, SupportedPayments};
+/// let payments: SupportedPayments = [
+///     (MethodId("lightning".into()), EndpointData("{\"bolt11\":\"lnbc1...\"}".into())),
+///     (MethodId("onchain".into()), EndpointData("bc1q...".into())),
+/// ]
+/// .into_iter()
+/// .collect();
+///
+/// let failures = validate_payment_list(&payments);
+/// assert_eq!(failures.len(), 1);
+/// assert_eq!(failures[0].0.0, "onchain");
+/// ```
+pub fn validate_payment_list(payments: &SupportedPayments) -> Vec<(MethodId, PaykitError)> {
+    payments
+        .sorted_entries()
+        .into_iter()
+        .filter_map(|(method, data)| {
+            parse_endpoint(method, data)
+                .err()
+                .map(|err| (method.clone(), err))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EndpointData;
+
+    fn payments(entries: &[(&str, &str)]) -> SupportedPayments {
+        entries
+            .iter()
+            .map(|(method, body)| (MethodId((*method).into()), EndpointData((*body).into())))
+            .collect()
+    }
+
+    #[test]
+    fn valid_payloads_pass() {
+        let payments = payments(&[
+            ("lightning", "{\"bolt11\":\"lnbc10u1p3...\"}"),
+            ("custom", "anything goes"),
+        ]);
+        assert!(validate_payment_list(&payments).is_empty());
+    }
+
+    #[test]
+    fn malformed_lightning_payload_is_reported() {
+        let payments = payments(&[("lightning", "{\"bolt11\":\"\"}"), ("custom", "not json")]);
+        let failures = validate_payment_list(&payments);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, MethodId("lightning".into()));
+        assert!(
+            matches!(failures[0].1, PaykitError::InvalidData(_)),
+            "{:?}",
+            failures[0].1
+        );
+    }
+}
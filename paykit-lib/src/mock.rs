@@ -0,0 +1,666 @@
+//! In-memory transport for unit tests.
+//!
+//! [`MockTransport`] implements both [`crate::UnauthenticatedTransportRead`] and
+//! [`crate::AuthenticatedTransport`] on top of plain maps, so code consuming the
+//! Paykit helpers can be tested without an `EphemeralTestnet` or the `pubky` feature.
+//! It is the recommended way to unit-test integrations; enable it from your
+//! dev-dependencies:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! paykit-lib = { version = "0.1", features = ["mock"] }
+//! ```
+//!
+//! Clones share the same state, so one handle can be used as the payee's writer while
+//! another reads on behalf of a payer.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use async_trait::async_trait;
+
+use crate::transport::paths::validate_relative_path;
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// In-memory transport implementing both Paykit transport traits.
+///
+/// Writes go to the `owner` key given at construction; reads accept any payee.
+/// Missing endpoints read as `Ok(None)` and missing lists as empty, mirroring the
+/// Pubky adapters.
+#[derive(Clone, Debug)]
+pub struct MockTransport {
+    owner: PublicKey,
+    state: Arc<Mutex<MockState>>,
+    #[cfg(feature = "tokio")]
+    in_flight: Arc<InFlight>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    endpoints: HashMap<(PublicKey, MethodId), EndpointData>,
+    content_types: HashMap<(PublicKey, MethodId), String>,
+    raw_files: HashMap<(PublicKey, String), EndpointData>,
+    follows: HashSet<(PublicKey, PublicKey)>,
+    fail_next: VecDeque<PaykitError>,
+    failing_methods: HashMap<MethodId, String>,
+    failing_contacts: HashMap<PublicKey, String>,
+    calls: HashMap<&'static str, usize>,
+    #[cfg(feature = "tokio")]
+    latency: std::time::Duration,
+}
+
+/// Calls currently sleeping through the simulated latency, kept outside the state lock
+/// because they are counted while awaiting.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default)]
+struct InFlight {
+    current: std::sync::atomic::AtomicUsize,
+    peak: std::sync::atomic::AtomicUsize,
+}
+
+impl MockTransport {
+    /// Create an empty transport that writes on behalf of `owner`.
+    pub fn new(owner: PublicKey) -> Self {
+        Self {
+            owner,
+            state: Arc::default(),
+            #[cfg(feature = "tokio")]
+            in_flight: Arc::default(),
+        }
+    }
+
+    /// Key that authenticated writes are attributed to.
+    pub fn owner(&self) -> &PublicKey {
+        &self.owner
+    }
+
+    /// Seed an endpoint for any payee without going through the write trait.
+    pub fn insert_endpoint(&self, payee: &PublicKey, method: MethodId, data: EndpointData) {
+        self.state().endpoints.insert((payee.clone(), method), data);
+    }
+
+    /// Seed a non-endpoint file, e.g. `profile.json`, served by
+    /// [`UnauthenticatedTransportRead::fetch_raw`] at `relative_path`.
+    pub fn insert_raw(&self, payee: &PublicKey, relative_path: &str, data: EndpointData) {
+        self.state()
+            .raw_files
+            .insert((payee.clone(), relative_path.to_string()), data);
+    }
+
+    /// Seed a follow entry so `contact` shows up in `owner`'s known contacts.
+    pub fn insert_contact(&self, owner: &PublicKey, contact: &PublicKey) {
+        self.state()
+            .follows
+            .insert((owner.clone(), contact.clone()));
+    }
+
+    /// Make the next transport call (read or write) fail with
+    /// `PaykitError::Transport(message)`. The failure is consumed by that call; calling
+    /// this repeatedly queues failures for consecutive calls.
+    pub fn fail_next(&self, message: impl Into<String>) {
+        self.state()
+            .fail_next
+            .push_back(PaykitError::Transport(message.into()));
+    }
+
+    /// Make the next transport call fail with `PaykitError::Network(message)`, as if the
+    /// connection dropped before a response arrived.
+    pub fn fail_next_network(&self, message: impl Into<String>) {
+        self.state()
+            .fail_next
+            .push_back(PaykitError::Network(message.into()));
+    }
+
+    /// Make the next transport call fail with `PaykitError::RateLimited`, as if the
+    /// server had answered 429 with the given `Retry-After` delay. Queued together with
+    /// [`Self::fail_next`] failures.
+    pub fn rate_limit_next(&self, retry_after: Option<std::time::Duration>) {
+        self.state()
+            .fail_next
+            .push_back(PaykitError::RateLimited { retry_after });
+    }
+
+    /// Make every call touching `method` fail with `PaykitError::Transport(message)`
+    /// until [`Self::clear_failures`] is called.
+    pub fn fail_method(&self, method: MethodId, message: impl Into<String>) {
+        self.state().failing_methods.insert(method, message.into());
+    }
+
+    /// Make every contact listing of `owner` fail with `PaykitError::Transport(message)`
+    /// until [`Self::clear_failures`] is called.
+    pub fn fail_contacts_of(&self, owner: &PublicKey, message: impl Into<String>) {
+        self.state()
+            .failing_contacts
+            .insert(owner.clone(), message.into());
+    }
+
+    /// Drop all pending failure injections.
+    pub fn clear_failures(&self) {
+        let mut state = self.state();
+        state.fail_next.clear();
+        state.failing_methods.clear();
+        state.failing_contacts.clear();
+    }
+
+    /// Delay every subsequent call by `latency` before it is served, to simulate a slow
+    /// homeserver. Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn set_latency(&self, latency: std::time::Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Highest number of calls that were waiting on the latency set by
+    /// [`Self::set_latency`] at the same time. Requires the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    pub fn peak_in_flight(&self) -> usize {
+        self.in_flight
+            .peak
+            .load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Number of times the trait method named `op` (e.g. `"fetch_payment_endpoint"`)
+    /// has been called, including calls that failed.
+    pub fn call_count(&self, op: &str) -> usize {
+        self.state().calls.get(op).copied().unwrap_or(0)
+    }
+
+    /// Total number of trait method calls across all operations.
+    pub fn total_calls(&self) -> usize {
+        self.state().calls.values().sum()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MockState> {
+        // A panicking test must not poison the state for other assertions.
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    async fn simulate_latency(&self) {
+        #[cfg(feature = "tokio")]
+        {
+            use std::sync::atomic::Ordering;
+
+            let latency = self.state().latency;
+            if !latency.is_zero() {
+                let current = self.in_flight.current.fetch_add(1, Ordering::SeqCst) + 1;
+                self.in_flight.peak.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(latency).await;
+                self.in_flight.current.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    fn begin(&self, op: &'static str) -> Result<MutexGuard<'_, MockState>> {
+        let mut state = self.state();
+        *state.calls.entry(op).or_default() += 1;
+        match state.fail_next.pop_front() {
+            Some(err) => Err(err),
+            None => Ok(state),
+        }
+    }
+
+    fn begin_method(
+        &self,
+        op: &'static str,
+        method: &MethodId,
+    ) -> Result<MutexGuard<'_, MockState>> {
+        let state = self.begin(op)?;
+        match state.failing_methods.get(method) {
+            Some(message) => Err(PaykitError::Transport(message.clone())),
+            None => Ok(state),
+        }
+    }
+
+    fn begin_contacts(
+        &self,
+        op: &'static str,
+        owner: &PublicKey,
+    ) -> Result<MutexGuard<'_, MockState>> {
+        let state = self.begin(op)?;
+        match state.failing_contacts.get(owner) {
+            Some(message) => Err(PaykitError::Transport(message.clone())),
+            None => Ok(state),
+        }
+    }
+}
+
+impl MockState {
+    /// Stores `data` as `owner`'s endpoint; a plain write forgets any earlier content type.
+    fn write(
+        &mut self,
+        owner: &PublicKey,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: Option<&str>,
+    ) {
+        let key = (owner.clone(), method.clone());
+        match content_type {
+            Some(content_type) => self.content_types.insert(key.clone(), content_type.into()),
+            None => self.content_types.remove(&key),
+        };
+        self.endpoints.insert(key, data.clone());
+    }
+
+    fn payments_of(&self, payee: &PublicKey) -> SupportedPayments {
+        let entries = self
+            .endpoints
+            .iter()
+            .filter(|((key, _), _)| key == payee)
+            .map(|((_, method), data)| (method.clone(), data.clone()))
+            .collect();
+        SupportedPayments { entries }
+    }
+
+    /// Contacts of `owner`, plus the seeded entries that are not valid keys.
+    ///
+    /// Without the `pubky` feature, keys are re-validated with `PublicKey::parse` so
+    /// junk seeded through the placeholder type is reported like a stray follow file.
+    fn contacts_of(&self, owner: &PublicKey) -> (Vec<PublicKey>, Vec<String>) {
+        let (mut contacts, junk): (Vec<_>, Vec<_>) = self
+            .follows
+            .iter()
+            .filter(|(key, _)| key == owner)
+            .map(|(_, contact)| contact.clone())
+            .partition(is_valid_key);
+        contacts.sort_by_cached_key(ToString::to_string);
+        let mut junk: Vec<_> = junk.iter().map(ToString::to_string).collect();
+        junk.sort();
+        (contacts, junk)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl UnauthenticatedTransportRead for MockTransport {
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.simulate_latency().await;
+        let state = self.begin("fetch_supported_payments")?;
+        Ok(state.payments_of(payee))
+    }
+
+    /// Methods marked with [`MockTransport::fail_method`] are reported as failures
+    /// instead of listed.
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.simulate_latency().await;
+        let state = self.begin("fetch_supported_payments_lenient")?;
+        let mut payments = state.payments_of(payee);
+        let mut failures = Vec::new();
+        for (method, message) in &state.failing_methods {
+            if payments.entries.remove(method).is_some() {
+                failures.push((method.clone(), PaykitError::Transport(message.clone())));
+            }
+        }
+        failures.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+        Ok((payments, failures))
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.simulate_latency().await;
+        let state = self.begin("count_supported_payments")?;
+        Ok(state.payments_of(payee).len())
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.simulate_latency().await;
+        let state = self.begin_method("fetch_payment_endpoint", method)?;
+        Ok(state
+            .endpoints
+            .get(&(payee.clone(), method.clone()))
+            .cloned())
+    }
+
+    /// Serves files seeded with [`MockTransport::insert_raw`], then endpoints, which
+    /// live in the same directory.
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        validate_relative_path(relative_path)?;
+        self.simulate_latency().await;
+        let state = self.begin("fetch_raw")?;
+        if let Some(data) = state
+            .raw_files
+            .get(&(payee.clone(), relative_path.to_string()))
+        {
+            return Ok(Some(data.clone()));
+        }
+        Ok(state
+            .endpoints
+            .get(&(payee.clone(), MethodId(relative_path.to_string())))
+            .cloned())
+    }
+
+    /// Reports the content type given to
+    /// [`AuthenticatedTransport::upsert_payment_endpoint_with_type`], if any.
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.simulate_latency().await;
+        let state = self.begin_method("fetch_payment_endpoint_full", method)?;
+        let key = (payee.clone(), method.clone());
+        Ok(state.endpoints.get(&key).map(|data| FetchedEndpoint {
+            data: data.clone(),
+            content_type: state.content_types.get(&key).cloned(),
+            last_modified: None,
+        }))
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.simulate_latency().await;
+        let state = self.begin_method("fetch_payment_endpoint_exists", method)?;
+        Ok(state
+            .endpoints
+            .contains_key(&(payee.clone(), method.clone())))
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.simulate_latency().await;
+        let state = self.begin_contacts("fetch_known_contacts", owner)?;
+        Ok(state.contacts_of(owner).0)
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.simulate_latency().await;
+        let state = self.begin_contacts("fetch_known_contacts_with_report", owner)?;
+        Ok(state.contacts_of(owner))
+    }
+
+    /// Pages through contacts in key order; the cursor is the last key returned.
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.simulate_latency().await;
+        let state = self.begin_contacts("fetch_known_contacts_page", owner)?;
+        let mut contacts = state.contacts_of(owner).0;
+        contacts.sort_by_key(ToString::to_string);
+        let mut remaining: Vec<_> = contacts
+            .into_iter()
+            .filter(|contact| {
+                cursor
+                    .as_ref()
+                    .is_none_or(|after| contact.to_string() > *after)
+            })
+            .collect();
+        let limit = limit.max(1);
+        if remaining.len() <= limit {
+            return Ok((remaining, None));
+        }
+        remaining.truncate(limit);
+        let next = remaining.last().map(ToString::to_string);
+        Ok((remaining, next))
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.simulate_latency().await;
+        let _state = self.begin("health_check")?;
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthenticatedTransport for MockTransport {
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.begin_method("upsert_payment_endpoint", method)?;
+        state.write(&self.owner, method, data, None);
+        Ok(())
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.begin_method("upsert_payment_endpoint_with_type", method)?;
+        state.write(&self.owner, method, data, Some(content_type));
+        Ok(())
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.begin_method("remove_payment_endpoint", method)?;
+        // Mirror the homeserver, which rejects deleting a missing file.
+        let key = (self.owner.clone(), method.clone());
+        state.content_types.remove(&key);
+        match state.endpoints.remove(&key) {
+            Some(_) => Ok(()),
+            None => Err(not_found(format!(
+                "delete endpoint: {} not found",
+                method.0
+            ))),
+        }
+    }
+
+    /// ETags are a hash of the payload, so identical content has the same tag.
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.simulate_latency().await;
+        let mut state = self.begin_method("upsert_payment_endpoint_if_match", method)?;
+        let key = (self.owner.clone(), method.clone());
+        let actual = state.endpoints.get(&key).map(etag_of);
+        if actual != expected_etag {
+            return Err(PaykitError::Conflict {
+                expected: expected_etag,
+                actual,
+            });
+        }
+        state.write(&self.owner, method, data, None);
+        Ok(etag_of(data))
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.simulate_latency().await;
+        let state = self.begin("list_own_endpoints")?;
+        Ok(state.payments_of(&self.owner))
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.simulate_latency().await;
+        let state = self.begin("list_own_contacts")?;
+        Ok(state.contacts_of(&self.owner).0)
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.begin("add_contact")?;
+        state.follows.insert((self.owner.clone(), contact.clone()));
+        Ok(())
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.simulate_latency().await;
+        let mut state = self.begin("remove_contact")?;
+        if state.follows.remove(&(self.owner.clone(), contact.clone())) {
+            Ok(())
+        } else {
+            Err(not_found(format!("delete contact: {contact} not found")))
+        }
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.simulate_latency().await;
+        let _state = self.begin("health_check")?;
+        Ok(())
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        Ok(self.owner.clone())
+    }
+}
+
+/// SDK keys are validated when they are constructed.
+#[cfg(feature = "pubky")]
+fn is_valid_key(_key: &PublicKey) -> bool {
+    true
+}
+
+/// The placeholder type accepts any string, so check it the way a real listing would.
+#[cfg(not(feature = "pubky"))]
+fn is_valid_key(key: &PublicKey) -> bool {
+    PublicKey::parse(&key.0).is_ok()
+}
+
+fn etag_of(data: &EndpointData) -> String {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    data.0.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// The error a homeserver answers with when deleting a missing file.
+fn not_found(message: String) -> PaykitError {
+    PaykitError::Server {
+        status: 404,
+        message,
+    }
+}
+
+/// Generates a fresh public key for tests, regardless of the `pubky` feature.
+#[cfg(test)]
+pub(crate) fn random_public_key() -> PublicKey {
+    #[cfg(feature = "pubky")]
+    {
+        pubky::Keypair::random().public_key()
+    }
+    #[cfg(not(feature = "pubky"))]
+    {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        const Z_BASE_32: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        // Spell the counter in z-base-32 so the key passes `PublicKey::parse`; the
+        // leading and trailing `y`s keep the length and padding bits valid.
+        let mut n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let mut key = vec![b'y'; PublicKey::ENCODED_LEN];
+        for slot in key[..PublicKey::ENCODED_LEN - 1].iter_mut().rev() {
+            *slot = Z_BASE_32[n % 32];
+            n /= 32;
+        }
+        PublicKey(String::from_utf8(key).expect("z-base-32 is ASCII"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{get_known_contacts, get_payment_endpoint, get_payment_list, set_payment_endpoint};
+
+    #[tokio::test]
+    async fn writes_are_visible_to_reads() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let method = MethodId::new("lightning").unwrap();
+        let data = EndpointData("{\"bolt11\":\"ln...\"}".into());
+
+        set_payment_endpoint(&mock, method.clone(), data.clone())
+            .await
+            .unwrap();
+
+        let fetched = get_payment_endpoint(&mock, &owner, &method).await.unwrap();
+        assert_eq!(fetched, Some(data.clone()));
+        let list = get_payment_list(&mock, &owner).await.unwrap();
+        assert_eq!(list.entries, HashMap::from([(method, data)]));
+    }
+
+    #[tokio::test]
+    async fn missing_data_reads_as_empty() {
+        let mock = MockTransport::new(random_public_key());
+        let stranger = random_public_key();
+        let method = MethodId::new("onchain").unwrap();
+
+        assert!(get_payment_endpoint(&mock, &stranger, &method)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(get_payment_list(&mock, &stranger)
+            .await
+            .unwrap()
+            .entries
+            .is_empty());
+        assert!(get_known_contacts(&mock, &stranger)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn seeded_contacts_are_listed() {
+        let owner = random_public_key();
+        let contact = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_contact(&owner, &contact);
+
+        let contacts = get_known_contacts(&mock, &owner).await.unwrap();
+        assert_eq!(contacts, vec![contact]);
+    }
+
+    #[cfg(not(feature = "pubky"))]
+    #[tokio::test]
+    async fn junk_contacts_are_reported() {
+        let owner = random_public_key();
+        let contact = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_contact(&owner, &contact);
+        mock.insert_contact(&owner, &PublicKey("junk".into()));
+
+        let (contacts, skipped) = crate::get_known_contacts_with_report(&mock, &owner)
+            .await
+            .unwrap();
+        assert_eq!(contacts, vec![contact]);
+        assert_eq!(skipped, vec!["junk".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn contacts_are_listed_in_key_order() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for _ in 0..16 {
+            mock.insert_contact(&owner, &random_public_key());
+        }
+
+        let first = get_known_contacts(&mock, &owner).await.unwrap();
+        let second = get_known_contacts(&mock, &owner).await.unwrap();
+        assert_eq!(first, second);
+        let keys: Vec<_> = first.iter().map(ToString::to_string).collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "{keys:?}");
+    }
+
+    #[tokio::test]
+    async fn injected_failure_hits_only_next_call() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.fail_next("boom");
+
+        let err = get_payment_list(&mock, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(msg) if msg.contains("boom")));
+        assert!(get_payment_list(&mock, &owner).await.is_ok());
+    }
+}
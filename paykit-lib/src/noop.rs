@@ -0,0 +1,153 @@
+//! Inert transport for builds with Paykit switched off.
+//!
+//! [`NoopTransport`] lets apps keep one code path when Paykit is disabled on a
+//! platform or behind a runtime flag: reads see an identity that has published
+//! nothing, and writes fail with `PaykitError::Unimplemented`.
+
+use async_trait::async_trait;
+
+use crate::transport::paths::validate_relative_path;
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{EndpointData, MethodId, PaykitError, PublicKey, Result, SupportedPayments};
+
+const NOOP: &str = "noop transport";
+
+/// Transport that stores nothing and sends nothing.
+///
+/// Every read succeeds with an empty list or `None`, including
+/// `AuthenticatedTransport::list_own_endpoints`. Every write returns
+/// `PaykitError::Unimplemented("noop transport")`.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::noop::NoopTransport;
+/// # use paykit_lib::{get_payment_list, PublicKey};
+/// # async fn demo(pk: &PublicKey) -> paykit_lib::Result<()> {
+/// assert!(get_payment_list(&NoopTransport, pk).await?.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopTransport;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl UnauthenticatedTransportRead for NoopTransport {
+    async fn fetch_supported_payments(&self, _payee: &PublicKey) -> Result<SupportedPayments> {
+        Ok(SupportedPayments::default())
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        _payee: &PublicKey,
+        _method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        Ok(None)
+    }
+
+    async fn fetch_raw(
+        &self,
+        _payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        validate_relative_path(relative_path)?;
+        Ok(None)
+    }
+
+    async fn fetch_known_contacts(&self, _owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl AuthenticatedTransport for NoopTransport {
+    async fn upsert_payment_endpoint(
+        &self,
+        _method: &MethodId,
+        _data: &EndpointData,
+    ) -> Result<()> {
+        Err(PaykitError::Unimplemented(NOOP))
+    }
+
+    async fn remove_payment_endpoint(&self, _method: &MethodId) -> Result<()> {
+        Err(PaykitError::Unimplemented(NOOP))
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        _method: &MethodId,
+        _data: &EndpointData,
+        _expected_etag: Option<String>,
+    ) -> Result<String> {
+        Err(PaykitError::Unimplemented(NOOP))
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        Ok(SupportedPayments::default())
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        Ok(Vec::new())
+    }
+
+    async fn add_contact(&self, _contact: &PublicKey) -> Result<()> {
+        Err(PaykitError::Unimplemented(NOOP))
+    }
+
+    async fn remove_contact(&self, _contact: &PublicKey) -> Result<()> {
+        Err(PaykitError::Unimplemented(NOOP))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::random_public_key;
+    use crate::{
+        add_contact, get_known_contacts, get_payment_endpoint, get_payment_list,
+        has_payment_endpoint, remove_payment_endpoint, set_payment_endpoint,
+    };
+
+    #[tokio::test]
+    async fn reads_are_empty() {
+        let payee = random_public_key();
+        let method = MethodId::new("lightning").unwrap();
+
+        assert!(get_payment_list(&NoopTransport, &payee)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            get_payment_endpoint(&NoopTransport, &payee, &method)
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(!has_payment_endpoint(&NoopTransport, &payee, &method)
+            .await
+            .unwrap());
+        assert!(get_known_contacts(&NoopTransport, &payee)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(NoopTransport.list_own_endpoints().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn writes_are_unimplemented() {
+        let method = MethodId::new("lightning").unwrap();
+        let unimplemented =
+            |result: Result<()>| matches!(result, Err(PaykitError::Unimplemented(NOOP)));
+
+        assert!(unimplemented(
+            set_payment_endpoint(&NoopTransport, &method, EndpointData("ln".into())).await
+        ));
+        assert!(unimplemented(
+            remove_payment_endpoint(&NoopTransport, &method).await
+        ));
+        assert!(unimplemented(
+            add_contact(&NoopTransport, &random_public_key()).await
+        ));
+    }
+}
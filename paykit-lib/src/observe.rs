@@ -0,0 +1,469 @@
+//! Observability hook for transport calls.
+//!
+//! [`ObservedTransport`] reports the start and end of every operation on the wrapped
+//! transport to a [`TransportObserver`], giving integrators a single place to hang
+//! metrics, tracing spans, or logs without the crate depending on any telemetry stack.
+//! With the `metrics` feature, `MetricsObserver` records Prometheus-style metrics
+//! through the `metrics` facade.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Callbacks invoked around each transport operation.
+///
+/// `op` is the trait method name, e.g. `"fetch_supported_payments"`. Both callbacks
+/// default to doing nothing, so implementors only override what they need. They run
+/// inline on the calling task and should return quickly.
+pub trait TransportObserver: Send + Sync {
+    /// Called right before the operation starts.
+    fn on_start(&self, _op: &str) {}
+
+    /// Called once the operation completes, with its wall-clock duration and outcome.
+    fn on_finish(
+        &self,
+        _op: &str,
+        _duration: Duration,
+        _result: std::result::Result<(), &PaykitError>,
+    ) {
+    }
+}
+
+/// Observer that ignores every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopObserver;
+
+impl TransportObserver for NoopObserver {}
+
+/// Observer recording every operation through the [`metrics`] facade. Requires the
+/// `metrics` feature.
+///
+/// Each call increments the counter `paykit_operations_total{op, result}` and records
+/// its duration in the histogram `paykit_operation_duration_seconds{op}`. `op` is the
+/// trait method name; `result` is `ok` or the error class, one of `network`,
+/// `transport`, `http`, `rate_limited`, `unauthorized`, `timeout`, `invalid_data`,
+/// `invalid_method_id`, `payload_too_large`, `conflict`, `batch`, or `unimplemented`.
+/// Nothing is exported until the application installs a recorder, e.g. a Prometheus
+/// exporter.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::observe::{MetricsObserver, ObservedTransport};
+/// # use paykit_lib::UnauthenticatedTransportRead;
+/// # fn wrap<R: UnauthenticatedTransportRead + Sync>(reader: R) {
+/// let reader = ObservedTransport::new(reader, MetricsObserver);
+/// # }
+/// ```
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl TransportObserver for MetricsObserver {
+    fn on_finish(
+        &self,
+        op: &str,
+        duration: Duration,
+        result: std::result::Result<(), &PaykitError>,
+    ) {
+        let outcome = match result {
+            Ok(()) => "ok",
+            Err(err) => error_class(err),
+        };
+        metrics::counter!("paykit_operations_total", "op" => op.to_string(), "result" => outcome)
+            .increment(1);
+        metrics::histogram!("paykit_operation_duration_seconds", "op" => op.to_string())
+            .record(duration.as_secs_f64());
+    }
+}
+
+/// Label value for `err`'s variant, stable across releases.
+#[cfg(feature = "metrics")]
+fn error_class(err: &PaykitError) -> &'static str {
+    match err {
+        PaykitError::Unimplemented(_) => "unimplemented",
+        PaykitError::Transport(_) | PaykitError::TransportSource(_) => "transport",
+        PaykitError::Network(_) => "network",
+        PaykitError::InvalidData(_) => "invalid_data",
+        PaykitError::InvalidMethodId(_) => "invalid_method_id",
+        PaykitError::Batch(_) => "batch",
+        PaykitError::Timeout(_) => "timeout",
+        PaykitError::Conflict { .. } => "conflict",
+        PaykitError::AlreadyExists(_) => "already_exists",
+        PaykitError::PayloadTooLarge { .. } => "payload_too_large",
+        PaykitError::Unauthorized(_) => "unauthorized",
+        PaykitError::RateLimited { .. } => "rate_limited",
+        PaykitError::Server { .. } => "server",
+    }
+}
+
+/// Decorator invoking a [`TransportObserver`] around each call on the wrapped transport.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::observe::{ObservedTransport, TransportObserver};
+/// # use paykit_lib::{get_payment_list, PaykitError, PublicKey, UnauthenticatedTransportRead};
+/// struct Log;
+///
+/// impl TransportObserver for Log {
+///     fn on_finish(&self, op: &str, duration: Duration, result: std::result::Result<(), &PaykitError>) {
+///         println!("{op} took {duration:?} (ok: {})", result.is_ok());
+///     }
+/// }
+///
+/// # async fn demo<R: UnauthenticatedTransportRead + Sync>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let reader = ObservedTransport::new(reader, Log);
+/// let payments = get_payment_list(&reader, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct ObservedTransport<T, O = NoopObserver> {
+    inner: T,
+    observer: O,
+}
+
+impl<T, O> ObservedTransport<T, O> {
+    /// Wrap `inner`, reporting every call to `observer`.
+    pub fn new(inner: T, observer: O) -> Self {
+        Self { inner, observer }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Access the observer.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+}
+
+impl<T, O: TransportObserver> ObservedTransport<T, O> {
+    async fn run<V>(&self, op: &'static str, fut: impl Future<Output = Result<V>>) -> Result<V> {
+        self.observer.on_start(op);
+        let started = Instant::now();
+        let result = fut.await;
+        self.observer
+            .on_finish(op, started.elapsed(), result.as_ref().map(|_| ()));
+        result
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T, O> UnauthenticatedTransportRead for ObservedTransport<T, O>
+where
+    T: UnauthenticatedTransportRead + Sync,
+    O: TransportObserver,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.run(
+            "fetch_supported_payments",
+            self.inner.fetch_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.run(
+            "count_supported_payments",
+            self.inner.count_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.run(
+            "fetch_supported_payments_lenient",
+            self.inner.fetch_supported_payments_lenient(payee),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.run(
+            "fetch_payment_endpoint",
+            self.inner.fetch_payment_endpoint(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.run(
+            "fetch_payment_endpoint_full",
+            self.inner.fetch_payment_endpoint_full(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.run(
+            "fetch_payment_endpoint_exists",
+            self.inner.fetch_payment_endpoint_exists(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.run("fetch_raw", self.inner.fetch_raw(payee, relative_path))
+            .await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.run(
+            "fetch_known_contacts",
+            self.inner.fetch_known_contacts(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.run(
+            "fetch_known_contacts_with_report",
+            self.inner.fetch_known_contacts_with_report(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.run(
+            "fetch_known_contacts_page",
+            self.inner.fetch_known_contacts_page(owner, cursor, limit),
+        )
+        .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T, O> AuthenticatedTransport for ObservedTransport<T, O>
+where
+    T: AuthenticatedTransport + Sync,
+    O: TransportObserver,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint",
+            self.inner.upsert_payment_endpoint(method, data),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint_with_type",
+            self.inner
+                .upsert_payment_endpoint_with_type(method, data, content_type),
+        )
+        .await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.run(
+            "remove_payment_endpoint",
+            self.inner.remove_payment_endpoint(method),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.run(
+            "upsert_payment_endpoint_if_match",
+            self.inner
+                .upsert_payment_endpoint_if_match(method, data, expected_etag),
+        )
+        .await
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.run("list_own_endpoints", self.inner.list_own_endpoints())
+            .await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.run("list_own_contacts", self.inner.list_own_contacts())
+            .await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("add_contact", self.inner.add_contact(contact))
+            .await
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("remove_contact", self.inner.remove_contact(contact))
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::get_payment_list;
+    use crate::mock::{random_public_key, MockTransport};
+
+    #[derive(Default)]
+    struct Recorder {
+        started: Mutex<Vec<String>>,
+        finished: Mutex<Vec<(String, Duration, bool)>>,
+    }
+
+    impl TransportObserver for Recorder {
+        fn on_start(&self, op: &str) {
+            self.started.lock().unwrap().push(op.to_string());
+        }
+
+        fn on_finish(
+            &self,
+            op: &str,
+            duration: Duration,
+            result: std::result::Result<(), &PaykitError>,
+        ) {
+            self.finished
+                .lock()
+                .unwrap()
+                .push((op.to_string(), duration, result.is_ok()));
+        }
+    }
+
+    // Needs `MockTransport::set_latency` to make the duration measurable.
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn reports_start_and_finish_once() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.set_latency(Duration::from_millis(20));
+        let observed = ObservedTransport::new(mock, Recorder::default());
+
+        get_payment_list(&observed, &owner).await.unwrap();
+
+        let recorder = observed.observer();
+        assert_eq!(
+            *recorder.started.lock().unwrap(),
+            vec!["fetch_supported_payments"]
+        );
+        let finished = recorder.finished.lock().unwrap();
+        assert_eq!(finished.len(), 1);
+        let (op, duration, ok) = &finished[0];
+        assert_eq!(op, "fetch_supported_payments");
+        assert!(*duration >= Duration::from_millis(20), "{duration:?}");
+        assert!(ok);
+    }
+
+    #[tokio::test]
+    async fn reports_failures() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.fail_next("offline");
+        let observed = ObservedTransport::new(mock, Recorder::default());
+
+        assert!(get_payment_list(&observed, &owner).await.is_err());
+
+        let finished = observed.observer().finished.lock().unwrap();
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].0, "fetch_supported_payments");
+        assert!(!finished[0].2);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn metrics_observer_counts_operations() {
+        use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let observed = ObservedTransport::new(mock.clone(), MetricsObserver);
+
+        metrics::with_local_recorder(&recorder, || {
+            futures::executor::block_on(async {
+                get_payment_list(&observed, &owner).await.unwrap();
+                mock.fail_next("offline");
+                assert!(get_payment_list(&observed, &owner).await.is_err());
+            })
+        });
+
+        let counter = |result: &str| {
+            snapshotter
+                .snapshot()
+                .into_vec()
+                .into_iter()
+                .find_map(|(key, _, _, value)| {
+                    let labels: Vec<_> = key
+                        .key()
+                        .labels()
+                        .map(|label| (label.key().to_string(), label.value().to_string()))
+                        .collect();
+                    let wanted = [
+                        ("op".to_string(), "fetch_supported_payments".to_string()),
+                        ("result".to_string(), result.to_string()),
+                    ];
+                    (key.key().name() == "paykit_operations_total" && labels == wanted)
+                        .then_some(value)
+                })
+        };
+        assert_eq!(counter("ok"), Some(DebugValue::Counter(1)));
+        assert_eq!(counter("transport"), Some(DebugValue::Counter(1)));
+    }
+}
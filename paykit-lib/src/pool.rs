@@ -0,0 +1,476 @@
+//! Per-payee pool of resolved readers.
+//!
+//! Reading a payee first means finding the homeserver that hosts their data. When a
+//! batch job reads many payees in a loop, or the same payees again and again, that
+//! lookup dominates. [`PooledRead`] asks a [`HomeserverResolver`] for a reader bound
+//! to each payee's homeserver and keeps it for a time-to-live, so repeated reads of the
+//! same payee reuse the resolution.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+#[cfg(feature = "pubky")]
+use crate::transport::pubky::{unauthenticated_transport::PubkyUnauthenticatedTransport, Url};
+use crate::transport::traits::{DynRead, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Default number of payees a [`PooledRead`] keeps resolved.
+pub const DEFAULT_POOL_SIZE: usize = 256;
+
+/// Finds the reader serving a payee's data.
+///
+/// Implementations typically look up the payee's homeserver and return a transport
+/// pointed at it, such as `PubkyUnauthenticatedTransport::with_mirror`.
+/// `PubkyHomeserverResolver` does this through the Pubky SDK.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait HomeserverResolver: Send + Sync {
+    /// Resolve a reader for `payee`.
+    async fn resolve(&self, payee: &PublicKey) -> Result<DynRead>;
+}
+
+/// SVCB parameter in which a `localhost` homeserver publishes its plain HTTP port
+/// (`pubky_common::constants::reserved_param_keys::HTTP_PORT`).
+#[cfg(feature = "pubky")]
+const HTTP_PORT_PARAM: u16 = 65280;
+
+/// [`HomeserverResolver`] that looks payees up through the Pubky SDK.
+///
+/// Each resolution reads the payee's `_pubky` record to find their homeserver, then the
+/// homeserver's own record for its ICANN domain, and returns a copy of the template
+/// reader with [`with_mirror`](PubkyUnauthenticatedTransport::with_mirror) pointed at
+/// that domain. Every read through the pooled reader, listings and contacts included,
+/// then goes straight to the homeserver.
+/// Homeservers without an ICANN domain cannot be resolved.
+///
+/// # Examples
+/// ```no_run
+/// # use std::time::Duration;
+/// # use paykit_lib::pool::{PooledRead, PubkyHomeserverResolver};
+/// # use paykit_lib::{get_payment_endpoint, MethodId, PublicKey};
+/// # async fn demo(pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let pubky = pubky::Pubky::new().expect("default client");
+/// let pooled = PooledRead::new(PubkyHomeserverResolver::new(pubky), Duration::from_secs(300));
+/// let lightning = MethodId::new("lightning")?;
+/// get_payment_endpoint(&pooled, pk, &lightning).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "pubky")]
+#[derive(Clone)]
+pub struct PubkyHomeserverResolver {
+    pubky: pubky::Pubky,
+    reader: PubkyUnauthenticatedTransport,
+}
+
+#[cfg(feature = "pubky")]
+impl PubkyHomeserverResolver {
+    /// Resolve through `pubky`, reading with a default reader on the same client.
+    pub fn new(pubky: pubky::Pubky) -> Self {
+        let reader = PubkyUnauthenticatedTransport::new(pubky.public_storage());
+        Self::with_reader(pubky, reader)
+    }
+
+    /// Resolve through `pubky` and hand out copies of `reader`, keeping its paths,
+    /// limits, and HTTP client.
+    pub fn with_reader(pubky: pubky::Pubky, reader: PubkyUnauthenticatedTransport) -> Self {
+        Self { pubky, reader }
+    }
+
+    /// Base URL of the homeserver hosting `payee`'s data, e.g.
+    /// `https://homeserver.example.com/`.
+    ///
+    /// Fails with `PaykitError::Transport` when the payee has no `_pubky` record or
+    /// their homeserver publishes no ICANN domain.
+    pub async fn homeserver_url(&self, payee: &PublicKey) -> Result<Url> {
+        let unresolved = |reason: &str| {
+            PaykitError::Transport(format!("resolve homeserver of {payee}: {reason}"))
+        };
+        let homeserver = self
+            .pubky
+            .get_homeserver_of(payee)
+            .await
+            .ok_or_else(|| unresolved("no _pubky record"))?;
+
+        let qname = homeserver.to_string();
+        let endpoints = self.pubky.client().pkarr().resolve_https_endpoints(&qname);
+        futures::pin_mut!(endpoints);
+        while let Some(endpoint) = futures::StreamExt::next(&mut endpoints).await {
+            let Some(domain) = endpoint.domain() else {
+                continue;
+            };
+            let http_port = endpoint
+                .get_param(HTTP_PORT_PARAM)
+                .and_then(|bytes| <[u8; 2]>::try_from(bytes).ok())
+                .map(u16::from_be_bytes);
+            let url = match (domain, http_port, endpoint.port()) {
+                ("localhost", Some(port), _) => format!("http://localhost:{port}"),
+                (_, _, Some(port)) => format!("https://{domain}:{port}"),
+                _ => format!("https://{domain}"),
+            };
+            return Url::parse(&url).map_err(|err| unresolved(&err.to_string()));
+        }
+        Err(unresolved(&format!(
+            "{homeserver} publishes no ICANN domain"
+        )))
+    }
+}
+
+#[cfg(feature = "pubky")]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl HomeserverResolver for PubkyHomeserverResolver {
+    async fn resolve(&self, payee: &PublicKey) -> Result<DynRead> {
+        let url = self.homeserver_url(payee).await?;
+        Ok(Arc::new(self.reader.clone().with_mirror(url)))
+    }
+}
+
+/// Reader that resolves each payee once per time-to-live.
+///
+/// Every read is served by the reader the resolver returned for that payee. Entries
+/// expire after the TTL; when the pool is full, the oldest entry makes room for the
+/// new one. A read failing with `PaykitError::Network` drops the payee's entry, since
+/// their homeserver may have moved. Failed resolutions are not pooled. Two concurrent
+/// first reads of one payee may both resolve it.
+///
+/// Clones share both the resolver and the pool.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::pool::{HomeserverResolver, PooledRead};
+/// # use paykit_lib::{get_payment_list, PublicKey};
+/// # async fn demo(resolver: impl HomeserverResolver, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let pooled = PooledRead::new(resolver, Duration::from_secs(300)).with_max_size(1_000);
+/// get_payment_list(&pooled, pk).await?;
+/// // Reuses the reader resolved above.
+/// get_payment_list(&pooled, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PooledRead<Res> {
+    resolver: Arc<Res>,
+    ttl: Duration,
+    max_size: usize,
+    pool: Arc<Mutex<HashMap<PublicKey, (Instant, DynRead)>>>,
+}
+
+impl<Res> Clone for PooledRead<Res> {
+    fn clone(&self) -> Self {
+        Self {
+            resolver: Arc::clone(&self.resolver),
+            ttl: self.ttl,
+            max_size: self.max_size,
+            pool: Arc::clone(&self.pool),
+        }
+    }
+}
+
+impl<Res> PooledRead<Res> {
+    /// Pool readers from `resolver` for `ttl`, keeping up to [`DEFAULT_POOL_SIZE`]
+    /// payees.
+    pub fn new(resolver: Res, ttl: Duration) -> Self {
+        Self {
+            resolver: Arc::new(resolver),
+            ttl,
+            max_size: DEFAULT_POOL_SIZE,
+            pool: Arc::default(),
+        }
+    }
+
+    /// Keep at most `max_size` payees resolved. Zero disables pooling.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Access the resolver.
+    pub fn resolver(&self) -> &Res {
+        &self.resolver
+    }
+
+    /// Configured time-to-live for pooled entries.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Configured maximum number of pooled payees.
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Number of payees currently pooled, including expired entries not yet evicted.
+    pub fn len(&self) -> usize {
+        self.pool().len()
+    }
+
+    /// Whether no payee is pooled.
+    pub fn is_empty(&self) -> bool {
+        self.pool().is_empty()
+    }
+
+    /// Drop `payee`'s entry so the next read resolves them again.
+    pub fn invalidate(&self, payee: &PublicKey) {
+        self.pool().remove(payee);
+    }
+
+    /// Drop every entry.
+    pub fn clear(&self) {
+        self.pool().clear();
+    }
+
+    fn pool(&self) -> MutexGuard<'_, HashMap<PublicKey, (Instant, DynRead)>> {
+        self.pool.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn settle<T>(&self, payee: &PublicKey, result: Result<T>) -> Result<T> {
+        if let Err(PaykitError::Network(_)) = &result {
+            self.invalidate(payee);
+        }
+        result
+    }
+}
+
+impl<Res> PooledRead<Res>
+where
+    Res: HomeserverResolver,
+{
+    async fn reader_for(&self, payee: &PublicKey) -> Result<DynRead> {
+        if let Some((stored, reader)) = self.pool().get(payee) {
+            if stored.elapsed() < self.ttl {
+                return Ok(Arc::clone(reader));
+            }
+        }
+        let reader = self.resolver.resolve(payee).await?;
+        if self.max_size > 0 {
+            let mut pool = self.pool();
+            pool.retain(|_, (stored, _)| stored.elapsed() < self.ttl);
+            if pool.len() >= self.max_size && !pool.contains_key(payee) {
+                let oldest = pool
+                    .iter()
+                    .min_by_key(|(_, (stored, _))| *stored)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    pool.remove(&oldest);
+                }
+            }
+            pool.insert(payee.clone(), (Instant::now(), Arc::clone(&reader)));
+        }
+        Ok(reader)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<Res> UnauthenticatedTransportRead for PooledRead<Res>
+where
+    Res: HomeserverResolver,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(payee, reader.fetch_supported_payments(payee).await)
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(payee, reader.count_supported_payments(payee).await)
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(payee, reader.fetch_supported_payments_lenient(payee).await)
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(payee, reader.fetch_payment_endpoint(payee, method).await)
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(
+            payee,
+            reader.fetch_payment_endpoint_full(payee, method).await,
+        )
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(
+            payee,
+            reader.fetch_payment_endpoint_exists(payee, method).await,
+        )
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        let reader = self.reader_for(payee).await?;
+        self.settle(payee, reader.fetch_raw(payee, relative_path).await)
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        let reader = self.reader_for(owner).await?;
+        self.settle(owner, reader.fetch_known_contacts(owner).await)
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        let reader = self.reader_for(owner).await?;
+        self.settle(owner, reader.fetch_known_contacts_with_report(owner).await)
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        let reader = self.reader_for(owner).await?;
+        self.settle(
+            owner,
+            reader.fetch_known_contacts_page(owner, cursor, limit).await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{get_payment_endpoint, get_payment_list};
+
+    /// Resolves every payee to the same reader and counts the lookups.
+    struct CountingResolver {
+        reader: DynRead,
+        resolutions: AtomicUsize,
+    }
+
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    impl HomeserverResolver for CountingResolver {
+        async fn resolve(&self, _payee: &PublicKey) -> Result<DynRead> {
+            self.resolutions.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::clone(&self.reader))
+        }
+    }
+
+    /// Reader whose homeserver is unreachable.
+    struct Unreachable;
+
+    #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+    impl UnauthenticatedTransportRead for Unreachable {
+        async fn fetch_supported_payments(&self, _payee: &PublicKey) -> Result<SupportedPayments> {
+            Err(PaykitError::Network("connection reset".into()))
+        }
+
+        async fn fetch_payment_endpoint(
+            &self,
+            _payee: &PublicKey,
+            _method: &MethodId,
+        ) -> Result<Option<EndpointData>> {
+            Err(PaykitError::Network("connection reset".into()))
+        }
+
+        async fn fetch_known_contacts(&self, _owner: &PublicKey) -> Result<Vec<PublicKey>> {
+            Err(PaykitError::Network("connection reset".into()))
+        }
+    }
+
+    fn pooled(reader: DynRead, ttl: Duration) -> PooledRead<CountingResolver> {
+        let resolver = CountingResolver {
+            reader,
+            resolutions: AtomicUsize::new(0),
+        };
+        PooledRead::new(resolver, ttl)
+    }
+
+    fn seeded() -> (MockTransport, PublicKey) {
+        let payee = random_public_key();
+        let mock = MockTransport::new(payee.clone());
+        mock.insert_endpoint(
+            &payee,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        );
+        (mock, payee)
+    }
+
+    fn resolutions(pooled: &PooledRead<CountingResolver>) -> usize {
+        pooled.resolver().resolutions.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn repeated_reads_resolve_once() {
+        let (mock, payee) = seeded();
+        let pooled = pooled(Arc::new(mock.clone()), Duration::from_secs(60));
+        let method = MethodId::new("lightning").unwrap();
+
+        get_payment_list(&pooled, &payee).await.unwrap();
+        let endpoint = get_payment_endpoint(&pooled, &payee, &method)
+            .await
+            .unwrap();
+
+        assert_eq!(endpoint, Some(EndpointData("ln".into())));
+        assert_eq!(resolutions(&pooled), 1);
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+        assert_eq!(pooled.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_and_evicted_payees_resolve_again() {
+        let (mock, payee) = seeded();
+        let expiring = pooled(Arc::new(mock.clone()), Duration::ZERO);
+        get_payment_list(&expiring, &payee).await.unwrap();
+        get_payment_list(&expiring, &payee).await.unwrap();
+        assert_eq!(resolutions(&expiring), 2);
+
+        let small = pooled(Arc::new(mock), Duration::from_secs(60)).with_max_size(1);
+        get_payment_list(&small, &payee).await.unwrap();
+        get_payment_list(&small, &random_public_key())
+            .await
+            .unwrap();
+        assert_eq!(small.len(), 1);
+        get_payment_list(&small, &payee).await.unwrap();
+        assert_eq!(resolutions(&small), 3);
+    }
+
+    #[tokio::test]
+    async fn network_errors_drop_the_entry() {
+        let pooled = pooled(Arc::new(Unreachable), Duration::from_secs(60));
+        let payee = random_public_key();
+
+        assert!(get_payment_list(&pooled, &payee).await.is_err());
+        assert!(pooled.is_empty());
+        assert!(get_payment_list(&pooled, &payee).await.is_err());
+        assert_eq!(resolutions(&pooled), 2);
+    }
+}
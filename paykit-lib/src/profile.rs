@@ -0,0 +1,201 @@
+//! Offline snapshots of a payee's Paykit footprint.
+//!
+//! [`export_profile`] captures everything a payee publishes (payment endpoints and
+//! known contacts) in one [`PaykitProfile`], and [`import_profile`] republishes a
+//! snapshot through another session, e.g. to back up an account or move it to a new
+//! key. With the `serde` feature the profile serializes to a single JSON document.
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{map_transport_error, set_payment_endpoints, PublicKey, Result, SupportedPayments};
+
+/// Everything a payee publishes through Paykit at one point in time.
+///
+/// With the `serde` feature, keys serialize as z-base-32 strings, e.g.
+/// `{"owner":"o4dk...","payments":{"lightning":"lnbc1..."},"contacts":["8pin..."]}`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaykitProfile {
+    /// Key the snapshot was taken from.
+    #[cfg_attr(feature = "serde", serde(with = "key_string"))]
+    pub owner: PublicKey,
+    /// Published payment endpoints.
+    pub payments: SupportedPayments,
+    /// Known contacts, sorted by key.
+    #[cfg_attr(feature = "serde", serde(with = "key_strings"))]
+    pub contacts: Vec<PublicKey>,
+}
+
+/// Reads `payee`'s payment list and known contacts into a [`PaykitProfile`].
+///
+/// Both reads go through `reader` concurrently; the first failure is returned and the
+/// partial snapshot discarded.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{export_profile, import_profile, PublicKey};
+/// # use paykit_lib::{AuthenticatedTransport, UnauthenticatedTransportRead};
+/// # async fn migrate(
+/// #     reader: &(impl UnauthenticatedTransportRead + Sync),
+/// #     old_key: &PublicKey,
+/// #     new_session: &(impl AuthenticatedTransport + Sync),
+/// # ) -> paykit_lib::Result<()> {
+/// let profile = export_profile(reader, old_key).await?;
+/// import_profile(new_session, &profile).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn export_profile<R>(reader: &R, payee: &PublicKey) -> Result<PaykitProfile>
+where
+    R: UnauthenticatedTransportRead + ?Sized,
+{
+    let (payments, contacts) = futures::try_join!(
+        async {
+            reader
+                .fetch_supported_payments(payee)
+                .await
+                .map_err(|err| map_transport_error("export_profile", err))
+        },
+        async {
+            reader
+                .fetch_known_contacts(payee)
+                .await
+                .map_err(|err| map_transport_error("export_profile", err))
+        },
+    )?;
+    Ok(PaykitProfile {
+        owner: payee.clone(),
+        payments,
+        contacts,
+    })
+}
+
+/// Republishes `profile`'s endpoints and contacts as the identity behind `client`.
+///
+/// # Semantics
+/// - Additive: endpoints and contacts the identity already has but the profile lacks
+///   are kept. Pass `profile.payments` to [`crate::set_payment_list`] to converge
+///   instead.
+/// - Endpoints are written first, concurrently; partial failures surface as
+///   `PaykitError::Batch` before any contact is touched. Contacts are then added one
+///   by one, stopping at the first failure.
+/// - `profile.owner` is not checked against the session, so a snapshot can be imported
+///   under a different key. Importing twice is harmless.
+pub async fn import_profile<S>(client: &S, profile: &PaykitProfile) -> Result<()>
+where
+    S: AuthenticatedTransport + Sync + ?Sized,
+{
+    set_payment_endpoints(client, profile.payments.clone()).await?;
+    for contact in &profile.contacts {
+        client
+            .add_contact(contact)
+            .await
+            .map_err(|err| map_transport_error("import_profile", err))?;
+    }
+    Ok(())
+}
+
+/// Serializes a key as its z-base-32 string, whichever `PublicKey` type is in use.
+#[cfg(feature = "serde")]
+mod key_string {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    use crate::PublicKey;
+
+    pub fn serialize<S: Serializer>(key: &PublicKey, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(key)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PublicKey, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        crate::parse_public_key(&encoded).map_err(D::Error::custom)
+    }
+}
+
+/// Like [`key_string`], for a list of keys.
+#[cfg(feature = "serde")]
+mod key_strings {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    use crate::PublicKey;
+
+    pub fn serialize<S: Serializer>(keys: &[PublicKey], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(keys.iter().map(ToString::to_string))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<PublicKey>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|encoded| crate::parse_public_key(encoded).map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{EndpointData, MethodId, PaykitError};
+
+    fn seeded() -> (MockTransport, PublicKey) {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.insert_endpoint(
+            &owner,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("lnbc1...".into()),
+        );
+        mock.insert_endpoint(
+            &owner,
+            MethodId::new("onchain").unwrap(),
+            EndpointData("bc1q...".into()),
+        );
+        mock.insert_contact(&owner, &random_public_key());
+        mock.insert_contact(&owner, &random_public_key());
+        (mock, owner)
+    }
+
+    #[tokio::test]
+    async fn round_trips_between_accounts() {
+        let (source, old_key) = seeded();
+        let profile = export_profile(&source, &old_key).await.unwrap();
+        assert_eq!(profile.owner, old_key);
+        assert_eq!(profile.payments.len(), 2);
+        assert_eq!(profile.contacts.len(), 2);
+
+        let new_key = random_public_key();
+        let target = MockTransport::new(new_key.clone());
+        import_profile(&target, &profile).await.unwrap();
+
+        let copied = export_profile(&target, &new_key).await.unwrap();
+        assert_eq!(copied.owner, new_key);
+        assert_eq!(copied.payments, profile.payments);
+        assert_eq!(copied.contacts, profile.contacts);
+    }
+
+    #[tokio::test]
+    async fn export_fails_as_a_whole() {
+        let (source, owner) = seeded();
+        source.fail_contacts_of(&owner, "follows unavailable");
+        let err = export_profile(&source, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)), "{err:?}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn json_round_trip() {
+        let (source, owner) = seeded();
+        let profile = export_profile(&source, &owner).await.unwrap();
+
+        let json = serde_json::to_value(&profile).unwrap();
+        assert_eq!(json["owner"], owner.to_string());
+        assert_eq!(json["payments"]["lightning"], "lnbc1...");
+        assert_eq!(json["contacts"][0], profile.contacts[0].to_string());
+        let decoded: PaykitProfile = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, profile);
+
+        let bad = serde_json::json!({ "owner": "nope", "payments": {}, "contacts": [] });
+        assert!(serde_json::from_value::<PaykitProfile>(bad).is_err());
+    }
+}
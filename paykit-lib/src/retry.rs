@@ -0,0 +1,489 @@
+//! Retrying decorator with exponential backoff.
+//!
+//! [`RetryingTransport`] wraps any [`UnauthenticatedTransportRead`] and/or
+//! [`AuthenticatedTransport`] and retries operations whose error is transient (see
+//! [`PaykitError::is_transient`](crate::PaykitError::is_transient)): network failures,
+//! timeouts, `PaykitError::RateLimited`, and `PaykitError::Server` 5xx and 429 responses.
+//! When a rate-limited response advises a delay, that delay replaces the backoff; a delay
+//! longer than `RetryConfig::max_delay` ends the retries with the `RateLimited` error.
+//! Other HTTP statuses, local and resolution failures (`Transport`), data errors
+//! (`InvalidData`, `InvalidMethodId`), and `Unimplemented` are returned immediately since
+//! retrying cannot fix them.
+//!
+//! Requires the `tokio` feature for the backoff timer.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Retry policy for [`RetryingTransport`].
+///
+/// The delay before retry `n` (1-based) is `base_delay * 2^(n-1)`, capped at
+/// `max_delay`, with equal jitter applied (a random value between half the delay and
+/// the full delay) so concurrent callers do not retry in lockstep.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Total attempts including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound for any single delay.
+    ///
+    /// A server-advised `Retry-After` above this bound is not waited out; the
+    /// `PaykitError::RateLimited` error is returned instead.
+    pub max_delay: Duration,
+    /// Whether deletes (`remove_payment_endpoint`, `remove_contact`) may be retried.
+    ///
+    /// Off by default: a delete that reached the homeserver but whose response was lost
+    /// fails with not-found when retried, masking the original success.
+    pub retry_deletes: bool,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            retry_deletes: false,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Set the total number of attempts (clamped to at least one).
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry.
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound for any single delay.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Allow retrying deletes.
+    pub fn retry_deletes(mut self, retry_deletes: bool) -> Self {
+        self.retry_deletes = retry_deletes;
+        self
+    }
+
+    /// Jittered delay to wait before retry number `retry` (1-based).
+    fn delay_for(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let capped = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        let half = capped / 2;
+        let spread = (capped - half).as_nanos() as u64;
+        if spread == 0 {
+            return capped;
+        }
+        let random = RandomState::new().build_hasher().finish();
+        half + Duration::from_nanos(random % (spread + 1))
+    }
+}
+
+/// Decorator that retries transient transport failures.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::retry::{RetryConfig, RetryingTransport};
+/// # use paykit_lib::{get_payment_list, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo<R: UnauthenticatedTransportRead + Sync>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let config = RetryConfig::default()
+///     .max_attempts(5)
+///     .base_delay(Duration::from_millis(50));
+/// let reader = RetryingTransport::new(reader, config);
+/// let payments = get_payment_list(&reader, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RetryingTransport<T> {
+    inner: T,
+    config: RetryConfig,
+}
+
+impl<T> RetryingTransport<T> {
+    /// Wrap `inner` with the given retry policy.
+    pub fn new(inner: T, config: RetryConfig) -> Self {
+        Self { inner, config }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Active retry policy.
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    async fn run<F, Fut, V>(&self, retryable: bool, mut op: F) -> Result<V>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<V>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Err(err)
+                    if retryable && attempt < self.config.max_attempts && err.is_transient() =>
+                {
+                    let delay = match err {
+                        PaykitError::RateLimited {
+                            retry_after: Some(delay),
+                        } if delay > self.config.max_delay => return Err(err),
+                        PaykitError::RateLimited {
+                            retry_after: Some(delay),
+                        } => delay,
+                        _ => self.config.delay_for(attempt),
+                    };
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> UnauthenticatedTransportRead for RetryingTransport<T>
+where
+    T: UnauthenticatedTransportRead + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.run(true, || self.inner.fetch_supported_payments(payee))
+            .await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.run(true, || self.inner.count_supported_payments(payee))
+            .await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.run(true, || self.inner.fetch_supported_payments_lenient(payee))
+            .await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.run(true, || self.inner.fetch_payment_endpoint(payee, method))
+            .await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.run(true, || {
+            self.inner.fetch_payment_endpoint_full(payee, method)
+        })
+        .await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.run(true, || {
+            self.inner.fetch_payment_endpoint_exists(payee, method)
+        })
+        .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.run(true, || self.inner.fetch_raw(payee, relative_path))
+            .await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.run(true, || self.inner.fetch_known_contacts(owner))
+            .await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.run(true, || self.inner.fetch_known_contacts_with_report(owner))
+            .await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.run(true, || {
+            self.inner
+                .fetch_known_contacts_page(owner, cursor.clone(), limit)
+        })
+        .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run(true, || self.inner.health_check()).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> AuthenticatedTransport for RetryingTransport<T>
+where
+    T: AuthenticatedTransport + Sync,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        // Upserts are idempotent: writing the same payload twice is harmless.
+        self.run(true, || self.inner.upsert_payment_endpoint(method, data))
+            .await
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.run(true, || {
+            self.inner
+                .upsert_payment_endpoint_with_type(method, data, content_type)
+        })
+        .await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.run(self.config.retry_deletes, || {
+            self.inner.remove_payment_endpoint(method)
+        })
+        .await
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        // Never retried: if the first attempt landed, the retry sees the new ETag and
+        // reports a conflict, masking the success.
+        self.inner
+            .upsert_payment_endpoint_if_match(method, data, expected_etag)
+            .await
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.run(true, || self.inner.list_own_endpoints()).await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.run(true, || self.inner.list_own_contacts()).await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run(true, || self.inner.add_contact(contact)).await
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run(self.config.retry_deletes, || {
+            self.inner.remove_contact(contact)
+        })
+        .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run(true, || self.inner.health_check()).await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{get_payment_list, remove_payment_endpoint, set_payment_endpoint, PaykitError};
+
+    fn fast(max_attempts: u32) -> RetryConfig {
+        RetryConfig::default()
+            .max_attempts(max_attempts)
+            .base_delay(Duration::from_millis(1))
+            .max_delay(Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.fail_next_network("flaky");
+        mock.fail_next_network("flaky");
+        let retrying = RetryingTransport::new(mock.clone(), fast(3));
+
+        get_payment_list(&retrying, &owner).await.unwrap();
+        assert_eq!(mock.call_count("fetch_supported_payments"), 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limits_wait_the_advised_delay() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let advised = Duration::from_millis(150);
+        mock.rate_limit_next(Some(advised));
+        let config = fast(3).max_delay(Duration::from_millis(200));
+        let retrying = RetryingTransport::new(mock.clone(), config);
+
+        let started = std::time::Instant::now();
+        get_payment_list(&retrying, &owner).await.unwrap();
+        assert!(started.elapsed() >= advised, "{:?}", started.elapsed());
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn rate_limits_beyond_max_delay_give_up() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        let advised = Duration::from_secs(3600);
+        mock.rate_limit_next(Some(advised));
+        let retrying = RetryingTransport::new(mock.clone(), fast(3));
+
+        let started = std::time::Instant::now();
+        let err = get_payment_list(&retrying, &owner).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(matches!(
+            err,
+            PaykitError::RateLimited { retry_after: Some(delay) } if delay == advised
+        ));
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        for _ in 0..3 {
+            mock.fail_next_network("down");
+        }
+        let retrying = RetryingTransport::new(mock.clone(), fast(2));
+
+        let err = get_payment_list(&retrying, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Network(_)));
+        assert_eq!(mock.call_count("fetch_supported_payments"), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_local_transport_errors() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.fail_next("unresolvable homeserver");
+        let retrying = RetryingTransport::new(mock.clone(), fast(3));
+
+        let err = get_payment_list(&retrying, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)));
+        assert_eq!(mock.call_count("fetch_supported_payments"), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_unimplemented() {
+        struct Unsupported;
+
+        #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+        #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+        impl AuthenticatedTransport for Unsupported {
+            async fn upsert_payment_endpoint(&self, _: &MethodId, _: &EndpointData) -> Result<()> {
+                Err(PaykitError::Unimplemented("upsert"))
+            }
+
+            async fn remove_payment_endpoint(&self, _: &MethodId) -> Result<()> {
+                Err(PaykitError::Unimplemented("remove"))
+            }
+        }
+
+        let retrying = RetryingTransport::new(Unsupported, fast(3));
+        let err = set_payment_endpoint(
+            &retrying,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, PaykitError::Unimplemented(_)));
+    }
+
+    #[tokio::test]
+    async fn deletes_retry_only_when_enabled() {
+        let mock = MockTransport::new(random_public_key());
+        let method = MethodId::new("lightning").unwrap();
+
+        let strict = RetryingTransport::new(mock.clone(), fast(3));
+        set_payment_endpoint(&strict, method.clone(), EndpointData("ln".into()))
+            .await
+            .unwrap();
+        mock.fail_next_network("flaky");
+        assert!(remove_payment_endpoint(&strict, method.clone())
+            .await
+            .is_err());
+        assert_eq!(mock.call_count("remove_payment_endpoint"), 1);
+
+        let lenient = RetryingTransport::new(mock.clone(), fast(3).retry_deletes(true));
+        mock.fail_next_network("flaky");
+        remove_payment_endpoint(&lenient, method).await.unwrap();
+        assert_eq!(mock.call_count("remove_payment_endpoint"), 3);
+    }
+
+    #[test]
+    fn only_transient_http_statuses_retry() {
+        let http = |status| PaykitError::Server {
+            status,
+            message: String::new(),
+        };
+        assert!(http(503).is_transient());
+        assert!(http(429).is_transient());
+        assert!(!http(404).is_transient());
+        assert!(!http(401).is_transient());
+    }
+
+    #[test]
+    fn delay_grows_and_is_capped() {
+        let config = RetryConfig::default()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(300));
+        let first = config.delay_for(1);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let capped = config.delay_for(10);
+        assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+    }
+}
@@ -0,0 +1,315 @@
+//! Reference-counted transport handles.
+//!
+//! [`SharedRead`] and [`SharedAuth`] put a transport behind an [`Arc`] so that many
+//! tasks can use one instance. Cloning a handle only bumps a reference count. The
+//! wrapped transport is never cloned, so its HTTP client, connection pool, and any
+//! decorator state such as a cache are built once and shared by every task.
+//!
+//! `PubkyUnauthenticatedTransport` and `PubkyAuthenticatedTransport` are `Clone`
+//! too, but each clone copies the adapter's fields. Prefer a shared handle when
+//! the transport is not `Clone`, when it is a stack of decorators you only want to
+//! build once, or when handing it to `tokio::spawn` in a loop. A plain clone is
+//! fine for a few long-lived owners.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Cheaply clonable handle to a shared [`UnauthenticatedTransportRead`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::shared::SharedRead;
+/// # use paykit_lib::{get_payment_list, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo(reader: impl UnauthenticatedTransportRead + Send + Sync + 'static, payees: Vec<PublicKey>) {
+/// let reader = SharedRead::new(reader);
+/// for payee in payees {
+///     let reader = reader.clone();
+///     tokio::spawn(async move { get_payment_list(&reader, &payee).await });
+/// }
+/// # }
+/// ```
+pub struct SharedRead<R: ?Sized> {
+    inner: Arc<R>,
+}
+
+impl<R> SharedRead<R> {
+    /// Move `reader` behind a new reference count.
+    pub fn new(reader: R) -> Self {
+        Self {
+            inner: Arc::new(reader),
+        }
+    }
+}
+
+impl<R: ?Sized> SharedRead<R> {
+    /// Access the shared reader.
+    pub fn inner(&self) -> &Arc<R> {
+        &self.inner
+    }
+}
+
+impl<R: ?Sized> Clone for SharedRead<R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<R: ?Sized> Deref for SharedRead<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R: ?Sized> From<Arc<R>> for SharedRead<R> {
+    fn from(inner: Arc<R>) -> Self {
+        Self { inner }
+    }
+}
+
+/// Cheaply clonable handle to a shared [`AuthenticatedTransport`].
+///
+/// Writes from every clone go through the same session.
+pub struct SharedAuth<T: ?Sized> {
+    inner: Arc<T>,
+}
+
+impl<T> SharedAuth<T> {
+    /// Move `client` behind a new reference count.
+    pub fn new(client: T) -> Self {
+        Self {
+            inner: Arc::new(client),
+        }
+    }
+}
+
+impl<T: ?Sized> SharedAuth<T> {
+    /// Access the shared client.
+    pub fn inner(&self) -> &Arc<T> {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> Clone for SharedAuth<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for SharedAuth<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: ?Sized> From<Arc<T>> for SharedAuth<T> {
+    fn from(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<R> UnauthenticatedTransportRead for SharedRead<R>
+where
+    R: UnauthenticatedTransportRead + Send + Sync + ?Sized,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.inner.fetch_supported_payments(payee).await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.inner.count_supported_payments(payee).await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.inner.fetch_supported_payments_lenient(payee).await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_payment_endpoint(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.inner.fetch_payment_endpoint_full(payee, method).await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.inner
+            .fetch_payment_endpoint_exists(payee, method)
+            .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.inner.fetch_raw(payee, relative_path).await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.inner.fetch_known_contacts(owner).await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.inner.fetch_known_contacts_with_report(owner).await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.inner
+            .fetch_known_contacts_page(owner, cursor, limit)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> AuthenticatedTransport for SharedAuth<T>
+where
+    T: AuthenticatedTransport + Send + Sync + ?Sized,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.inner.upsert_payment_endpoint(method, data).await
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.inner
+            .upsert_payment_endpoint_with_type(method, data, content_type)
+            .await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.inner.remove_payment_endpoint(method).await
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.inner
+            .upsert_payment_endpoint_if_match(method, data, expected_etag)
+            .await
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.inner.list_own_endpoints().await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.inner.list_own_contacts().await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.inner.add_contact(contact).await
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.inner.remove_contact(contact).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.inner.health_check().await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{get_payment_list, set_payment_endpoint};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn spawned_tasks_share_one_transport() {
+        let payee = random_public_key();
+        let mock = MockTransport::new(payee.clone());
+        let writer = SharedAuth::new(mock.clone());
+        set_payment_endpoint(
+            &writer,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        )
+        .await
+        .unwrap();
+
+        let reader = SharedRead::new(mock.clone());
+        let tasks: Vec<_> = (0..16)
+            .map(|_| {
+                let reader = reader.clone();
+                let payee = payee.clone();
+                tokio::spawn(async move { get_payment_list(&reader, &payee).await })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap().unwrap().len(), 1);
+        }
+
+        assert_eq!(mock.call_count("fetch_supported_payments"), 16);
+        assert_eq!(Arc::strong_count(reader.inner()), 1);
+    }
+
+    #[tokio::test]
+    async fn trait_objects_can_be_shared() {
+        let payee = random_public_key();
+        let mock = MockTransport::new(payee.clone());
+        let dynamic: Arc<dyn UnauthenticatedTransportRead + Send + Sync> = Arc::new(mock);
+        let reader = SharedRead::from(dynamic);
+
+        assert!(get_payment_list(&reader.clone(), &payee)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,152 @@
+//! Endpoint payloads signed with the payee's key.
+//!
+//! A payee can wrap a payload in a [`SignedEndpoint`] envelope, a JSON object holding the
+//! payload and a base64 Ed25519 signature over its UTF-8 bytes. Payers then check the
+//! envelope against the payee's public key with [`SignedEndpoint::verify`], so a payload
+//! served by a compromised homeserver or mirror is detected. Requires the `signed`
+//! feature.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::Signature;
+use pubky::Keypair;
+use serde::{Deserialize, Serialize};
+
+use crate::{EndpointData, PaykitError, PublicKey, Result};
+
+/// Length in bytes of an Ed25519 signature.
+pub const SIGNATURE_LEN: usize = 64;
+
+/// An endpoint payload together with the payee's signature over it.
+///
+/// On the wire this is `{"payload":"...","signature":"<base64>"}`.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::signed::SignedEndpoint;
+/// let keypair = pubky::Keypair::random();
+/// let data = SignedEndpoint::sign(&keypair, "lnbc1...").to_endpoint_data();
+///
+/// let envelope = SignedEndpoint::try_from(&data)?;
+/// assert!(envelope.verify(&keypair.public_key())?);
+/// # Ok::<(), paykit_lib::PaykitError>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedEndpoint {
+    /// The payload as published.
+    pub payload: String,
+    /// Ed25519 signature over the payload's UTF-8 bytes.
+    pub signature: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Envelope {
+    payload: String,
+    signature: String,
+}
+
+impl SignedEndpoint {
+    /// Sign `payload` with `keypair`.
+    pub fn sign(keypair: &Keypair, payload: impl Into<String>) -> Self {
+        let payload = payload.into();
+        let signature = keypair.sign(payload.as_bytes()).to_bytes().to_vec();
+        Self { payload, signature }
+    }
+
+    /// Encode the envelope as an endpoint body.
+    pub fn to_endpoint_data(&self) -> EndpointData {
+        let envelope = Envelope {
+            payload: self.payload.clone(),
+            signature: BASE64.encode(&self.signature),
+        };
+        EndpointData(serde_json::to_string(&envelope).expect("envelope serializes"))
+    }
+
+    /// Check the signature against `payee`'s key.
+    ///
+    /// Returns `Ok(false)` when the signature does not match the payload or the key, and
+    /// `PaykitError::InvalidData` when it is not [`SIGNATURE_LEN`] bytes long.
+    pub fn verify(&self, payee: &PublicKey) -> Result<bool> {
+        let signature = Signature::from_slice(&self.signature).map_err(|_| {
+            PaykitError::InvalidData(format!(
+                "signed endpoint: signature is {} bytes, expected {SIGNATURE_LEN}",
+                self.signature.len()
+            ))
+        })?;
+        Ok(payee.verify(self.payload.as_bytes(), &signature).is_ok())
+    }
+}
+
+impl TryFrom<&EndpointData> for SignedEndpoint {
+    type Error = PaykitError;
+
+    /// Decode an envelope, failing with `PaykitError::InvalidData` when the body is not a
+    /// well-formed envelope. The signature itself is not checked.
+    fn try_from(data: &EndpointData) -> Result<Self> {
+        let envelope: Envelope = serde_json::from_str(&data.0)
+            .map_err(|err| PaykitError::InvalidData(format!("signed endpoint: {err}")))?;
+        let signature = BASE64.decode(&envelope.signature).map_err(|err| {
+            PaykitError::InvalidData(format!("signed endpoint: bad signature encoding: {err}"))
+        })?;
+        Ok(Self {
+            payload: envelope.payload,
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(envelope: &SignedEndpoint) -> SignedEndpoint {
+        SignedEndpoint::try_from(&envelope.to_endpoint_data()).unwrap()
+    }
+
+    #[test]
+    fn valid_signature_verifies() {
+        let keypair = Keypair::random();
+        let envelope = round_trip(&SignedEndpoint::sign(&keypair, "lnbc1..."));
+        assert_eq!(envelope.payload, "lnbc1...");
+        assert_eq!(envelope.signature.len(), SIGNATURE_LEN);
+        assert!(envelope.verify(&keypair.public_key()).unwrap());
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let keypair = Keypair::random();
+        let mut envelope = SignedEndpoint::sign(&keypair, "lnbc1...");
+        envelope.payload = "lnbc2...".into();
+        assert!(!round_trip(&envelope).verify(&keypair.public_key()).unwrap());
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let envelope = SignedEndpoint::sign(&Keypair::random(), "lnbc1...");
+        assert!(!envelope.verify(&Keypair::random().public_key()).unwrap());
+    }
+
+    #[test]
+    fn malformed_envelopes_are_rejected() {
+        let payee = Keypair::random().public_key();
+        for body in [
+            "lnbc1...",
+            "{\"payload\":\"lnbc1...\"}",
+            "{\"payload\":\"lnbc1...\",\"signature\":\"not base64!\"}",
+            "{\"payload\":\"x\",\"signature\":\"\",\"extra\":1}",
+        ] {
+            let err = SignedEndpoint::try_from(&EndpointData(body.into())).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{body}: {err:?}"
+            );
+        }
+
+        let short = SignedEndpoint {
+            payload: "lnbc1...".into(),
+            signature: vec![0; 10],
+        };
+        let err = short.verify(&payee).unwrap_err();
+        assert!(matches!(err, PaykitError::InvalidData(_)), "{err:?}");
+    }
+}
@@ -0,0 +1,307 @@
+//! Per-operation timeout decorator.
+//!
+//! [`TimeoutTransport`] bounds every call on the wrapped transport so a stalled
+//! homeserver cannot block the caller indefinitely. Elapsed deadlines surface as
+//! `PaykitError::Timeout`; any other failure from the inner transport is returned
+//! unchanged.
+//!
+//! Requires the `tokio` feature.
+
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::transport::traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Decorator applying a fixed deadline to each transport operation.
+///
+/// Combine with [`crate::retry::RetryingTransport`] by wrapping the timeout inside the
+/// retry layer if each attempt should get its own deadline.
+///
+/// # Examples
+/// ```
+/// # use std::time::Duration;
+/// # use paykit_lib::timeout::TimeoutTransport;
+/// # use paykit_lib::{get_payment_list, PublicKey, UnauthenticatedTransportRead};
+/// # async fn demo<R: UnauthenticatedTransportRead + Sync>(reader: R, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// let reader = TimeoutTransport::new(reader, Duration::from_secs(5));
+/// let payments = get_payment_list(&reader, pk).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TimeoutTransport<T> {
+    inner: T,
+    timeout: Duration,
+}
+
+impl<T> TimeoutTransport<T> {
+    /// Wrap `inner`, failing any single operation that runs longer than `timeout`.
+    pub fn new(inner: T, timeout: Duration) -> Self {
+        Self { inner, timeout }
+    }
+
+    /// Access the wrapped transport.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Configured per-operation deadline.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    async fn run<V>(&self, op: &'static str, fut: impl Future<Output = Result<V>>) -> Result<V> {
+        tokio::time::timeout(self.timeout, fut)
+            .await
+            .unwrap_or_else(|_| {
+                Err(PaykitError::Timeout(format!(
+                    "{op} exceeded {:?}",
+                    self.timeout
+                )))
+            })
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> UnauthenticatedTransportRead for TimeoutTransport<T>
+where
+    T: UnauthenticatedTransportRead + Sync,
+{
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        self.run(
+            "fetch_supported_payments",
+            self.inner.fetch_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        self.run(
+            "count_supported_payments",
+            self.inner.count_supported_payments(payee),
+        )
+        .await
+    }
+
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        self.run(
+            "fetch_supported_payments_lenient",
+            self.inner.fetch_supported_payments_lenient(payee),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.run(
+            "fetch_payment_endpoint",
+            self.inner.fetch_payment_endpoint(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        self.run(
+            "fetch_payment_endpoint_full",
+            self.inner.fetch_payment_endpoint_full(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        self.run(
+            "fetch_payment_endpoint_exists",
+            self.inner.fetch_payment_endpoint_exists(payee, method),
+        )
+        .await
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        self.run("fetch_raw", self.inner.fetch_raw(payee, relative_path))
+            .await
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        self.run(
+            "fetch_known_contacts",
+            self.inner.fetch_known_contacts(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        self.run(
+            "fetch_known_contacts_with_report",
+            self.inner.fetch_known_contacts_with_report(owner),
+        )
+        .await
+    }
+
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        self.run(
+            "fetch_known_contacts_page",
+            self.inner.fetch_known_contacts_page(owner, cursor, limit),
+        )
+        .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<T> AuthenticatedTransport for TimeoutTransport<T>
+where
+    T: AuthenticatedTransport + Sync,
+{
+    async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint",
+            self.inner.upsert_payment_endpoint(method, data),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        content_type: &str,
+    ) -> Result<()> {
+        self.run(
+            "upsert_payment_endpoint_with_type",
+            self.inner
+                .upsert_payment_endpoint_with_type(method, data, content_type),
+        )
+        .await
+    }
+
+    async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
+        self.run(
+            "remove_payment_endpoint",
+            self.inner.remove_payment_endpoint(method),
+        )
+        .await
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        self.run(
+            "upsert_payment_endpoint_if_match",
+            self.inner
+                .upsert_payment_endpoint_if_match(method, data, expected_etag),
+        )
+        .await
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        self.run("list_own_endpoints", self.inner.list_own_endpoints())
+            .await
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        self.run("list_own_contacts", self.inner.list_own_contacts())
+            .await
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("add_contact", self.inner.add_contact(contact))
+            .await
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        self.run("remove_contact", self.inner.remove_contact(contact))
+            .await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        self.run("health_check", self.inner.health_check()).await
+    }
+
+    fn public_key(&self) -> Result<PublicKey> {
+        self.inner.public_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{random_public_key, MockTransport};
+    use crate::{get_payment_list, set_payment_endpoint};
+
+    #[tokio::test]
+    async fn slow_reads_time_out() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.set_latency(Duration::from_millis(200));
+        let bounded = TimeoutTransport::new(mock, Duration::from_millis(10));
+
+        let err = get_payment_list(&bounded, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Timeout(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn slow_writes_time_out() {
+        let mock = MockTransport::new(random_public_key());
+        mock.set_latency(Duration::from_millis(200));
+        let bounded = TimeoutTransport::new(mock, Duration::from_millis(10));
+
+        let err = set_payment_endpoint(
+            &bounded,
+            MethodId::new("lightning").unwrap(),
+            EndpointData("ln".into()),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, PaykitError::Timeout(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn inner_errors_pass_through() {
+        let owner = random_public_key();
+        let mock = MockTransport::new(owner.clone());
+        mock.fail_next("boom");
+        let bounded = TimeoutTransport::new(mock, Duration::from_secs(5));
+
+        let err = get_payment_list(&bounded, &owner).await.unwrap_err();
+        assert!(matches!(err, PaykitError::Transport(_)), "{err:?}");
+    }
+}
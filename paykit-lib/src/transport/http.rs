@@ -0,0 +1,59 @@
+//! Plain HTTP reads against a known homeserver, shared by the Pubky mirror reads and
+//! the browser reader.
+
+use std::time::Duration;
+
+use reqwest::{header::RETRY_AFTER, Client, Response, StatusCode};
+
+use crate::{PaykitError, PublicKey, Result};
+
+/// Header naming the tenant a homeserver request is addressed to.
+pub const PUBKY_HOST_HEADER: &str = "pubky-host";
+
+/// Sends a `GET` for `{base_url}{path}` on `host`'s storage, mapping 404 and 410 to
+/// `None`, 401 and 403 to `PaykitError::Unauthorized`, 429 to
+/// `PaykitError::RateLimited` with the `Retry-After` delay, and other failures to
+/// `PaykitError::Server`. `base_url` must not end with a slash.
+pub(crate) async fn get(
+    client: &Client,
+    base_url: &str,
+    host: &PublicKey,
+    path: &str,
+    query: &[(&str, &str)],
+    label: &str,
+) -> Result<Option<Response>> {
+    let resp = client
+        .get(format!("{base_url}{path}"))
+        .header(PUBKY_HOST_HEADER, host.to_string())
+        .query(query)
+        .send()
+        .await
+        .map_err(|err| crate::network_error(&err))?;
+
+    match resp.status() {
+        StatusCode::NOT_FOUND | StatusCode::GONE => Ok(None),
+        status if status.is_success() => Ok(Some(resp)),
+        StatusCode::TOO_MANY_REQUESTS => Err(PaykitError::RateLimited {
+            retry_after: retry_after(&resp),
+        }),
+        status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+            let message = resp.text().await.unwrap_or_default();
+            Err(PaykitError::Unauthorized(format!(
+                "{label}: {status}: {message}"
+            )))
+        }
+        status => {
+            let message = resp.text().await.unwrap_or_default();
+            Err(PaykitError::Server {
+                status: status.as_u16(),
+                message: format!("{label}: {message}"),
+            })
+        }
+    }
+}
+
+/// Delay from a `Retry-After` header given in seconds; HTTP dates are not supported.
+fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
+}
@@ -1,17 +1,26 @@
 //! Transport abstractions used by Paykit.
 //!
 //! This module exposes the public traits that callers must implement as well as the
-//! feature-gated Pubky adapters that satisfy those traits out of the box.
+//! feature-gated Pubky and browser adapters that satisfy those traits out of the box.
 
+pub mod paths;
 pub mod traits;
 
+#[cfg(any(feature = "pubky", feature = "wasm"))]
+mod http;
+
 #[cfg(feature = "pubky")]
 pub mod pubky;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use traits::{AuthenticatedTransport, UnauthenticatedTransportRead};
+pub use traits::{AuthenticatedTransport, DynRead, UnauthenticatedTransportRead};
 
 #[cfg(feature = "pubky")]
 pub use pubky::{
     authenticated_transport::PubkyAuthenticatedTransport,
     unauthenticated_transport::PubkyUnauthenticatedTransport,
 };
+
+#[cfg(feature = "wasm")]
+pub use wasm::WasmUnauthenticatedTransport;
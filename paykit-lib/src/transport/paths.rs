@@ -0,0 +1,305 @@
+//! Storage layout shared by every transport that speaks the Pubky path conventions.
+//!
+//! These paths only describe where Paykit data lives, so they stay available without the
+//! `pubky` feature for adapters such as the filesystem or browser readers.
+
+use crate::{MethodId, PaykitError, PublicKey, Result};
+
+/// Conventional prefix for Paykit data hosted on Pubky storage.
+/// `v0` means that the paykit conventions is to store data on pubky as following:
+///  - /pub/paykit.app/v0/{method_id} -> with payload being the payment endpoint
+pub const PAYKIT_PATH_PREFIX: &str = "/pub/paykit.app/v0/";
+/// Directory that stores contact/follow information (one file per known contact).
+pub const PUBKY_FOLLOWS_PATH: &str = "/pub/pubky.app/follows/";
+
+/// Location of Paykit endpoint files on Pubky storage.
+///
+/// Endpoints live at `{app_prefix}{version}/{method_id}`. The [`Default`] matches
+/// [`PAYKIT_PATH_PREFIX`] (`/pub/paykit.app/` plus `v0`); use a custom value to read
+/// or publish under a newer layout or a different app namespace.
+///
+/// Contacts live in a separate follows directory, [`PUBKY_FOLLOWS_PATH`] by default;
+/// apps keeping their own contact namespace can point it elsewhere with
+/// [`PaykitPaths::with_follows`].
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::transport::paths::{PaykitPaths, PAYKIT_PATH_PREFIX};
+/// assert_eq!(PaykitPaths::default().prefix(), PAYKIT_PATH_PREFIX);
+/// let v1 = PaykitPaths::default().with_version("v1");
+/// assert_eq!(v1.prefix(), "/pub/paykit.app/v1/");
+/// let own_contacts = PaykitPaths::default().with_follows("/pub/wallet.app/contacts");
+/// assert_eq!(own_contacts.follows(), "/pub/wallet.app/contacts/");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PaykitPaths {
+    app_prefix: String,
+    version: String,
+    follows: String,
+}
+
+impl Default for PaykitPaths {
+    fn default() -> Self {
+        Self::new("/pub/paykit.app/", "v0")
+    }
+}
+
+impl PaykitPaths {
+    /// Build paths for `app_prefix` (e.g. `/pub/paykit.app/`) and `version` (e.g. `v0`).
+    /// Leading and trailing slashes are normalized. Contacts stay under
+    /// [`PUBKY_FOLLOWS_PATH`].
+    pub fn new(app_prefix: impl AsRef<str>, version: impl AsRef<str>) -> Self {
+        Self {
+            app_prefix: wrap_slashes(app_prefix.as_ref()),
+            version: version.as_ref().trim_matches('/').to_string(),
+            follows: PUBKY_FOLLOWS_PATH.to_string(),
+        }
+    }
+
+    /// Same app prefix with a different protocol version.
+    pub fn with_version(mut self, version: impl AsRef<str>) -> Self {
+        self.version = version.as_ref().trim_matches('/').to_string();
+        self
+    }
+
+    /// Same endpoint layout with contacts read from and written to `follows` (e.g.
+    /// `/pub/wallet.app/contacts/`). Leading and trailing slashes are normalized.
+    pub fn with_follows(mut self, follows: impl AsRef<str>) -> Self {
+        self.follows = wrap_slashes(follows.as_ref());
+        self
+    }
+
+    /// App namespace, always wrapped in slashes.
+    pub fn app_prefix(&self) -> &str {
+        &self.app_prefix
+    }
+
+    /// Protocol version segment.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Directory holding the endpoint files, with a trailing slash.
+    pub fn prefix(&self) -> String {
+        format!("{}{}/", self.app_prefix, self.version)
+    }
+
+    /// Path of the endpoint file for `method`.
+    pub fn endpoint(&self, method: &MethodId) -> String {
+        format!("{}{}", self.prefix(), method.0)
+    }
+
+    /// Path of `relative_path` under [`Self::prefix`], e.g. `profile.json` or
+    /// `extensions/profile.json`. Check untrusted input with [`validate_relative_path`]
+    /// first; this only joins.
+    pub fn file(&self, relative_path: &str) -> String {
+        format!("{}{relative_path}", self.prefix())
+    }
+
+    /// Directory holding one marker file per contact, wrapped in slashes.
+    pub fn follows(&self) -> &str {
+        &self.follows
+    }
+
+    /// Path of the marker file for `contact`.
+    pub fn follow(&self, contact: &PublicKey) -> String {
+        format!("{}{contact}", self.follows)
+    }
+}
+
+/// Checks that `path` names a file inside the Paykit directory, for
+/// [`UnauthenticatedTransportRead::fetch_raw`](crate::UnauthenticatedTransportRead::fetch_raw).
+///
+/// The path must be relative, made of `/`-separated segments that are each non-empty
+/// and not `.` or `..`, and free of backslashes and control characters, so it cannot
+/// climb out of the prefix or name a directory. `%`, `?`, and `#` are rejected too, so a
+/// percent-encoded `..` or a query or fragment cannot change the requested path.
+/// Failures are `PaykitError::InvalidData`.
+pub fn validate_relative_path(path: &str) -> Result<()> {
+    let reason = if path.is_empty() {
+        "must not be empty"
+    } else if path.starts_with('/') {
+        "must be relative"
+    } else if path.contains('\\') {
+        "must not contain backslashes"
+    } else if path.chars().any(char::is_control) {
+        "must not contain control characters"
+    } else if path.contains(['%', '?', '#']) {
+        "must not contain `%`, `?` or `#`"
+    } else if path
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        "must not contain empty, `.` or `..` segments"
+    } else {
+        return Ok(());
+    };
+    Err(PaykitError::InvalidData(format!(
+        "relative path {path:?} {reason}"
+    )))
+}
+
+/// Name of the file a listed `path` points at, i.e. its last segment, e.g. `lightning`
+/// for `pubky://<key>/pub/paykit.app/v0/lightning`.
+///
+/// Directories (a trailing slash) and empty names yield `None`. This is the rule the
+/// first-party transports apply to listings; custom transports can reuse it, or the
+/// typed [`method_id_from_path`] and [`contact_key_from_path`].
+pub fn entry_name(path: &str) -> Option<&str> {
+    if path.ends_with('/') {
+        return None;
+    }
+    path.rsplit('/').next().filter(|name| !name.is_empty())
+}
+
+/// Method id of a listed endpoint file, or `None` for directories and names that
+/// [`MethodId::new`] rejects. With the `integrity` feature, checksum sidecars
+/// (`lightning.sha256`) also yield `None`.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::transport::paths::method_id_from_path;
+/// # use paykit_lib::MethodId;
+/// let method = method_id_from_path("/pub/paykit.app/v0/lightning");
+/// assert_eq!(method, Some(MethodId("lightning".into())));
+/// assert_eq!(method_id_from_path("/pub/paykit.app/v0/nested/"), None);
+/// ```
+pub fn method_id_from_path(path: &str) -> Option<MethodId> {
+    let name = entry_name(path).filter(|name| !is_sidecar(name))?;
+    MethodId::new(name).ok()
+}
+
+/// Contact key of a listed follow file, or `None` for directories and names that are
+/// not a public key.
+pub fn contact_key_from_path(path: &str) -> Option<PublicKey> {
+    crate::parse_public_key(entry_name(path)?).ok()
+}
+
+/// Whether a listed `name` is a checksum sidecar written by [`crate::integrity`], which
+/// listings hide. Always `false` without the `integrity` feature.
+#[cfg(feature = "integrity")]
+pub(crate) fn is_sidecar(name: &str) -> bool {
+    name.ends_with(crate::integrity::SIDECAR_SUFFIX)
+}
+
+#[cfg(not(feature = "integrity"))]
+pub(crate) fn is_sidecar(_name: &str) -> bool {
+    false
+}
+
+fn wrap_slashes(path: &str) -> String {
+    format!("/{}/", path.trim_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_paths_match_constant() {
+        assert_eq!(PaykitPaths::default().prefix(), PAYKIT_PATH_PREFIX);
+        assert_eq!(
+            PaykitPaths::default().endpoint(&MethodId("lightning".into())),
+            format!("{PAYKIT_PATH_PREFIX}lightning")
+        );
+    }
+
+    #[test]
+    fn custom_paths_are_normalized() {
+        let paths = PaykitPaths::new("pub/example.app", "/v2/");
+        assert_eq!(paths.app_prefix(), "/pub/example.app/");
+        assert_eq!(paths.version(), "v2");
+        assert_eq!(paths.prefix(), "/pub/example.app/v2/");
+        assert_eq!(paths.follows(), PUBKY_FOLLOWS_PATH);
+
+        let paths = paths.with_follows("pub/example.app/contacts");
+        assert_eq!(paths.follows(), "/pub/example.app/contacts/");
+        assert_eq!(paths.prefix(), "/pub/example.app/v2/");
+    }
+
+    #[test]
+    fn method_ids_from_listed_paths() {
+        for (path, expected) in [
+            ("/pub/paykit.app/v0/lightning", Some("lightning")),
+            ("pubky://owner/pub/paykit.app/v0/onchain", Some("onchain")),
+            ("lnurl", Some("lnurl")),
+            ("/pub/paykit.app/v0/nested/", None),
+            ("/pub/paykit.app/v0/", None),
+            ("/pub/paykit.app/v0//", None),
+            ("", None),
+            ("/pub/paykit.app/v0/..", None),
+        ] {
+            assert_eq!(
+                method_id_from_path(path),
+                expected.map(|id| MethodId(id.into())),
+                "{path:?}"
+            );
+        }
+        let too_long = format!("/pub/paykit.app/v0/{}", "x".repeat(MethodId::MAX_LEN + 1));
+        assert_eq!(method_id_from_path(&too_long), None);
+    }
+
+    #[cfg(feature = "integrity")]
+    #[test]
+    fn checksum_sidecars_are_not_methods() {
+        assert_eq!(
+            method_id_from_path("/pub/paykit.app/v0/lightning.sha256"),
+            None
+        );
+        assert_eq!(
+            method_id_from_path("/pub/paykit.app/v0/lightning.sha"),
+            Some(MethodId("lightning.sha".into()))
+        );
+    }
+
+    #[test]
+    fn contact_keys_from_listed_paths() {
+        let key = crate::mock::random_public_key();
+        assert_eq!(
+            contact_key_from_path(&format!("{PUBKY_FOLLOWS_PATH}{key}")),
+            Some(key.clone())
+        );
+        assert_eq!(entry_name(&format!("{PUBKY_FOLLOWS_PATH}{key}/")), None);
+        assert_eq!(
+            contact_key_from_path(&format!("{PUBKY_FOLLOWS_PATH}{key}/")),
+            None
+        );
+        assert_eq!(contact_key_from_path(PUBKY_FOLLOWS_PATH), None);
+        assert_eq!(
+            contact_key_from_path("/pub/pubky.app/follows/notakey"),
+            None
+        );
+    }
+
+    #[test]
+    fn relative_paths_stay_inside_the_prefix() {
+        for path in ["profile.json", "extensions/profile.json", "a.b/c-d_e"] {
+            assert!(validate_relative_path(path).is_ok(), "{path:?}");
+        }
+        for path in [
+            "",
+            "/pub/paykit.app/v0/profile.json",
+            "../secret",
+            "extensions/../../secret",
+            "./profile.json",
+            "extensions/",
+            "extensions//profile.json",
+            "ext\\profile.json",
+            "profile\njson",
+            "%2e%2e/secret",
+            "extensions/%2E%2E/%2e%2e/secret",
+            "profile.json?raw=1",
+            "profile.json#top",
+        ] {
+            let err = validate_relative_path(path).unwrap_err();
+            assert!(
+                matches!(err, PaykitError::InvalidData(_)),
+                "{path:?}: {err:?}"
+            );
+        }
+        assert_eq!(
+            PaykitPaths::default().file("profile.json"),
+            format!("{PAYKIT_PATH_PREFIX}profile.json")
+        );
+    }
+}
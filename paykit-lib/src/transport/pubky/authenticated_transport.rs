@@ -1,55 +1,315 @@
 //! Authenticated Pubky adapter that satisfies [`crate::AuthenticatedTransport`].
 
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use pubky::PubkySession;
+use pubky::{recovery_file::decrypt_recovery_file, Pubky, PubkySession};
 
-use super::PAYKIT_PATH_PREFIX;
+use super::unauthenticated_transport::parse_contacts;
+use super::{is_not_found, transport_error, PaykitPaths};
+use crate::transport::paths::method_id_from_path;
 use crate::transport::traits::AuthenticatedTransport;
-use crate::{EndpointData, MethodId, PaykitError, Result};
+use crate::{EndpointData, MethodId, PaykitError, PublicKey, Result, SupportedPayments};
 
 /// Adapter around `pubky::PubkySession` implementing `AuthenticatedTransport`.
+///
+/// # Conditional writes
+/// The homeserver honours ETags on reads only, so
+/// [`AuthenticatedTransport::upsert_payment_endpoint_if_match`] checks the current
+/// ETag with a `HEAD` request right before writing and reads the new one back after.
+/// This catches stale writers but is not atomic: a write landing between the check
+/// and the `PUT` can still be overwritten.
+///
+/// # Content types
+/// The homeserver derives each file's content type from its leading bytes and path
+/// extension and ignores the request's `Content-Type`, so
+/// [`AuthenticatedTransport::upsert_payment_endpoint_with_type`] keeps the default and
+/// writes the payload without the type.
 #[derive(Clone)]
 pub struct PubkyAuthenticatedTransport {
     session: PubkySession,
+    paths: PaykitPaths,
 }
 
 impl PubkyAuthenticatedTransport {
-    /// Create a new adapter from an existing session.
+    /// Create a new adapter from an existing session, publishing under the default
+    /// `v0` layout.
     pub fn new(session: PubkySession) -> Self {
-        Self { session }
+        Self::new_with_paths(session, PaykitPaths::default())
+    }
+
+    /// Create an adapter that publishes endpoints under `paths`.
+    pub fn new_with_paths(session: PubkySession, paths: PaykitPaths) -> Self {
+        Self { session, paths }
+    }
+
+    /// Decrypt the keypair in a `.pkarr` recovery file with `passphrase` and open a root
+    /// session for it on mainnet.
+    ///
+    /// See [`Self::from_recovery_file_with_sdk`], which this calls with `Pubky::new()`.
+    pub async fn from_recovery_file(
+        bytes: &[u8],
+        passphrase: &str,
+        homeserver: &PublicKey,
+    ) -> Result<Self> {
+        let sdk = Pubky::new().map_err(|err| transport_error("build pubky client", err))?;
+        Self::from_recovery_file_with_sdk(&sdk, bytes, passphrase, homeserver).await
+    }
+
+    /// Like [`Self::from_recovery_file`], using `sdk` (e.g. a testnet facade) for the
+    /// network calls.
+    ///
+    /// When the key already publishes a homeserver record, the session comes from
+    /// `signin` against that homeserver, which may differ from `homeserver`. Otherwise
+    /// the key is signed up on `homeserver` without a signup token, which publishes the
+    /// record. A file that cannot be decrypted (wrong passphrase or corrupt contents)
+    /// fails with `PaykitError::InvalidData`; sign-in and signup failures keep the
+    /// mapping of the `From<pubky::Error>` conversion, prefixed with the step that
+    /// failed.
+    ///
+    /// Keeping the recovery file and passphrase safe, and rotating the key, stays the
+    /// caller's responsibility.
+    pub async fn from_recovery_file_with_sdk(
+        sdk: &Pubky,
+        bytes: &[u8],
+        passphrase: &str,
+        homeserver: &PublicKey,
+    ) -> Result<Self> {
+        let keypair = decrypt_recovery_file(bytes, passphrase)
+            .map_err(|err| PaykitError::InvalidData(format!("recovery file: {err}")))?;
+        let signer = sdk.signer(keypair);
+        let session = match sdk.get_homeserver_of(&signer.public_key()).await {
+            Some(_) => signer
+                .signin()
+                .await
+                .map_err(|err| transport_error("sign in", err))?,
+            None => signer
+                .signup(homeserver, None)
+                .await
+                .map_err(|err| transport_error(&format!("sign up on {homeserver}"), err))?,
+        };
+        Ok(Self::new(session))
     }
 
     /// Access the wrapped session for advanced payers/payees.
     pub fn session(&self) -> &PubkySession {
         &self.session
     }
+
+    /// Endpoint layout this adapter writes to.
+    pub fn paths(&self) -> &PaykitPaths {
+        &self.paths
+    }
+
+    /// Checks that the session may write the endpoint directory, without contacting
+    /// the homeserver.
+    ///
+    /// Mirrors the homeserver's rule: some granted capability must include write
+    /// access and have a scope that prefixes [`PaykitPaths::prefix`]. A root session
+    /// (`/:rw`, as returned by `signup`/`signin`) always passes. Fails with
+    /// [`PaykitError::Unauthorized`] naming the granted capabilities otherwise.
+    pub fn ensure_capability(&self) -> Result<()> {
+        let prefix = self.paths.prefix();
+        let capabilities = self.session.info().capabilities();
+        let writable = capabilities.iter().any(|cap| {
+            prefix.starts_with(&cap.scope)
+                && cap.actions.iter().any(|action| char::from(action) == 'w')
+        });
+        if writable {
+            return Ok(());
+        }
+        Err(PaykitError::Unauthorized(format!(
+            "session for {} cannot write {prefix} (granted: {})",
+            self.session.info().public_key(),
+            if capabilities.is_empty() {
+                "none".to_string()
+            } else {
+                capabilities.to_string()
+            }
+        )))
+    }
+
+    async fn current_etag(&self, path: &str) -> Result<Option<String>> {
+        match self.session.storage().stats(path).await {
+            Ok(stats) => Ok(stats.and_then(|stats| stats.etag)),
+            Err(err) if is_not_found(&err) => Ok(None),
+            Err(err) => Err(transport_error("stat endpoint", err)),
+        }
+    }
 }
 
 impl From<PubkySession> for PubkyAuthenticatedTransport {
     fn from(session: PubkySession) -> Self {
-        Self { session }
+        Self::new(session)
     }
 }
 
-#[async_trait]
+/// Request body for `data`.
+///
+/// The SDK's `put` takes an owned body and the traits borrow the payload, so this
+/// copies it.
+fn request_body(data: &EndpointData) -> Vec<u8> {
+    data.as_str().as_bytes().to_vec()
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl AuthenticatedTransport for PubkyAuthenticatedTransport {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(method = %method), err(Display))
+    )]
     async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()> {
-        let path = format!("{PAYKIT_PATH_PREFIX}{}", method.0);
+        let path = self.paths.endpoint(method);
         self.session
             .storage()
-            .put(path, data.0.clone())
+            .put(path, request_body(data))
             .await
-            .map_err(|err| PaykitError::Transport(format!("put endpoint: {err}")))?;
+            .map_err(|err| transport_error("put endpoint", err))?;
         Ok(())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(method = %method), err(Display))
+    )]
+    /// With the `integrity` feature, also deletes the method's checksum sidecar once
+    /// the endpoint is gone. A missing sidecar is not an error.
     async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()> {
-        let path = format!("{PAYKIT_PATH_PREFIX}{}", method.0);
+        let path = self.paths.endpoint(method);
+        let storage = self.session.storage();
+        storage
+            .delete(path)
+            .await
+            .map_err(|err| transport_error("delete endpoint", err))?;
+
+        // An id too long to carry the suffix cannot have a sidecar.
+        #[cfg(feature = "integrity")]
+        if let Ok(sidecar) = crate::integrity::sidecar_method(method) {
+            match storage.delete(self.paths.endpoint(&sidecar)).await {
+                Ok(_) => {}
+                Err(err) if is_not_found(&err) => {}
+                Err(err) => return Err(transport_error("delete sidecar", err)),
+            }
+        }
+        Ok(())
+    }
+
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        expected_etag: Option<String>,
+    ) -> Result<String> {
+        let path = self.paths.endpoint(method);
+        let storage = self.session.storage();
+        let actual = self.current_etag(&path).await?;
+        if actual != expected_etag {
+            return Err(PaykitError::Conflict {
+                expected: expected_etag,
+                actual,
+            });
+        }
+
+        storage
+            .put(path.as_str(), request_body(data))
+            .await
+            .map_err(|err| transport_error("put endpoint", err))?;
+        self.current_etag(&path).await?.ok_or_else(|| {
+            PaykitError::InvalidData(format!("put endpoint {}: no etag returned", method.0))
+        })
+    }
+
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments> {
+        let storage = self.session.storage();
+        let listing = match storage.list(self.paths.prefix()) {
+            Ok(builder) => builder.shallow(true).send().await,
+            Err(err) => Err(err),
+        };
+        let entries = match listing {
+            Ok(entries) => entries,
+            Err(err) if is_not_found(&err) => Vec::new(),
+            Err(err) => return Err(transport_error("list own endpoints", err)),
+        };
+
+        let mut map = HashMap::new();
+        for resource in entries {
+            let path = resource.path.as_str();
+            let Some(method) = method_id_from_path(path) else {
+                continue;
+            };
+
+            let resp = match storage.get(path).await {
+                Ok(resp) => resp,
+                Err(err) if is_not_found(&err) => continue,
+                Err(err) => {
+                    return Err(transport_error(&format!("get own endpoint {method}"), err))
+                }
+            };
+            let bytes = resp
+                .bytes()
+                .await
+                .map_err(|err| crate::network_error(&err))?;
+            if bytes.is_empty() {
+                continue;
+            }
+            let payload = String::from_utf8(bytes.to_vec()).map_err(|err| {
+                PaykitError::InvalidData(format!("get own endpoint {method} ({path}): {err}"))
+            })?;
+            map.insert(method, EndpointData(payload));
+        }
+
+        Ok(SupportedPayments { entries: map })
+    }
+
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>> {
+        let listing = match self.session.storage().list(self.paths.follows()) {
+            Ok(builder) => builder.shallow(true).send().await,
+            Err(err) => Err(err),
+        };
+        let entries = match listing {
+            Ok(entries) => entries,
+            Err(err) if is_not_found(&err) => Vec::new(),
+            Err(err) => return Err(transport_error("list own contacts", err)),
+        };
+        let (contacts, _skipped) = parse_contacts(&entries);
+        Ok(contacts)
+    }
+
+    async fn add_contact(&self, contact: &PublicKey) -> Result<()> {
+        // Contacts are empty marker files named after the followed key.
+        let path = self.paths.follow(contact);
+        self.session
+            .storage()
+            .put(path, Vec::<u8>::new())
+            .await
+            .map_err(|err| transport_error("put contact", err))?;
+        Ok(())
+    }
+
+    async fn remove_contact(&self, contact: &PublicKey) -> Result<()> {
+        let path = self.paths.follow(contact);
         self.session
             .storage()
             .delete(path)
             .await
-            .map_err(|err| PaykitError::Transport(format!("delete endpoint: {err}")))?;
+            .map_err(|err| transport_error("delete contact", err))?;
         Ok(())
     }
+
+    /// Sends a `HEAD` for the session's Paykit directory; a missing directory still
+    /// counts as reachable.
+    async fn health_check(&self) -> Result<()> {
+        self.session
+            .storage()
+            .exists(self.paths.prefix())
+            .await
+            .map_err(|err| transport_error("health check", err))?;
+        Ok(())
+    }
+
+    /// Reads the identity from the session, without a request.
+    fn public_key(&self) -> Result<PublicKey> {
+        Ok(self.session.info().public_key().clone())
+    }
 }
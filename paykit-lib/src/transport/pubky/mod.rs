@@ -5,12 +5,204 @@
 //! [`crate::UnauthenticatedTransportRead`] traits without depending on specific SDK
 //! types.
 
+use pubky::{
+    errors::{PkarrError, RequestError},
+    Error as PubkyError, StatusCode,
+};
+
+use crate::PaykitError;
+
 pub mod authenticated_transport;
 pub mod unauthenticated_transport;
 
-/// Conventional prefix for Paykit data hosted on Pubky storage.
-/// `v0` means that the paykit conventions is to store data on pubky as following:
-///  - /pub/paykit.app/v0/{method_id} -> with payload being the payment endpoint
-pub const PAYKIT_PATH_PREFIX: &str = "/pub/paykit.app/v0/";
-/// Directory that stores contact/follow information (one file per known contact).
-pub const PUBKY_FOLLOWS_PATH: &str = "/pub/pubky.app/follows/";
+pub use super::paths::{PaykitPaths, PAYKIT_PATH_PREFIX, PUBKY_FOLLOWS_PATH};
+/// URL type taken by [`unauthenticated_transport::PubkyUnauthenticatedTransport::with_mirror`].
+pub use reqwest::Url;
+
+/// Converts SDK failures so adapters built on the Pubky SDK can use `?` directly.
+///
+/// 401 and 403 responses become [`PaykitError::Unauthorized`], so wallets can prompt
+/// for a new session, and 429 becomes [`PaykitError::RateLimited`] without a delay: the
+/// SDK does not expose response headers, so `Retry-After` cannot be read. Other
+/// non-success server responses keep their status as [`PaykitError::Server`], so a 404 or
+/// 410 satisfies [`PaykitError::is_not_found`].
+/// Requests that got no response at all become [`PaykitError::Network`]. Everything
+/// else (invalid requests, authentication, PKARR) is wrapped unchanged in
+/// [`PaykitError::TransportSource`], where [`PaykitError::is_transient`] can still
+/// inspect it.
+impl From<PubkyError> for PaykitError {
+    fn from(err: PubkyError) -> Self {
+        match err {
+            PubkyError::Request(RequestError::Server { status, message })
+                if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN =>
+            {
+                PaykitError::Unauthorized(format!("{status}: {message}"))
+            }
+            PubkyError::Request(RequestError::Server { status, .. })
+                if status == StatusCode::TOO_MANY_REQUESTS =>
+            {
+                PaykitError::RateLimited { retry_after: None }
+            }
+            PubkyError::Request(RequestError::Server { status, message }) => PaykitError::Server {
+                status: status.as_u16(),
+                message,
+            },
+            PubkyError::Request(RequestError::Transport(err)) => crate::network_error(&err),
+            err => PaykitError::TransportSource(Box::new(err)),
+        }
+    }
+}
+
+/// Like the [`From`] conversion, but prefixes `context` to server error messages.
+pub(crate) fn transport_error(context: &str, err: PubkyError) -> PaykitError {
+    match PaykitError::from(err) {
+        PaykitError::Server { status, message } => PaykitError::Server {
+            status,
+            message: format!("{context}: {message}"),
+        },
+        PaykitError::Unauthorized(message) => {
+            PaykitError::Unauthorized(format!("{context}: {message}"))
+        }
+        other => other,
+    }
+}
+
+/// Returns `true` when the SDK reports the resource as missing (404) or gone (410).
+pub(crate) fn is_not_found(err: &PubkyError) -> bool {
+    matches!(
+        err,
+        PubkyError::Request(RequestError::Server { status, .. })
+            if *status == StatusCode::NOT_FOUND || *status == StatusCode::GONE
+    )
+}
+
+/// Returns `true` for SDK failures that happened on the network rather than locally
+/// (invalid input, authentication, or client construction).
+pub(crate) fn is_transient(err: &PubkyError) -> bool {
+    match err {
+        PubkyError::Request(RequestError::Transport(_)) => true,
+        PubkyError::Request(RequestError::Server { status, .. }) => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        PubkyError::Pkarr(PkarrError::Query(_) | PkarrError::Publish(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_errors_keep_their_status() {
+        let err = PubkyError::Request(RequestError::Server {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "boom".into(),
+        });
+        let mapped = transport_error("fetch endpoint", err);
+        assert!(
+            matches!(&mapped, PaykitError::Server { status: 500, message } if message == "fetch endpoint: boom"),
+            "{mapped:?}"
+        );
+        assert_eq!(mapped.status_code(), Some(500));
+    }
+
+    #[tokio::test]
+    async fn connection_refused_is_a_network_error() {
+        let client = pubky::PubkyHttpClient::new().unwrap();
+        // Nothing listens on the discard port, so the connection is refused.
+        let err = client
+            .request(pubky::Method::GET, "http://127.0.0.1:9/")
+            .send()
+            .await
+            .unwrap_err();
+        let err = PubkyError::from(err);
+        assert!(matches!(
+            err,
+            PubkyError::Request(RequestError::Transport(_))
+        ));
+
+        let mapped = transport_error("fetch endpoint", err);
+        assert!(
+            matches!(&mapped, PaykitError::Network(message) if message.contains("127.0.0.1")),
+            "{mapped:?}"
+        );
+        assert_eq!(mapped.status_code(), None);
+        assert!(mapped.is_transient());
+    }
+
+    #[test]
+    fn error_responses_are_not_network_errors() {
+        let err = PubkyError::Request(RequestError::Server {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: "boom".into(),
+        });
+        let mapped = PaykitError::from(err);
+        assert!(
+            matches!(mapped, PaykitError::Server { status: 500, .. }),
+            "{mapped:?}"
+        );
+        assert!(mapped.is_transient());
+    }
+
+    #[test]
+    fn rejected_credentials_are_unauthorized() {
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let err = PubkyError::Request(RequestError::Server {
+                status,
+                message: "no write capability".into(),
+            });
+            assert!(!is_transient(&err));
+            let mapped = transport_error("put endpoint", err);
+            assert!(
+                matches!(&mapped, PaykitError::Unauthorized(msg) if msg.starts_with("put endpoint: ")),
+                "{mapped:?}"
+            );
+            assert!(!mapped.is_transient());
+            assert!(!mapped.is_not_found());
+        }
+    }
+
+    #[test]
+    fn throttled_requests_are_rate_limited() {
+        let err = PubkyError::Request(RequestError::Server {
+            status: StatusCode::TOO_MANY_REQUESTS,
+            message: "slow down".into(),
+        });
+        assert!(is_transient(&err));
+        let mapped = transport_error("list endpoints", err);
+        assert!(
+            matches!(mapped, PaykitError::RateLimited { retry_after: None }),
+            "{mapped:?}"
+        );
+        assert!(mapped.is_transient());
+    }
+
+    #[test]
+    fn local_sdk_failures_are_not_transient() {
+        let err = PubkyError::Authentication(pubky::errors::AuthError::Validation("bad".into()));
+        let mapped = transport_error("sign in", err);
+        assert!(matches!(mapped, PaykitError::TransportSource(_)));
+        assert!(!mapped.is_transient());
+        assert!(!mapped.is_not_found());
+    }
+
+    #[test]
+    fn missing_resources_convert_to_not_found() {
+        fn fetch(status: StatusCode) -> crate::Result<()> {
+            Err(PubkyError::Request(RequestError::Server {
+                status,
+                message: "no such file".into(),
+            }))?
+        }
+
+        for status in [StatusCode::NOT_FOUND, StatusCode::GONE] {
+            let err = fetch(status).unwrap_err();
+            assert!(err.is_not_found(), "{err:?}");
+            assert!(!err.is_transient());
+        }
+        let err = fetch(StatusCode::SERVICE_UNAVAILABLE).unwrap_err();
+        assert!(!err.is_not_found());
+        assert!(err.is_transient());
+    }
+}
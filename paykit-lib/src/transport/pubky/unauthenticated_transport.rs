@@ -1,35 +1,195 @@
 //! Unauthenticated Pubky adapter that exposes reads over [`crate::UnauthenticatedTransportRead`].
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use pubky::{
-    errors::RequestError, Error as PubkyError, PubkyResource,
-    PublicStorage as SdkUnauthenticatedTransport, StatusCode,
+    Pubky, PubkyHttpClient, PubkyResource, PublicStorage as SdkUnauthenticatedTransport,
+    ResourceStats,
 };
+use reqwest::{Client, Response};
 
-use super::{PAYKIT_PATH_PREFIX, PUBKY_FOLLOWS_PATH};
+use super::{is_not_found, transport_error, PaykitPaths, Url};
+use crate::transport::http;
+use crate::transport::paths::{
+    contact_key_from_path, entry_name, is_sidecar, method_id_from_path, validate_relative_path,
+};
 use crate::transport::traits::UnauthenticatedTransportRead;
-use crate::{EndpointData, MethodId, PaykitError, PublicKey, Result, SupportedPayments};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
+
+/// Default number of endpoint bodies fetched concurrently by
+/// [`PubkyUnauthenticatedTransport::fetch_supported_payments`].
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 8;
+
+/// Largest contacts page served by
+/// [`PubkyUnauthenticatedTransport::fetch_known_contacts_page`]. Homeservers cap a
+/// listing at 1000 entries and the adapter asks for one extra to detect the last page.
+pub const MAX_CONTACTS_PAGE: usize = 999;
+
+/// Default cap on a single endpoint body read by [`PubkyUnauthenticatedTransport`]
+/// (64 KiB).
+pub const DEFAULT_MAX_ENDPOINT_BYTES: usize = 64 * 1024;
+
+/// Entries requested per chunk when walking a whole directory listing.
+const LIST_CHUNK: usize = 1000;
 
 /// Adapter around `pubky::PublicStorage` implementing `UnauthenticatedTransportRead`.
 #[derive(Clone)]
 pub struct PubkyUnauthenticatedTransport {
     inner: SdkUnauthenticatedTransport,
+    paths: PaykitPaths,
+    fetch_concurrency: usize,
+    max_endpoint_bytes: usize,
+    case_insensitive_methods: bool,
+    strict_empty: bool,
+    recursive: bool,
+    mirror: Option<Url>,
+    http: Client,
 }
 
 impl PubkyUnauthenticatedTransport {
-    /// Build an adapter from an existing SDK handle.
+    /// Build an adapter from an existing SDK handle, reading the default `v0` layout.
     pub fn new(inner: SdkUnauthenticatedTransport) -> Self {
-        Self { inner }
+        Self::new_with_paths(inner, PaykitPaths::default())
+    }
+
+    /// Build an adapter that reads endpoints from `paths`.
+    pub fn new_with_paths(inner: SdkUnauthenticatedTransport, paths: PaykitPaths) -> Self {
+        Self {
+            inner,
+            paths,
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            max_endpoint_bytes: DEFAULT_MAX_ENDPOINT_BYTES,
+            case_insensitive_methods: false,
+            strict_empty: false,
+            recursive: false,
+            mirror: None,
+            http: Client::new(),
+        }
+    }
+
+    /// Build an adapter on caller-configured HTTP clients, reading the default `v0`
+    /// layout.
+    ///
+    /// `client` carries every PKARR-resolved read. Configure it with
+    /// `PubkyHttpClient::builder()` (relays, request timeout, user agent). Its inner
+    /// `reqwest` clients honour the standard `HTTP_PROXY`, `HTTPS_PROXY`, and `NO_PROXY`
+    /// environment variables, but the SDK offers no way to set TLS roots: homeservers
+    /// are verified by their Pubky key, not by a certificate authority.
+    ///
+    /// `http` serves reads sent straight to a URL
+    /// ([`Self::fetch_payment_endpoint_from`] and [`Self::with_mirror`]) and is used as
+    /// given, so an explicit proxy (`reqwest::Proxy`) or extra root certificates
+    /// (`reqwest::ClientBuilder::add_root_certificate`) apply to those reads.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use paykit_lib::PubkyUnauthenticatedTransport;
+    /// let proxy = reqwest::Proxy::all("http://proxy.corp.example:3128").unwrap();
+    /// let http = reqwest::Client::builder().proxy(proxy).build().unwrap();
+    /// let client = pubky::PubkyHttpClient::new().unwrap();
+    /// let reader = PubkyUnauthenticatedTransport::with_http_client(client, http);
+    /// ```
+    pub fn with_http_client(client: PubkyHttpClient, http: Client) -> Self {
+        let storage = Pubky::with_client(client).public_storage();
+        Self {
+            http,
+            ..Self::new(storage)
+        }
+    }
+
+    /// Start configuring a reader with a custom network; see
+    /// [`PubkyUnauthenticatedTransportBuilder`].
+    pub fn builder() -> PubkyUnauthenticatedTransportBuilder {
+        PubkyUnauthenticatedTransportBuilder::default()
     }
 
     /// Attempt to construct the underlying SDK transport via `pubky::PublicStorage::new()`.
+    ///
+    /// This is the zero-config path (mainnet defaults); use [`Self::builder`] to pick a
+    /// testnet or custom relays.
     pub fn try_new() -> Result<Self> {
-        let inner = SdkUnauthenticatedTransport::new().map_err(|err| {
-            PaykitError::Transport(format!("failed to create Pubky public transport: {err}"))
-        })?;
-        Ok(Self { inner })
+        let inner = SdkUnauthenticatedTransport::new()
+            .map_err(|err| transport_error("failed to create Pubky public transport", err))?;
+        Ok(Self::new(inner))
+    }
+
+    /// Cap how many endpoint bodies are fetched at once when listing a payee's
+    /// supported payments (clamped to at least one). Defaults to
+    /// [`DEFAULT_FETCH_CONCURRENCY`].
+    pub fn with_fetch_concurrency(mut self, limit: usize) -> Self {
+        self.fetch_concurrency = limit.max(1);
+        self
+    }
+
+    /// Reject endpoint bodies larger than `limit` bytes with
+    /// `PaykitError::PayloadTooLarge`, so a hostile payee cannot exhaust the payer's
+    /// memory. Defaults to [`DEFAULT_MAX_ENDPOINT_BYTES`].
+    pub fn with_max_endpoint_bytes(mut self, limit: usize) -> Self {
+        self.max_endpoint_bytes = limit;
+        self
+    }
+
+    /// Lowercase (ASCII) the method ids of listed payments, so a payee publishing
+    /// `Lightning` is found under `lightning`. Off by default.
+    ///
+    /// Applies to the listing methods (`fetch_supported_payments` and its variants);
+    /// two published ids differing only in case then fail the listing with
+    /// `PaykitError::InvalidData`. Single-endpoint reads still address the exact path.
+    pub fn with_case_insensitive_methods(mut self, enabled: bool) -> Self {
+        self.case_insensitive_methods = enabled;
+        self
+    }
+
+    /// Report an endpoint that exists but has an empty body as
+    /// `PaykitError::InvalidData` instead of reading it as absent, to surface endpoints
+    /// written blank. Off by default.
+    ///
+    /// Applies to every body read, so one empty endpoint fails a whole listing.
+    /// [`fetch_payment_endpoint_exists`](UnauthenticatedTransportRead::fetch_payment_endpoint_exists)
+    /// still answers `false` for it.
+    pub fn with_strict_empty(mut self, enabled: bool) -> Self {
+        self.strict_empty = enabled;
+        self
+    }
+
+    /// List the payee's endpoint directory recursively, so nested methods such as
+    /// `lightning/mainnet` are found. Off by default: only files directly under the
+    /// prefix are listed and subdirectories are skipped.
+    ///
+    /// Applies to the listing methods (`fetch_supported_payments`, its variants, and
+    /// `count_supported_payments`). A nested method keeps its sub-path in the
+    /// [`MethodId`], e.g. `MethodId("lightning/mainnet")`. Such ids fail
+    /// [`MethodId::validate`], so nested methods can only be listed: the single-endpoint
+    /// helpers ([`get_payment_endpoint`](crate::get_payment_endpoint),
+    /// [`set_payment_endpoint`](crate::set_payment_endpoint), ...) reject them with
+    /// `PaykitError::InvalidMethodId`. Entries outside the prefix are skipped. Contact
+    /// listings are always shallow.
+    pub fn with_recursive(mut self, enabled: bool) -> Self {
+        self.recursive = enabled;
+        self
+    }
+
+    /// Read from the homeserver mirror at `base_url` instead of resolving each payee's
+    /// homeserver; see [`Self::fetch_payment_endpoint_from`].
+    ///
+    /// Every read uses the mirror: single endpoints, raw files, payment listings and
+    /// the bodies they fetch, counts, and contact listings, including
+    /// [`Self::list_entries_lenient`].
+    /// [`health_check`](UnauthenticatedTransportRead::health_check) probes it.
+    pub fn with_mirror(mut self, base_url: Url) -> Self {
+        self.mirror = Some(base_url);
+        self
+    }
+
+    /// Mirror set with [`Self::with_mirror`], if any.
+    pub fn mirror(&self) -> Option<&Url> {
+        self.mirror.as_ref()
     }
 
     /// Access the wrapped SDK transport handle.
@@ -37,123 +197,1119 @@ impl PubkyUnauthenticatedTransport {
         &self.inner
     }
 
-    async fn fetch_text(&self, addr: String, label: &str) -> Result<Option<String>> {
-        match self.inner.get(&addr).await {
-            Ok(resp) => {
-                let bytes = resp
-                    .bytes()
-                    .await
-                    .map_err(|err| PaykitError::Transport(format!("{label}: {err}")))?;
-                if bytes.is_empty() {
-                    return Ok(None);
+    /// Endpoint layout this adapter reads from.
+    pub fn paths(&self) -> &PaykitPaths {
+        &self.paths
+    }
+
+    /// Fetch `payee`'s endpoint for `method` from the homeserver at `base_url`, e.g.
+    /// `https://homeserver.example.com`, without resolving the payee's key.
+    ///
+    /// The request goes to `{base_url}{endpoint path}` with the payee in the
+    /// `pubky-host` header, so `base_url` must serve the payee's data. Missing or empty
+    /// endpoints read as `None`, and the body size cap applies as for
+    /// [`fetch_payment_endpoint`](UnauthenticatedTransportRead::fetch_payment_endpoint).
+    /// Unlike SDK reads, a 429 carries the `Retry-After` delay in
+    /// `PaykitError::RateLimited`.
+    pub async fn fetch_payment_endpoint_from(
+        &self,
+        base_url: &Url,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        self.fetch_path_from(base_url, payee, &self.paths.endpoint(method))
+            .await
+    }
+
+    /// Fetches `path` of `payee`'s storage from the homeserver at `base_url`.
+    async fn fetch_path_from(
+        &self,
+        base_url: &Url,
+        payee: &PublicKey,
+        path: &str,
+    ) -> Result<Option<EndpointData>> {
+        Ok(self
+            .fetch_resource_from(base_url, payee, path)
+            .await?
+            .map(|(text, _stats)| EndpointData(text)))
+    }
+
+    /// Like [`Self::fetch_path_from`], also returning the response's metadata headers.
+    async fn fetch_resource_from(
+        &self,
+        base_url: &Url,
+        payee: &PublicKey,
+        path: &str,
+    ) -> Result<Option<(String, ResourceStats)>> {
+        let base_url = base_url.as_str().trim_end_matches('/');
+        let label = "fetch endpoint from mirror";
+        let Some(resp) = http::get(&self.http, base_url, payee, path, &[], label).await? else {
+            return Ok(None);
+        };
+        let stats = ResourceStats::from_headers(resp.headers());
+        let url = format!("{base_url}{path}");
+        Ok(self
+            .read_body(resp, &url, label)
+            .await?
+            .map(|text| (text, stats)))
+    }
+
+    /// List `payee`'s endpoints across several protocol versions of this adapter's app
+    /// prefix.
+    ///
+    /// `versions` is ordered newest first (e.g. `&["v1", "v0"]`). Every version is read
+    /// and the results are merged; when the same method id is published under more than
+    /// one version, the payload from the earliest entry in `versions` wins. Versions
+    /// with no data contribute nothing, and any transport failure aborts the listing.
+    pub async fn fetch_supported_payments_versioned(
+        &self,
+        payee: &PublicKey,
+        versions: &[&str],
+    ) -> Result<SupportedPayments> {
+        let mut merged = HashMap::new();
+        for version in versions {
+            let paths = self.paths.clone().with_version(version);
+            let payments = self.fetch_supported_payments_at(&paths, payee, "").await?;
+            for (method, data) in payments.entries {
+                merged.entry(method).or_insert(data);
+            }
+        }
+        Ok(SupportedPayments { entries: merged })
+    }
+
+    /// Stream `payee`'s endpoints as their bodies arrive instead of collecting them.
+    ///
+    /// Yields the same entries as
+    /// [`fetch_supported_payments`](UnauthenticatedTransportRead::fetch_supported_payments)
+    /// in completion order, with at most the configured fetch concurrency in flight.
+    /// Missing or empty bodies are skipped. A failure is yielded as an `Err` item;
+    /// callers that stop at the first error get the same semantics as the map variant.
+    pub fn stream_supported_payments<'a>(
+        &'a self,
+        payee: &'a PublicKey,
+    ) -> impl Stream<Item = Result<(MethodId, EndpointData)>> + Send + 'a {
+        self.stream_at(self.paths.clone(), payee, "")
+    }
+
+    /// List only `payee`'s endpoints whose method id starts with `prefix`, e.g.
+    /// `lightning.` for a family of methods.
+    ///
+    /// The directory listing is filtered before any body is requested, so endpoints
+    /// outside the prefix are never downloaded. An empty prefix matches every method.
+    pub async fn fetch_supported_payments_filtered(
+        &self,
+        payee: &PublicKey,
+        prefix: &str,
+    ) -> Result<SupportedPayments> {
+        self.fetch_supported_payments_at(&self.paths, payee, prefix)
+            .await
+    }
+
+    /// Lists the `(method, file)` pairs under `paths` whose method id starts with
+    /// `filter`, without fetching any body.
+    async fn list_targets(
+        &self,
+        paths: &PaykitPaths,
+        payee: &PublicKey,
+        filter: &str,
+    ) -> Result<Vec<(String, PubkyResource)>> {
+        let prefix = paths.prefix();
+        let addr = format!("pubky{payee}{prefix}");
+        let entries = self
+            .list_entries(addr, "list supported payments", !self.recursive)
+            .await?;
+
+        let mut targets = Vec::new();
+        for resource in entries {
+            if resource.path.as_str().ends_with('/') {
+                warn_skipped("supported payments", &resource, "directory");
+                continue;
+            }
+
+            let method = if self.recursive {
+                // Recursive listings keep the sub-path below the prefix in the method id.
+                match resource
+                    .path
+                    .as_str()
+                    .strip_prefix(prefix.as_str())
+                    .filter(|method| !method.is_empty())
+                {
+                    Some(method) => method.to_string(),
+                    None => {
+                        warn_skipped("supported payments", &resource, "outside the prefix");
+                        continue;
+                    }
+                }
+            } else {
+                match method_id_from_path(resource.path.as_str()) {
+                    Some(method) => method.0,
+                    None => {
+                        warn_skipped("supported payments", &resource, "invalid method id");
+                        continue;
+                    }
                 }
-                let data = String::from_utf8(bytes.to_vec())
-                    .map_err(|err| PaykitError::Transport(format!("{label}: {err}")))?;
-                Ok(Some(data))
+            };
+            if is_sidecar(&method) || !method.starts_with(filter) {
+                continue;
+            }
+            targets.push((method, resource));
+        }
+        Ok(targets)
+    }
+
+    /// Streams the endpoints under `paths` whose method id starts with `filter`.
+    fn stream_at<'a>(
+        &'a self,
+        paths: PaykitPaths,
+        payee: &'a PublicKey,
+        filter: &'a str,
+    ) -> impl Stream<Item = Result<(MethodId, EndpointData)>> + Send + 'a {
+        let targets = async move {
+            let targets = self.list_targets(&paths, payee, filter).await?;
+            Ok::<_, PaykitError>(stream::iter(targets.into_iter().map(Ok)))
+        };
+
+        // Bodies are independent, so fetch them concurrently (bounded to spare the
+        // homeserver).
+        stream::once(targets)
+            .try_flatten()
+            .map_ok(move |(method, resource)| async move {
+                let label = format!("fetch endpoint {}", method);
+                let payload = self.fetch_listed(&resource, &label).await?;
+                Ok(payload.map(|payload| (MethodId(method), EndpointData(payload))))
+            })
+            .try_buffer_unordered(self.fetch_concurrency)
+            .try_filter_map(future::ok)
+    }
+
+    async fn fetch_supported_payments_at(
+        &self,
+        paths: &PaykitPaths,
+        payee: &PublicKey,
+        filter: &str,
+    ) -> Result<SupportedPayments> {
+        // The first failure aborts the whole listing.
+        let entries = self
+            .stream_at(paths.clone(), payee, filter)
+            .try_collect::<HashMap<_, _>>()
+            .await?;
+        let entries = if self.case_insensitive_methods {
+            fold_method_case(entries)?
+        } else {
+            entries
+        };
+        Ok(SupportedPayments { entries })
+    }
+
+    /// Fetches a file found by a listing, from the mirror when one is set.
+    async fn fetch_listed(&self, resource: &PubkyResource, label: &str) -> Result<Option<String>> {
+        let Some(mirror) = &self.mirror else {
+            return self.fetch_text(resource.to_string(), label).await;
+        };
+        Ok(self
+            .fetch_resource_from(mirror, &resource.owner, resource.path.as_str())
+            .await?
+            .map(|(text, _stats)| text))
+    }
+
+    async fn fetch_text(&self, addr: String, label: &str) -> Result<Option<String>> {
+        Ok(self
+            .fetch_resource(addr, label)
+            .await?
+            .map(|(text, _stats)| text))
+    }
+
+    /// Like [`Self::fetch_text`], also returning the response's metadata headers.
+    async fn fetch_resource(
+        &self,
+        addr: String,
+        label: &str,
+    ) -> Result<Option<(String, ResourceStats)>> {
+        let resp = match self.inner.get(&addr).await {
+            Ok(resp) => resp,
+            Err(err) if is_not_found(&err) => return Ok(None),
+            Err(err) => return Err(transport_error(label, err)),
+        };
+        let stats = ResourceStats::from_headers(resp.headers());
+        Ok(self
+            .read_body(resp, &addr, label)
+            .await?
+            .map(|text| (text, stats)))
+    }
+
+    /// Buffers a successful response's body as UTF-8, enforcing the size cap. An empty
+    /// body reads as `None`.
+    async fn read_body(
+        &self,
+        mut resp: Response,
+        addr: &str,
+        label: &str,
+    ) -> Result<Option<String>> {
+        let limit = self.max_endpoint_bytes;
+        // Refuse early when the server announces the size, and keep counting while
+        // buffering in case it does not (or lies).
+        if let Some(len) = resp.content_length() {
+            let actual = usize::try_from(len).unwrap_or(usize::MAX);
+            if actual > limit {
+                return Err(PaykitError::PayloadTooLarge { limit, actual });
+            }
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = resp
+            .chunk()
+            .await
+            .map_err(|err| crate::network_error(&err))?
+        {
+            if bytes.len() + chunk.len() > limit {
+                return Err(PaykitError::PayloadTooLarge {
+                    limit,
+                    actual: bytes.len() + chunk.len(),
+                });
             }
-            Err(err) if is_not_found(&err) => Ok(None),
-            Err(err) => Err(PaykitError::Transport(format!("{label}: {err}"))),
+            bytes.extend_from_slice(&chunk);
         }
+
+        if bytes.is_empty() {
+            if self.strict_empty {
+                return Err(PaykitError::InvalidData(format!(
+                    "{label} ({addr}): empty endpoint body"
+                )));
+            }
+            return Ok(None);
+        }
+        let data = String::from_utf8(bytes)
+            .map_err(|err| PaykitError::InvalidData(format!("{label} ({addr}): {err}")))?;
+        Ok(Some(data))
     }
 
-    async fn list_entries(&self, addr: String, label: &str) -> Result<Vec<PubkyResource>> {
-        let builder = match self.inner.list(&addr) {
-            Ok(builder) => builder,
+    /// List every entry directly under `addr` (a `pubky<key>/path/` address), chunk by
+    /// chunk, keeping what was read before a failure.
+    ///
+    /// Returns the entries listed so far plus the error that stopped the walk, if any.
+    /// A missing directory is an empty listing, not an error. Use this when a partial
+    /// view of a large directory beats none; the trait methods stay all-or-nothing.
+    pub async fn list_entries_lenient(
+        &self,
+        addr: &str,
+    ) -> (Vec<PubkyResource>, Option<PaykitError>) {
+        self.list_chunks(addr, "list entries", true).await
+    }
+
+    /// All-or-nothing form of [`Self::list_entries_lenient`]; `shallow: false` lists
+    /// every file below `addr` instead.
+    async fn list_entries(
+        &self,
+        addr: String,
+        label: &str,
+        shallow: bool,
+    ) -> Result<Vec<PubkyResource>> {
+        match self.list_chunks(&addr, label, shallow).await {
+            (entries, None) => Ok(entries),
+            (_, Some(err)) => Err(err),
+        }
+    }
+
+    async fn list_chunks(
+        &self,
+        addr: &str,
+        label: &str,
+        shallow: bool,
+    ) -> (Vec<PubkyResource>, Option<PaykitError>) {
+        // Deep listings resume from a full URL: an entry's name alone is ambiguous
+        // across subdirectories.
+        let cursor_of = if shallow {
+            cursor_of
+        } else {
+            PubkyResource::to_pubky_url
+        };
+        collect_chunks(LIST_CHUNK, cursor_of, |cursor| async move {
+            self.list_page(
+                addr.to_string(),
+                label,
+                cursor.as_deref(),
+                Some(LIST_CHUNK as u16),
+                shallow,
+            )
+            .await
+        })
+        .await
+    }
+
+    async fn list_page(
+        &self,
+        addr: String,
+        label: &str,
+        cursor: Option<&str>,
+        limit: Option<u16>,
+        shallow: bool,
+    ) -> Result<Vec<PubkyResource>> {
+        if let Some(mirror) = &self.mirror {
+            return self
+                .list_page_from(mirror, &addr, label, cursor, limit, shallow)
+                .await;
+        }
+        let mut builder = match self.inner.list(&addr) {
+            Ok(builder) => builder.shallow(shallow),
             Err(err) if is_not_found(&err) => return Ok(Vec::new()),
-            Err(err) => return Err(PaykitError::Transport(format!("{label}: {err}"))),
+            Err(err) => return Err(transport_error(label, err)),
         };
+        if let Some(cursor) = cursor {
+            builder = builder.cursor(cursor);
+        }
+        if let Some(limit) = limit {
+            builder = builder.limit(limit);
+        }
 
-        match builder.shallow(true).send().await {
+        match builder.send().await {
             Ok(entries) => Ok(entries),
             Err(err) if is_not_found(&err) => Ok(Vec::new()),
-            Err(err) => Err(PaykitError::Transport(format!(
-                "{label} send failed: {err}"
-            ))),
+            Err(err) => Err(transport_error(&format!("{label} send failed"), err)),
+        }
+    }
+
+    /// Like [`Self::list_page`], listing `addr` on the homeserver at `base_url` with
+    /// the query the SDK would send.
+    async fn list_page_from(
+        &self,
+        base_url: &Url,
+        addr: &str,
+        label: &str,
+        cursor: Option<&str>,
+        limit: Option<u16>,
+        shallow: bool,
+    ) -> Result<Vec<PubkyResource>> {
+        let dir: PubkyResource = addr.parse().map_err(|err| transport_error(label, err))?;
+        let base_url = base_url.as_str().trim_end_matches('/');
+        let limit = limit.map(|limit| limit.to_string());
+        let mut query = Vec::new();
+        if shallow {
+            query.push(("shallow", "true"));
+        }
+        if let Some(limit) = &limit {
+            query.push(("limit", limit.as_str()));
+        }
+        if let Some(cursor) = cursor {
+            query.push(("cursor", cursor));
+        }
+        let path = dir.path.as_str();
+        let Some(resp) = http::get(&self.http, base_url, &dir.owner, path, &query, label).await?
+        else {
+            return Ok(Vec::new());
+        };
+        let body = resp
+            .text()
+            .await
+            .map_err(|err| crate::network_error(&err))?;
+
+        // Listings are one `pubky://<owner>/<path>` URL per line.
+        body.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| line.parse().map_err(|err| transport_error(label, err)))
+            .collect()
+    }
+}
+
+/// Builder for a [`PubkyUnauthenticatedTransport`] with its own `PubkyHttpClient`.
+///
+/// Readers never talk to a homeserver directly: the payee's homeserver is discovered by
+/// resolving their PKARR record, so the network knobs below select where that
+/// resolution happens. Each maps onto the SDK's `PubkyHttpClientBuilder`:
+///
+/// | Builder method | SDK option |
+/// |---|---|
+/// | [`testnet`](Self::testnet) / [`testnet_with_host`](Self::testnet_with_host) | `PubkyHttpClientBuilder::testnet_with_host` (local DHT bootstrap and PKARR relay ports) |
+/// | [`relays`](Self::relays) | `pkarr::ClientBuilder::relays` (or `no_relays` when empty), replacing the default relay list |
+/// | [`bootstrap`](Self::bootstrap) | `pkarr::ClientBuilder::bootstrap`, replacing the DHT bootstrap nodes |
+/// | [`request_timeout`](Self::request_timeout) | `PubkyHttpClientBuilder::request_timeout` |
+/// | [`user_agent_extra`](Self::user_agent_extra) | `PubkyHttpClientBuilder::user_agent_extra` |
+///
+/// Explicit relays and bootstrap nodes are applied after the testnet preset, so they win
+/// when both are set. [`paths`](Self::paths) and
+/// [`fetch_concurrency`](Self::fetch_concurrency) configure the adapter itself.
+///
+/// # Examples
+/// ```no_run
+/// # use std::time::Duration;
+/// # use paykit_lib::PubkyUnauthenticatedTransport;
+/// let reader = PubkyUnauthenticatedTransport::builder()
+///     .relays(["https://pkarr.example.net/"])
+///     .request_timeout(Duration::from_secs(10))
+///     .build()?;
+/// # Ok::<_, paykit_lib::PaykitError>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct PubkyUnauthenticatedTransportBuilder {
+    testnet_host: Option<String>,
+    relays: Option<Vec<String>>,
+    bootstrap: Option<Vec<String>>,
+    request_timeout: Option<Duration>,
+    user_agent_extra: Option<String>,
+    paths: PaykitPaths,
+    fetch_concurrency: usize,
+    max_endpoint_bytes: usize,
+    case_insensitive_methods: bool,
+    strict_empty: bool,
+    recursive: bool,
+    mirror: Option<Url>,
+}
+
+impl Default for PubkyUnauthenticatedTransportBuilder {
+    fn default() -> Self {
+        Self {
+            testnet_host: None,
+            relays: None,
+            bootstrap: None,
+            request_timeout: None,
+            user_agent_extra: None,
+            paths: PaykitPaths::default(),
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            max_endpoint_bytes: DEFAULT_MAX_ENDPOINT_BYTES,
+            case_insensitive_methods: false,
+            strict_empty: false,
+            recursive: false,
+            mirror: None,
+        }
+    }
+}
+
+impl PubkyUnauthenticatedTransportBuilder {
+    /// Target a Pubky testnet running on `localhost`.
+    pub fn testnet(self) -> Self {
+        self.testnet_with_host("localhost")
+    }
+
+    /// Target a Pubky testnet running on `host` (e.g. a Docker service name).
+    pub fn testnet_with_host(mut self, host: impl Into<String>) -> Self {
+        self.testnet_host = Some(host.into());
+        self
+    }
+
+    /// Resolve PKARR records through these relay URLs instead of the defaults. An empty
+    /// list disables relays, leaving DHT resolution only.
+    pub fn relays<I, S>(mut self, relays: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.relays = Some(relays.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Bootstrap the DHT client from these `host:port` nodes instead of the defaults.
+    pub fn bootstrap<I, S>(mut self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.bootstrap = Some(nodes.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Timeout applied to each HTTP request.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Extra user-agent segment, e.g. `"mywallet/1.2.3"`.
+    pub fn user_agent_extra(mut self, extra: impl Into<String>) -> Self {
+        self.user_agent_extra = Some(extra.into());
+        self
+    }
+
+    /// Endpoint and follows layout to read; defaults to [`PaykitPaths::default`].
+    pub fn paths(mut self, paths: PaykitPaths) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_fetch_concurrency`].
+    pub fn fetch_concurrency(mut self, limit: usize) -> Self {
+        self.fetch_concurrency = limit.max(1);
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_max_endpoint_bytes`].
+    pub fn max_endpoint_bytes(mut self, limit: usize) -> Self {
+        self.max_endpoint_bytes = limit;
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_case_insensitive_methods`].
+    pub fn case_insensitive_methods(mut self, enabled: bool) -> Self {
+        self.case_insensitive_methods = enabled;
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_strict_empty`].
+    pub fn strict_empty(mut self, enabled: bool) -> Self {
+        self.strict_empty = enabled;
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_recursive`].
+    pub fn recursive(mut self, enabled: bool) -> Self {
+        self.recursive = enabled;
+        self
+    }
+
+    /// Same as [`PubkyUnauthenticatedTransport::with_mirror`].
+    pub fn mirror(mut self, base_url: Url) -> Self {
+        self.mirror = Some(base_url);
+        self
+    }
+
+    /// Build the HTTP client and wrap its public storage in an adapter.
+    ///
+    /// Fails with `PaykitError::Transport` for an invalid relay URL and with
+    /// `PaykitError::TransportSource` when the SDK client cannot be built.
+    pub fn build(self) -> Result<PubkyUnauthenticatedTransport> {
+        let mut builder = PubkyHttpClient::builder();
+        if let Some(host) = &self.testnet_host {
+            builder.testnet_with_host(host);
+        }
+
+        let mut invalid_relay = None;
+        builder.pkarr(|pkarr| {
+            match &self.relays {
+                Some(relays) if relays.is_empty() => {
+                    pkarr.no_relays();
+                }
+                Some(relays) => {
+                    if let Err(err) = pkarr.relays(relays) {
+                        invalid_relay = Some(err.to_string());
+                    }
+                }
+                None => {}
+            }
+            if let Some(nodes) = &self.bootstrap {
+                pkarr.bootstrap(nodes);
+            }
+            pkarr
+        });
+        if let Some(err) = invalid_relay {
+            return Err(PaykitError::Transport(format!(
+                "invalid pkarr relay: {err}"
+            )));
+        }
+
+        if let Some(timeout) = self.request_timeout {
+            builder.request_timeout(timeout);
         }
+        if let Some(extra) = &self.user_agent_extra {
+            builder.user_agent_extra(extra.as_str());
+        }
+
+        let client = builder
+            .build()
+            .map_err(|err| PaykitError::TransportSource(Box::new(err)))?;
+        let storage = Pubky::with_client(client).public_storage();
+        let mut reader = PubkyUnauthenticatedTransport::new_with_paths(storage, self.paths)
+            .with_fetch_concurrency(self.fetch_concurrency)
+            .with_max_endpoint_bytes(self.max_endpoint_bytes)
+            .with_case_insensitive_methods(self.case_insensitive_methods)
+            .with_strict_empty(self.strict_empty)
+            .with_recursive(self.recursive);
+        if let Some(mirror) = self.mirror {
+            reader = reader.with_mirror(mirror);
+        }
+        Ok(reader)
     }
 }
 
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 impl UnauthenticatedTransportRead for PubkyUnauthenticatedTransport {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(payee = %payee), err(Display))
+    )]
     async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
-        let addr = format!("pubky{payee}{PAYKIT_PATH_PREFIX}");
-        let entries = self.list_entries(addr, "list supported payments").await?;
+        self.fetch_supported_payments_at(&self.paths, payee, "")
+            .await
+    }
 
-        let mut map = HashMap::new();
-        for resource in entries {
-            if resource.path.as_str().ends_with('/') {
-                continue;
-            }
+    /// Lists the payee's directory once, then fetches every body concurrently, keeping
+    /// the failures apart. With case-insensitive methods enabled, a case collision among
+    /// the fetched entries still fails the whole call.
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)> {
+        let targets = self.list_targets(&self.paths, payee, "").await?;
+        let results: Vec<_> = stream::iter(targets)
+            .map(|(method, resource)| async move {
+                let label = format!("fetch endpoint {}", method);
+                let payload = self.fetch_listed(&resource, &label).await;
+                (MethodId(method), payload)
+            })
+            .buffer_unordered(self.fetch_concurrency)
+            .collect()
+            .await;
 
-            let method = resource
-                .path
-                .as_str()
-                .rsplit('/')
-                .next()
-                .filter(|segment| !segment.is_empty())
-                .ok_or_else(|| {
-                    PaykitError::Transport(
-                        "invalid resource returned for supported payment entry".into(),
-                    )
-                })?
-                .to_string();
-
-            let label = format!("fetch endpoint {}", method);
-            if let Some(payload) = self.fetch_text(resource.to_string(), &label).await? {
-                map.insert(MethodId(method), EndpointData(payload));
+        let mut entries = HashMap::new();
+        let mut failures = Vec::new();
+        for (method, payload) in results {
+            match payload {
+                Ok(Some(payload)) => {
+                    entries.insert(method, EndpointData(payload));
+                }
+                Ok(None) => {}
+                Err(err) => failures.push((method, err)),
             }
         }
+        if self.case_insensitive_methods {
+            entries = fold_method_case(entries)?;
+        }
+        failures.sort_by(|(a, _), (b, _)| a.0.cmp(&b.0));
+        Ok((SupportedPayments { entries }, failures))
+    }
 
-        Ok(SupportedPayments { entries: map })
+    /// Counts the files in the payee's listing; bodies are never downloaded, so empty
+    /// files that [`fetch_supported_payments`](Self::fetch_supported_payments) would skip
+    /// are included.
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize> {
+        let addr = format!("pubky{payee}{}", self.paths.prefix());
+        let entries = self
+            .list_entries(addr, "count supported payments", !self.recursive)
+            .await?;
+        Ok(entries
+            .iter()
+            .filter(|resource| {
+                let path = resource.path.as_str();
+                !path.ends_with('/') && !is_sidecar(path)
+            })
+            .count())
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(payee = %payee, method = %method), err(Display))
+    )]
     async fn fetch_payment_endpoint(
         &self,
         payee: &PublicKey,
         method: &MethodId,
     ) -> Result<Option<EndpointData>> {
-        let addr = format!("pubky{payee}{PAYKIT_PATH_PREFIX}{}", method.0);
+        if let Some(mirror) = &self.mirror {
+            return self
+                .fetch_payment_endpoint_from(mirror, payee, method)
+                .await;
+        }
+        let addr = format!("pubky{payee}{}", self.paths.endpoint(method));
         match self.fetch_text(addr, "fetch endpoint").await? {
             Some(payload) => Ok(Some(EndpointData(payload))),
             None => Ok(None),
         }
     }
 
+    /// Reads from the mirror when one is configured, like
+    /// [`fetch_payment_endpoint`](Self::fetch_payment_endpoint).
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>> {
+        let path = self.paths.endpoint(method);
+        let fetched = match &self.mirror {
+            Some(mirror) => self.fetch_resource_from(mirror, payee, &path).await?,
+            None => {
+                self.fetch_resource(format!("pubky{payee}{path}"), "fetch endpoint")
+                    .await?
+            }
+        };
+        let Some((payload, stats)) = fetched else {
+            return Ok(None);
+        };
+        Ok(Some(FetchedEndpoint {
+            data: EndpointData(payload),
+            content_type: stats.content_type.filter(|value| !value.is_empty()),
+            last_modified: stats.last_modified,
+        }))
+    }
+
+    /// Reads from the mirror when one is configured, like
+    /// [`fetch_payment_endpoint`](Self::fetch_payment_endpoint).
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        validate_relative_path(relative_path)?;
+        let path = self.paths.file(relative_path);
+        if let Some(mirror) = &self.mirror {
+            return self.fetch_path_from(mirror, payee, &path).await;
+        }
+        let addr = format!("pubky{payee}{path}");
+        Ok(self
+            .fetch_text(addr, "fetch raw file")
+            .await?
+            .map(EndpointData))
+    }
+
+    /// Reads from the mirror when one is configured, like
+    /// [`fetch_payment_endpoint`](Self::fetch_payment_endpoint).
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool> {
+        // Empty files read as missing, matching `fetch_payment_endpoint`; a server
+        // omitting the length is taken at its word.
+        let path = self.paths.endpoint(method);
+        if let Some(mirror) = &self.mirror {
+            // The body is left unread; only the headers are inspected.
+            let base_url = mirror.as_str().trim_end_matches('/');
+            let label = "check endpoint on mirror";
+            let resp = http::get(&self.http, base_url, payee, &path, &[], label).await?;
+            return Ok(resp.is_some_and(|resp| {
+                ResourceStats::from_headers(resp.headers()).content_length != Some(0)
+            }));
+        }
+        // HEAD only: the body is never transferred.
+        let addr = format!("pubky{payee}{path}");
+        match self.inner.stats(&addr).await {
+            Ok(stats) => Ok(stats.is_some_and(|stats| stats.content_length != Some(0))),
+            Err(err) if is_not_found(&err) => Ok(false),
+            Err(err) => Err(transport_error("check endpoint", err)),
+        }
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(owner = %owner), err(Display))
+    )]
     async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
-        let addr = format!("pubky{owner}{PUBKY_FOLLOWS_PATH}");
-        let entries = self.list_entries(addr, "list known contacts").await?;
+        let (contacts, _skipped) = self.fetch_known_contacts_with_report(owner).await?;
+        Ok(contacts)
+    }
 
-        let mut contacts = Vec::new();
-        for resource in entries {
-            if resource.path.as_str().ends_with('/') {
-                continue;
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        let addr = format!("pubky{owner}{}", self.paths.follows());
+        let entries = self.list_entries(addr, "list known contacts", true).await?;
+        Ok(parse_contacts(&entries))
+    }
+
+    /// Lists one page of contacts through the homeserver's `cursor`/`limit` options.
+    ///
+    /// `limit` is clamped to `1..=`[`MAX_CONTACTS_PAGE`]. The returned cursor is the name
+    /// of the last entry on the page, and is `None` once the listing is exhausted.
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        cursor: Option<String>,
+        limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)> {
+        let limit = limit.clamp(1, MAX_CONTACTS_PAGE);
+        let addr = format!("pubky{owner}{}", self.paths.follows());
+        // Ask for one extra entry so the last page is known without another round trip.
+        let mut entries = self
+            .list_page(
+                addr,
+                "list known contacts page",
+                cursor.as_deref(),
+                Some(limit as u16 + 1),
+                true,
+            )
+            .await?;
+
+        let next = if entries.len() > limit {
+            entries.truncate(limit);
+            entries.last().map(cursor_of)
+        } else {
+            None
+        };
+        Ok((parse_contacts(&entries).0, next))
+    }
+
+    /// Sends a `HEAD` for the Paykit directory on the mirror set with
+    /// [`with_mirror`](PubkyUnauthenticatedTransport::with_mirror); any HTTP response
+    /// counts as reachable. Without a mirror every read resolves its payee's homeserver
+    /// on demand, so there is no payee-independent server to probe and the check
+    /// succeeds.
+    async fn health_check(&self) -> Result<()> {
+        let Some(mirror) = &self.mirror else {
+            return Ok(());
+        };
+        let base_url = mirror.as_str().trim_end_matches('/');
+        self.http
+            .head(format!("{base_url}{}", self.paths.prefix()))
+            .send()
+            .await
+            .map_err(|err| {
+                crate::map_transport_error("health check", crate::network_error(&err))
+            })?;
+        Ok(())
+    }
+}
+
+/// Listing cursor resuming after `resource`: its name, with a trailing slash for
+/// directories.
+fn cursor_of(resource: &PubkyResource) -> String {
+    let path = resource.path.as_str();
+    let name = path.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    if path.ends_with('/') {
+        format!("{name}/")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Calls `fetch` with successive cursors, derived from each chunk's last entry by
+/// `cursor_of`, until a chunk comes back shorter than `chunk`.
+///
+/// Entries from chunks read before a failure are kept and returned with the error. A
+/// full chunk whose last entry does not move the cursor ends the walk with
+/// `PaykitError::InvalidData` instead of looping forever.
+async fn collect_chunks<F, Fut>(
+    chunk: usize,
+    cursor_of: fn(&PubkyResource) -> String,
+    mut fetch: F,
+) -> (Vec<PubkyResource>, Option<PaykitError>)
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Vec<PubkyResource>>>,
+{
+    let mut entries = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = match fetch(cursor.clone()).await {
+            Ok(page) => page,
+            Err(err) => return (entries, Some(err)),
+        };
+        let next = page.last().map(cursor_of);
+        let done = page.len() < chunk;
+        entries.extend(page);
+        match next {
+            _ if done => return (entries, None),
+            Some(next) if cursor.as_ref() != Some(&next) => cursor = Some(next),
+            next => {
+                let err = PaykitError::InvalidData(format!(
+                    "directory listing: cursor {next:?} did not advance"
+                ));
+                return (entries, Some(err));
             }
-            let name = resource
-                .path
-                .as_str()
-                .rsplit('/')
-                .next()
-                .filter(|segment| !segment.is_empty());
-            if let Some(pk_str) = name {
-                match pk_str.parse::<PublicKey>() {
-                    Ok(pk) => contacts.push(pk),
-                    Err(err) => {
-                        return Err(PaykitError::Transport(format!(
-                            "invalid contact entry '{pk_str}': {err}"
-                        )))
-                    }
+        }
+    }
+}
+
+/// Re-keys `entries` by lowercased method id, failing when two ids fold together.
+fn fold_method_case(
+    entries: HashMap<MethodId, EndpointData>,
+) -> Result<HashMap<MethodId, EndpointData>> {
+    let mut folded: HashMap<MethodId, (String, EndpointData)> = HashMap::new();
+    for (method, data) in entries {
+        let key = MethodId(method.0.to_ascii_lowercase());
+        if let Some((existing, _)) = folded.get(&key) {
+            // Name the pair in a stable order; listings arrive in completion order.
+            let mut pair = [existing.as_str(), method.0.as_str()];
+            pair.sort_unstable();
+            return Err(PaykitError::InvalidData(format!(
+                "method ids {:?} and {:?} collide when case-folded",
+                pair[0], pair[1]
+            )));
+        }
+        folded.insert(key, (method.0, data));
+    }
+    Ok(folded
+        .into_iter()
+        .map(|(method, (_, data))| (method, data))
+        .collect())
+}
+
+/// Records a listing entry that was left out, so operators can tell why data is
+/// missing. A no-op without the `tracing` feature.
+#[cfg(feature = "tracing")]
+fn warn_skipped(listing: &str, resource: &PubkyResource, reason: &str) {
+    tracing::warn!(listing, resource = %resource, reason, "skipped listing entry");
+}
+
+#[cfg(not(feature = "tracing"))]
+fn warn_skipped(_listing: &str, _resource: &PubkyResource, _reason: &str) {}
+
+/// Splits listed follow entries into valid contacts, each key once, and unparsable
+/// names.
+pub(crate) fn parse_contacts(entries: &[PubkyResource]) -> (Vec<PublicKey>, Vec<String>) {
+    let mut contacts = Vec::new();
+    let mut seen = HashSet::new();
+    let mut skipped = Vec::new();
+    for resource in entries {
+        if resource.path.as_str().ends_with('/') {
+            warn_skipped("known contacts", resource, "directory");
+            continue;
+        }
+        let path = resource.path.as_str();
+        if let Some(name) = entry_name(path) {
+            // A single stray file must not break contact discovery; report it instead.
+            match contact_key_from_path(path) {
+                Some(pk) if seen.insert(pk.clone()) => contacts.push(pk),
+                // Another file name (e.g. with a `pk:` prefix) already yielded this key.
+                Some(_) => warn_skipped("known contacts", resource, "duplicate key"),
+                None => {
+                    warn_skipped("known contacts", resource, "not a public key");
+                    skipped.push(name.to_string());
                 }
             }
         }
-
-        Ok(contacts)
     }
+    // Homeservers list in key order already; sort anyway so the guarantee does not
+    // depend on the server.
+    contacts.sort_by_cached_key(ToString::to_string);
+    skipped.sort();
+    (contacts, skipped)
 }
 
-fn is_not_found(err: &PubkyError) -> bool {
-    matches!(
-        err,
-        PubkyError::Request(RequestError::Server { status, .. })
-            if *status == StatusCode::NOT_FOUND || *status == StatusCode::GONE
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::paths::PUBKY_FOLLOWS_PATH;
+
+    fn resources(owner: &PublicKey, names: impl IntoIterator<Item = usize>) -> Vec<PubkyResource> {
+        names
+            .into_iter()
+            .map(|n| {
+                format!("pubky{owner}{PUBKY_FOLLOWS_PATH}entry-{n:04}")
+                    .parse()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn skipped_contacts_are_logged() {
+        use std::sync::{Arc, Mutex};
+
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+        /// Records the level and fields of every event.
+        #[derive(Clone, Default)]
+        struct Events(Arc<Mutex<Vec<(tracing::Level, String)>>>);
+
+        struct Fields(String);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push_str(&format!("{}={value:?} ", field.name()));
+            }
+        }
+
+        impl<S: tracing::Subscriber> Layer<S> for Events {
+            fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+                let mut fields = Fields(String::new());
+                event.record(&mut fields);
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push((*event.metadata().level(), fields.0));
+            }
+        }
+
+        let owner = crate::mock::random_public_key();
+        let contact = crate::mock::random_public_key();
+        let entries: Vec<PubkyResource> = [
+            format!("pubky{owner}{PUBKY_FOLLOWS_PATH}{contact}"),
+            format!("pubky{owner}{PUBKY_FOLLOWS_PATH}not-a-key"),
+        ]
+        .iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect();
+
+        let events = Events::default();
+        let subscriber = tracing_subscriber::registry().with(events.clone());
+        let (contacts, skipped) =
+            tracing::subscriber::with_default(subscriber, || parse_contacts(&entries));
+        assert_eq!(contacts, vec![contact]);
+        assert_eq!(skipped, vec!["not-a-key".to_string()]);
+
+        let recorded = events.0.lock().unwrap().clone();
+        assert_eq!(recorded.len(), 1, "{recorded:?}");
+        let (level, fields) = &recorded[0];
+        assert_eq!(*level, tracing::Level::WARN);
+        assert!(fields.contains("not-a-key"), "{fields}");
+        assert!(fields.contains("reason=\"not a public key\""), "{fields}");
+    }
+
+    #[test]
+    fn contacts_are_deduplicated_by_key() {
+        let owner = crate::mock::random_public_key();
+        let contact = crate::mock::random_public_key();
+        let entries: Vec<PubkyResource> = [
+            format!("pubky{owner}{PUBKY_FOLLOWS_PATH}{contact}"),
+            format!("pubky{owner}{PUBKY_FOLLOWS_PATH}pk:{contact}"),
+        ]
+        .iter()
+        .map(|addr| addr.parse().unwrap())
+        .collect();
+
+        let (contacts, skipped) = parse_contacts(&entries);
+        assert_eq!(contacts, vec![contact]);
+        assert!(skipped.is_empty(), "{skipped:?}");
+    }
+
+    #[test]
+    fn folding_method_case_detects_collisions() {
+        let entry = |method: &str| (MethodId(method.into()), EndpointData(method.into()));
+
+        let folded = fold_method_case([entry("Lightning"), entry("onchain")].into()).unwrap();
+        assert_eq!(folded[&MethodId("lightning".into())].0, "Lightning");
+        assert_eq!(folded[&MethodId("onchain".into())].0, "onchain");
+
+        let err = fold_method_case([entry("lightning"), entry("Lightning")].into()).unwrap_err();
+        assert!(
+            matches!(&err, PaykitError::InvalidData(msg) if msg.contains("\"Lightning\" and \"lightning\"")),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn lenient_listing_keeps_chunks_read_before_a_failure() {
+        let owner = crate::mock::random_public_key();
+        let mut calls = Vec::new();
+        let (entries, err) = collect_chunks(2, cursor_of, |cursor| {
+            calls.push(cursor.clone());
+            let result = match calls.len() {
+                1 => Ok(resources(&owner, [0, 1])),
+                2 => Ok(resources(&owner, [2, 3])),
+                _ => Err(PaykitError::Transport("connection reset".into())),
+            };
+            async move { result }
+        })
+        .await;
+
+        assert_eq!(entries, resources(&owner, 0..4));
+        assert!(
+            matches!(&err, Some(PaykitError::Transport(msg)) if msg == "connection reset"),
+            "{err:?}"
+        );
+        assert_eq!(
+            calls,
+            [None, Some("entry-0001".into()), Some("entry-0003".into())]
+        );
+    }
+
+    #[tokio::test]
+    async fn listing_stops_at_a_short_chunk() {
+        let owner = crate::mock::random_public_key();
+        let mut calls = 0;
+        let (entries, err) = collect_chunks(2, cursor_of, |_| {
+            calls += 1;
+            let page = if calls == 1 {
+                resources(&owner, [0, 1])
+            } else {
+                resources(&owner, [2])
+            };
+            async move { Ok(page) }
+        })
+        .await;
+
+        assert_eq!(entries.len(), 3);
+        assert!(err.is_none());
+        assert_eq!(calls, 2);
+    }
 }
@@ -1,15 +1,47 @@
 //! Core transport traits that decouple Paykit logic from specific SDKs or backends.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
-use crate::{EndpointData, MethodId, PublicKey, Result};
+use crate::{
+    EndpointData, FetchedEndpoint, MethodId, PaykitError, PublicKey, Result, SupportedPayments,
+};
 
 /// Trait describing read-only access to public Paykit transport.
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait UnauthenticatedTransportRead {
     /// Fetches the raw Supported Payments List for the provided `payee`.
-    async fn fetch_supported_payments(&self, payee: &PublicKey)
-        -> Result<crate::SupportedPayments>;
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments>;
+
+    /// Counts the methods `payee` publishes.
+    ///
+    /// The default implementation fetches the full list via
+    /// [`Self::fetch_supported_payments`]; transports that can list methods without
+    /// pulling every body should override it.
+    async fn count_supported_payments(&self, payee: &PublicKey) -> Result<usize>
+    where
+        Self: Sync,
+    {
+        Ok(self.fetch_supported_payments(payee).await?.len())
+    }
+
+    /// Like [`Self::fetch_supported_payments`], but a method whose body cannot be read
+    /// is reported next to the list instead of failing the whole call. Only a failure to
+    /// list `payee`'s methods is returned as `Err`. Failures are sorted by method id.
+    ///
+    /// The default implementation cannot tell the two apart: it returns the full list
+    /// from [`Self::fetch_supported_payments`] with no failures, or its error.
+    async fn fetch_supported_payments_lenient(
+        &self,
+        payee: &PublicKey,
+    ) -> Result<(SupportedPayments, Vec<(MethodId, PaykitError)>)>
+    where
+        Self: Sync,
+    {
+        Ok((self.fetch_supported_payments(payee).await?, Vec::new()))
+    }
 
     /// Fetches an individual payment endpoint document if it exists.
     async fn fetch_payment_endpoint(
@@ -18,16 +50,235 @@ pub trait UnauthenticatedTransportRead {
         method: &MethodId,
     ) -> Result<Option<EndpointData>>;
 
+    /// Like [`Self::fetch_payment_endpoint`], plus the metadata the transport knows about
+    /// the stored payload.
+    ///
+    /// The default implementation leaves every metadata field `None`.
+    async fn fetch_payment_endpoint_full(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<FetchedEndpoint>>
+    where
+        Self: Sync,
+    {
+        Ok(self
+            .fetch_payment_endpoint(payee, method)
+            .await?
+            .map(FetchedEndpoint::from))
+    }
+
+    /// Reports whether `payee` publishes a non-empty endpoint for `method`.
+    ///
+    /// The default implementation downloads the endpoint via
+    /// [`Self::fetch_payment_endpoint`]; transports that can check existence without
+    /// pulling the body should override it.
+    async fn fetch_payment_endpoint_exists(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<bool>
+    where
+        Self: Sync,
+    {
+        Ok(self.fetch_payment_endpoint(payee, method).await?.is_some())
+    }
+
+    /// Fetches a file stored under `payee`'s Paykit directory that is not necessarily a
+    /// method endpoint, e.g. `profile.json`.
+    ///
+    /// `relative_path` is resolved against the directory endpoints live in and must pass
+    /// [`validate_relative_path`](crate::transport::paths::validate_relative_path).
+    /// Missing files read as `Ok(None)`. The default implementation returns
+    /// `PaykitError::Unimplemented`.
+    async fn fetch_raw(
+        &self,
+        _payee: &PublicKey,
+        _relative_path: &str,
+    ) -> Result<Option<EndpointData>>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented("fetch_raw"))
+    }
+
     /// Returns the set of known contacts (public keys) reachable to the caller.
+    ///
+    /// Implementations should sort the result lexicographically by key string, as the
+    /// first-party transports do, so repeated calls yield the same order.
     async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>>;
+
+    /// Like [`Self::fetch_known_contacts`], but also returns the raw entry names that
+    /// could not be parsed as public keys instead of silently dropping them. Both lists
+    /// follow the same ordering as [`Self::fetch_known_contacts`].
+    ///
+    /// The default implementation reports no skipped entries.
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)>
+    where
+        Self: Sync,
+    {
+        Ok((self.fetch_known_contacts(owner).await?, Vec::new()))
+    }
+
+    /// Fetches up to `limit` contacts of `owner`, resuming after `cursor`.
+    ///
+    /// Returns the page plus the cursor for the next one, or `None` once the listing is
+    /// exhausted. Cursors are opaque and only meaningful to the transport that issued
+    /// them. The default implementation has no native pagination: it returns every
+    /// contact from [`Self::fetch_known_contacts`] as a single, final page.
+    async fn fetch_known_contacts_page(
+        &self,
+        owner: &PublicKey,
+        _cursor: Option<String>,
+        _limit: usize,
+    ) -> Result<(Vec<PublicKey>, Option<String>)>
+    where
+        Self: Sync,
+    {
+        Ok((self.fetch_known_contacts(owner).await?, None))
+    }
+
+    /// Checks that the backing service is reachable, e.g. before a batch of reads.
+    ///
+    /// Failures are reported as `PaykitError::Transport`. The default implementation
+    /// has nothing to probe and always succeeds.
+    async fn health_check(&self) -> Result<()>
+    where
+        Self: Sync,
+    {
+        Ok(())
+    }
 }
 
+/// Shared, type-erased reader.
+///
+/// Both traits are object safe, so readers of different concrete types can live in
+/// one collection. The helpers accept unsized readers; pass `&*reader` to call them.
+/// The alias is `Send + Sync`, so it can be shared across tasks.
+///
+/// # Examples
+/// ```
+/// # use paykit_lib::{get_payment_list, DynRead, PublicKey};
+/// # async fn demo(readers: Vec<DynRead>, pk: &PublicKey) -> paykit_lib::Result<()> {
+/// for reader in &readers {
+///     let payments = get_payment_list(&**reader, pk).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub type DynRead = Arc<dyn UnauthenticatedTransportRead + Send + Sync>;
+
 /// Trait describing authenticated write (and optional read) access.
-#[async_trait]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 pub trait AuthenticatedTransport {
     /// Writes or updates a payment endpoint document.
     async fn upsert_payment_endpoint(&self, method: &MethodId, data: &EndpointData) -> Result<()>;
 
     /// Removes an existing payment endpoint for the provided method.
     async fn remove_payment_endpoint(&self, method: &MethodId) -> Result<()>;
+
+    /// Writes a payment endpoint and advertises it with `content_type` (e.g.
+    /// `application/json`) to readers that look at
+    /// [`UnauthenticatedTransportRead::fetch_payment_endpoint_full`].
+    ///
+    /// The default implementation forwards to [`Self::upsert_payment_endpoint`] and drops
+    /// the content type, for transports that cannot store one.
+    async fn upsert_payment_endpoint_with_type(
+        &self,
+        method: &MethodId,
+        data: &EndpointData,
+        _content_type: &str,
+    ) -> Result<()>
+    where
+        Self: Sync,
+    {
+        self.upsert_payment_endpoint(method, data).await
+    }
+
+    /// Writes `data` only if the endpoint's current ETag equals `expected_etag`, and
+    /// returns the ETag of the new content.
+    ///
+    /// `None` expects the endpoint to be absent, i.e. the write only creates it. A
+    /// mismatch fails with `PaykitError::Conflict` without writing. The default
+    /// implementation returns `PaykitError::Unimplemented`.
+    async fn upsert_payment_endpoint_if_match(
+        &self,
+        _method: &MethodId,
+        _data: &EndpointData,
+        _expected_etag: Option<String>,
+    ) -> Result<String>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented(
+            "upsert_payment_endpoint_if_match",
+        ))
+    }
+
+    /// Lists the endpoints currently published by the authenticated identity.
+    ///
+    /// Transports that cannot read their own data keep the default, which returns
+    /// `PaykitError::Unimplemented`.
+    async fn list_own_endpoints(&self) -> Result<SupportedPayments>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented("list_own_endpoints"))
+    }
+
+    /// Lists the known contacts of the authenticated identity, sorted by key.
+    ///
+    /// Transports that cannot read their own data keep the default, which returns
+    /// `PaykitError::Unimplemented`.
+    async fn list_own_contacts(&self) -> Result<Vec<PublicKey>>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented("list_own_contacts"))
+    }
+
+    /// Records `contact` as a known contact of the authenticated identity.
+    ///
+    /// The default implementation returns `PaykitError::Unimplemented`.
+    async fn add_contact(&self, _contact: &PublicKey) -> Result<()>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented("add_contact"))
+    }
+
+    /// Removes `contact` from the authenticated identity's known contacts.
+    ///
+    /// The default implementation returns `PaykitError::Unimplemented`.
+    async fn remove_contact(&self, _contact: &PublicKey) -> Result<()>
+    where
+        Self: Sync,
+    {
+        Err(PaykitError::Unimplemented("remove_contact"))
+    }
+
+    /// Checks that the backing service is reachable, e.g. before a batch of writes.
+    ///
+    /// Failures are reported like those of any other operation, e.g.
+    /// `PaykitError::Unauthorized` for an expired session. The default implementation
+    /// has nothing to probe and always succeeds.
+    async fn health_check(&self) -> Result<()>
+    where
+        Self: Sync,
+    {
+        Ok(())
+    }
+
+    /// The public key this transport writes as.
+    ///
+    /// Lets code holding only a transport read back its own data without carrying the
+    /// key separately. Transports that do not know their identity keep the default,
+    /// which returns `PaykitError::Unimplemented`.
+    fn public_key(&self) -> Result<PublicKey> {
+        Err(PaykitError::Unimplemented("public_key"))
+    }
 }
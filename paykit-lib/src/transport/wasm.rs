@@ -0,0 +1,259 @@
+//! Browser-friendly reader that implements [`crate::UnauthenticatedTransportRead`] over
+//! `fetch`.
+//!
+//! The Pubky SDK resolves homeservers through PKARR and pulls in runtime code that does
+//! not build for `wasm32-unknown-unknown`. This adapter instead issues plain HTTP
+//! requests through `reqwest`, which uses the browser's `fetch` on that target, against a
+//! homeserver the caller already knows. Discovering that homeserver stays the caller's
+//! responsibility.
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use reqwest::{Client, Response};
+
+pub use super::http::PUBKY_HOST_HEADER;
+
+use super::http;
+use super::paths::{
+    contact_key_from_path, entry_name, method_id_from_path, validate_relative_path, PaykitPaths,
+};
+use crate::transport::traits::UnauthenticatedTransportRead;
+use crate::{EndpointData, MethodId, PaykitError, PublicKey, Result, SupportedPayments};
+
+/// Default number of endpoint bodies fetched concurrently by
+/// [`WasmUnauthenticatedTransport::fetch_supported_payments`].
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 8;
+
+/// Default cap on a single endpoint body read by [`WasmUnauthenticatedTransport`]
+/// (64 KiB).
+pub const DEFAULT_MAX_ENDPOINT_BYTES: usize = 64 * 1024;
+
+/// Reader for browser wallets, talking to one homeserver over HTTP.
+///
+/// Every request goes to `{homeserver}{path}` with the payee or owner in the
+/// [`PUBKY_HOST_HEADER`] header, using the same [`PaykitPaths`] layout and follows
+/// directory as the Pubky adapters. Missing resources read as `None` or an empty list.
+///
+/// # Examples
+/// ```no_run
+/// # use paykit_lib::{get_payment_list, WasmUnauthenticatedTransport, PublicKey};
+/// # async fn demo(payee: &PublicKey) -> paykit_lib::Result<()> {
+/// let reader = WasmUnauthenticatedTransport::new("https://homeserver.example.com");
+/// let payments = get_payment_list(&reader, payee).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct WasmUnauthenticatedTransport {
+    client: Client,
+    homeserver: String,
+    paths: PaykitPaths,
+    fetch_concurrency: usize,
+    max_endpoint_bytes: usize,
+}
+
+impl WasmUnauthenticatedTransport {
+    /// Build a reader for `homeserver` (e.g. `https://homeserver.example.com`), reading
+    /// the default `v0` layout.
+    pub fn new(homeserver: impl AsRef<str>) -> Self {
+        Self::new_with_client(Client::new(), homeserver)
+    }
+
+    /// Like [`Self::new`], reusing an existing `reqwest` client.
+    pub fn new_with_client(client: Client, homeserver: impl AsRef<str>) -> Self {
+        Self {
+            client,
+            homeserver: homeserver.as_ref().trim_end_matches('/').to_string(),
+            paths: PaykitPaths::default(),
+            fetch_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            max_endpoint_bytes: DEFAULT_MAX_ENDPOINT_BYTES,
+        }
+    }
+
+    /// Read endpoints from `paths` instead of the default layout.
+    pub fn with_paths(mut self, paths: PaykitPaths) -> Self {
+        self.paths = paths;
+        self
+    }
+
+    /// Cap how many endpoint bodies are fetched at once when listing a payee's
+    /// supported payments (clamped to at least one). Defaults to
+    /// [`DEFAULT_FETCH_CONCURRENCY`].
+    pub fn with_fetch_concurrency(mut self, limit: usize) -> Self {
+        self.fetch_concurrency = limit.max(1);
+        self
+    }
+
+    /// Reject endpoint bodies larger than `limit` bytes with
+    /// `PaykitError::PayloadTooLarge`. Defaults to [`DEFAULT_MAX_ENDPOINT_BYTES`].
+    pub fn with_max_endpoint_bytes(mut self, limit: usize) -> Self {
+        self.max_endpoint_bytes = limit;
+        self
+    }
+
+    /// Base URL requests are sent to, without a trailing slash.
+    pub fn homeserver(&self) -> &str {
+        &self.homeserver
+    }
+
+    /// Endpoint layout this adapter reads from.
+    pub fn paths(&self) -> &PaykitPaths {
+        &self.paths
+    }
+
+    /// Sends a `GET` for `path` on `host`'s storage, mapping 404 and 410 to `None`, 401
+    /// and 403 to `PaykitError::Unauthorized`, and 429 to `PaykitError::RateLimited`
+    /// with the `Retry-After` delay.
+    async fn get(
+        &self,
+        host: &PublicKey,
+        path: &str,
+        query: &[(&str, &str)],
+        label: &str,
+    ) -> Result<Option<Response>> {
+        http::get(&self.client, &self.homeserver, host, path, query, label).await
+    }
+
+    async fn fetch_text(
+        &self,
+        host: &PublicKey,
+        path: &str,
+        label: &str,
+    ) -> Result<Option<String>> {
+        let Some(resp) = self.get(host, path, &[], label).await? else {
+            return Ok(None);
+        };
+
+        let limit = self.max_endpoint_bytes;
+        if let Some(len) = resp.content_length() {
+            let actual = usize::try_from(len).unwrap_or(usize::MAX);
+            if actual > limit {
+                return Err(PaykitError::PayloadTooLarge { limit, actual });
+            }
+        }
+        let bytes = resp
+            .bytes()
+            .await
+            .map_err(|err| crate::network_error(&err))?;
+        if bytes.len() > limit {
+            return Err(PaykitError::PayloadTooLarge {
+                limit,
+                actual: bytes.len(),
+            });
+        }
+
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        let data = String::from_utf8(bytes.to_vec())
+            .map_err(|err| PaykitError::InvalidData(format!("{label} ({path}): {err}")))?;
+        Ok(Some(data))
+    }
+
+    /// Paths of the entries directly under `dir`, one `pubky://<host>/<path>` URL each.
+    async fn list_paths(&self, host: &PublicKey, dir: &str, label: &str) -> Result<Vec<String>> {
+        let Some(resp) = self.get(host, dir, &[("shallow", "true")], label).await? else {
+            return Ok(Vec::new());
+        };
+        let body = resp
+            .text()
+            .await
+            .map_err(|err| crate::network_error(&err))?;
+
+        // Listings are one `pubky://<host>/<path>` URL per line.
+        Ok(body
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl UnauthenticatedTransportRead for WasmUnauthenticatedTransport {
+    async fn fetch_supported_payments(&self, payee: &PublicKey) -> Result<SupportedPayments> {
+        let prefix = self.paths.prefix();
+        let methods = self
+            .list_paths(payee, &prefix, "list supported payments")
+            .await?
+            .iter()
+            .filter_map(|path| method_id_from_path(path))
+            .collect::<Vec<_>>();
+
+        // The first failure aborts the whole listing.
+        let entries = stream::iter(methods)
+            .map(|method| {
+                let path = self.paths.endpoint(&method);
+                async move {
+                    let label = format!("fetch endpoint {method}");
+                    let payload = self.fetch_text(payee, &path, &label).await?;
+                    Ok::<_, PaykitError>(payload.map(|payload| (method, EndpointData(payload))))
+                }
+            })
+            .buffer_unordered(self.fetch_concurrency)
+            .try_filter_map(futures::future::ok)
+            .try_collect()
+            .await?;
+        Ok(SupportedPayments { entries })
+    }
+
+    async fn fetch_payment_endpoint(
+        &self,
+        payee: &PublicKey,
+        method: &MethodId,
+    ) -> Result<Option<EndpointData>> {
+        let label = format!("fetch endpoint {}", method);
+        Ok(self
+            .fetch_text(payee, &self.paths.endpoint(method), &label)
+            .await?
+            .map(EndpointData))
+    }
+
+    async fn fetch_raw(
+        &self,
+        payee: &PublicKey,
+        relative_path: &str,
+    ) -> Result<Option<EndpointData>> {
+        validate_relative_path(relative_path)?;
+        Ok(self
+            .fetch_text(payee, &self.paths.file(relative_path), "fetch raw file")
+            .await?
+            .map(EndpointData))
+    }
+
+    async fn fetch_known_contacts(&self, owner: &PublicKey) -> Result<Vec<PublicKey>> {
+        let (contacts, _skipped) = self.fetch_known_contacts_with_report(owner).await?;
+        Ok(contacts)
+    }
+
+    async fn fetch_known_contacts_with_report(
+        &self,
+        owner: &PublicKey,
+    ) -> Result<(Vec<PublicKey>, Vec<String>)> {
+        let paths = self
+            .list_paths(owner, self.paths.follows(), "list known contacts")
+            .await?;
+
+        let mut contacts = Vec::new();
+        let mut seen = HashSet::new();
+        let mut skipped = Vec::new();
+        for path in &paths {
+            let Some(name) = entry_name(path) else {
+                continue;
+            };
+            match contact_key_from_path(path) {
+                // File names spelling the same key differently yield it once.
+                Some(pk) if seen.insert(pk.clone()) => contacts.push(pk),
+                Some(_) => {}
+                None => skipped.push(name.to_string()),
+            }
+        }
+        contacts.sort_by_cached_key(ToString::to_string);
+        skipped.sort();
+        Ok((contacts, skipped))
+    }
+}
@@ -0,0 +1,67 @@
+//! Core helpers driven without a tokio runtime; run with
+//! `cargo test --no-default-features --features mock --test runtime_agnostic`.
+
+#![cfg(all(feature = "mock", not(feature = "tokio")))]
+
+use std::time::Duration;
+
+use futures::executor::block_on;
+use paykit_lib::cache::CachingRead;
+use paykit_lib::fallback::FallbackRead;
+use paykit_lib::mock::MockTransport;
+use paykit_lib::observe::{NoopObserver, ObservedTransport};
+use paykit_lib::{
+    add_contact, get_known_contacts, get_payment_endpoint, get_payment_list, parse_public_keys,
+    set_payment_endpoint, EndpointData, MethodId, PublicKey,
+};
+
+const PAYEE: &str = "o4dksfbqk85ogzdb5osziw6befigbuxmuxkuxq8434q89uj56uyy";
+const CONTACT: &str = "8pinxxgqs41n4aididenw5apqp1urfmzdztr8jt4abrkdn435ewo";
+
+fn keys() -> (PublicKey, PublicKey) {
+    let (mut keys, rejected) = parse_public_keys(&[PAYEE, CONTACT]);
+    assert!(rejected.is_empty(), "{rejected:?}");
+    let contact = keys.pop().unwrap();
+    (keys.pop().unwrap(), contact)
+}
+
+#[test]
+fn helpers_run_without_tokio() {
+    let (payee, contact) = keys();
+    let mock = MockTransport::new(payee.clone());
+    let lightning = MethodId::new("lightning").unwrap();
+
+    block_on(async {
+        set_payment_endpoint(&mock, lightning.clone(), EndpointData("ln".into()))
+            .await
+            .unwrap();
+        add_contact(&mock, &contact).await.unwrap();
+
+        let list = get_payment_list(&mock, &payee).await.unwrap();
+        assert_eq!(list.get(&lightning), Some(&EndpointData("ln".into())));
+        let contacts = get_known_contacts(&mock, &payee).await.unwrap();
+        assert_eq!(contacts, vec![contact]);
+    });
+}
+
+#[test]
+fn decorators_run_without_tokio() {
+    let (payee, _) = keys();
+    let mock = MockTransport::new(payee.clone());
+    let lightning = MethodId::new("lightning").unwrap();
+    mock.insert_endpoint(&payee, lightning.clone(), EndpointData("ln".into()));
+
+    let cached = CachingRead::new(mock.clone(), Duration::from_secs(60));
+    let observed = ObservedTransport::new(cached, NoopObserver);
+    let reader = FallbackRead::new(vec![Box::new(observed)]);
+
+    block_on(async {
+        for _ in 0..2 {
+            let endpoint = get_payment_endpoint(&reader, &payee, &lightning)
+                .await
+                .unwrap();
+            assert_eq!(endpoint, Some(EndpointData("ln".into())));
+        }
+    });
+    assert_eq!(mock.call_count("fetch_payment_endpoint"), 1);
+}
@@ -0,0 +1,118 @@
+//! Browser reader tests; run with
+//! `cargo test --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm`.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use std::time::Duration;
+
+use paykit_lib::{
+    get_payment_endpoint, get_payment_list, MethodId, PaykitError, PublicKey,
+    WasmUnauthenticatedTransport,
+};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const PAYEE: &str = "o4dksfbqk85ogzdb5osziw6befigbuxmuxkuxq8434q89uj56uyy";
+
+// Stands in for a homeserver: answers only requests addressed to `host`, serving `files`
+// by path and a shallow listing for directories.
+#[wasm_bindgen(inline_js = r#"
+export function mock_homeserver(host, files) {
+    const respond = (request, body, status) => {
+        const response = new Response(body, { status });
+        // Constructed responses have no URL, but reqwest reads it back.
+        Object.defineProperty(response, "url", { value: request.url });
+        return response;
+    };
+    globalThis.fetch = async (request) => {
+        const path = new URL(request.url).pathname;
+        if (request.headers.get("pubky-host") !== host) {
+            return respond(request, "", 404);
+        }
+        if (path.endsWith("/")) {
+            const names = Object.keys(files).filter((file) => file.startsWith(path));
+            if (names.length === 0) {
+                return respond(request, "", 404);
+            }
+            return respond(request, names.map((file) => `pubky://${host}${file}`).join("\n"), 200);
+        }
+        if (path in files) {
+            return respond(request, files[path], 200);
+        }
+        return respond(request, "", 404);
+    };
+}
+
+export function mock_throttled(seconds) {
+    globalThis.fetch = async (request) => {
+        const response = new Response("", { status: 429, headers: { "retry-after": seconds } });
+        Object.defineProperty(response, "url", { value: request.url });
+        return response;
+    };
+}
+"#)]
+extern "C" {
+    fn mock_homeserver(host: &str, files: JsValue);
+    fn mock_throttled(seconds: &str);
+}
+
+fn payee() -> PublicKey {
+    PublicKey::parse(PAYEE).unwrap()
+}
+
+fn serve(files: &[(&str, &str)]) -> WasmUnauthenticatedTransport {
+    let object = js_sys::Object::new();
+    for (path, body) in files {
+        js_sys::Reflect::set(&object, &(*path).into(), &(*body).into()).unwrap();
+    }
+    mock_homeserver(PAYEE, object.into());
+    WasmUnauthenticatedTransport::new("https://homeserver.example.com/")
+}
+
+#[wasm_bindgen_test]
+async fn reads_mocked_endpoint() {
+    let reader = serve(&[
+        ("/pub/paykit.app/v0/lightning", "lnbc1..."),
+        ("/pub/paykit.app/v0/onchain", "bc1q..."),
+    ]);
+
+    let endpoint = get_payment_endpoint(&reader, &payee(), MethodId("lightning".into()))
+        .await
+        .unwrap();
+    assert_eq!(endpoint.unwrap().0, "lnbc1...");
+
+    let list = get_payment_list(&reader, &payee()).await.unwrap();
+    assert_eq!(list.entries.len(), 2);
+    assert_eq!(list.entries[&MethodId("onchain".into())].0, "bc1q...");
+}
+
+#[wasm_bindgen_test]
+async fn missing_endpoint_is_none() {
+    let reader = serve(&[]);
+
+    let endpoint = get_payment_endpoint(&reader, &payee(), MethodId("lightning".into()))
+        .await
+        .unwrap();
+    assert!(endpoint.is_none());
+    assert!(get_payment_list(&reader, &payee())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn throttling_reports_retry_after() {
+    mock_throttled("7");
+    let reader = WasmUnauthenticatedTransport::new("https://homeserver.example.com/");
+
+    let err = get_payment_list(&reader, &payee()).await.unwrap_err();
+    assert!(
+        matches!(
+            err,
+            PaykitError::RateLimited {
+                retry_after: Some(delay)
+            } if delay == Duration::from_secs(7)
+        ),
+        "{err:?}"
+    );
+}